@@ -1,9 +1,13 @@
 //! Build script for Atmosphere Android bindings
-//! 
-//! This generates the UniFFI scaffolding code from the UDL file.
+//!
+//! This generates the UniFFI scaffolding code from the UDL file, plus the
+//! protobuf types for the compact binary mesh framing (see `protocol.rs`).
 
 fn main() {
     // Generate the UniFFI scaffolding from the UDL file
     uniffi::generate_scaffolding("src/atmosphere.udl")
         .expect("Failed to generate UniFFI scaffolding");
+
+    prost_build::compile_protos(&["proto/mesh.proto"], &["proto/"])
+        .expect("failed to compile mesh.proto");
 }
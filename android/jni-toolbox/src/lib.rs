@@ -0,0 +1,302 @@
+//! `#[jni_export]` - collapse the hand-written `Java_com_llamafarm_*` glue
+//!
+//! Every native method in `atmosphere-android` used to be its own
+//! `extern "system" fn`: reconstruct `&AndroidNode` from the raw handle,
+//! convert each `JString`/`JByteArray` argument by hand, call the inherent
+//! method, and convert the result (or throw) by hand again. That
+//! boilerplate was identical in shape across every method and easy to get
+//! subtly wrong (a forgotten null-handle check, a mismatched exception
+//! class).
+//!
+//! `#[jni_export(AndroidNode, class = "AtmosphereNode")]` on an inherent
+//! method
+//!
+//! ```ignore
+//! impl AndroidNode {
+//!     #[jni_export(AndroidNode, class = "AtmosphereNode")]
+//!     pub fn connect_to_peer(&self, address: String) -> Result<(), String> {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! leaves the method itself untouched and additionally emits
+//! `Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeConnectToPeer`:
+//! it reconstructs `&AndroidNode` from the `jlong` handle (throwing
+//! `IllegalStateException` on a null handle, same as the hand-written
+//! functions did), converts each argument from its JNI type, calls through,
+//! and on `Err` throws `IllegalStateException` with the error's `Display`
+//! text rather than returning a sentinel the Kotlin side has to remember to
+//! check.
+//!
+//! Only the argument/return types actually used by `AndroidNode`'s methods
+//! are supported: arguments of type `String`, `bool`, `i32`, `i64` or
+//! `Vec<u8>`, and a return type of `Result<T, E>` with `T` one of `()`,
+//! `String`, `bool`, `i32`, `i64` or `u64`; a method returning anything other
+//! than a `Result` is rejected at compile time - this isn't a general-purpose JNI
+//! marshalling layer, just enough to stop re-deriving the same plumbing by
+//! hand for every new native method.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, FnArg, Ident, ItemFn, LitStr, Pat, ReturnType, Token, Type,
+};
+
+/// The package every generated `Java_...` function lives under; fixed
+/// because `atmosphere-android` only ever binds into one Kotlin package.
+const JNI_PACKAGE: &str = "com_llamafarm_atmosphere_bindings";
+
+struct JniExportArgs {
+    self_ty: Ident,
+    class: String,
+    /// Overrides the Java-side method name derived from the Rust method
+    /// name, for the handful of native entry points whose name doesn't
+    /// match the Rust method 1:1 (e.g. a `_json`-suffixed Rust helper
+    /// bound to a native method that predates the suffix).
+    name: Option<String>,
+}
+
+impl Parse for JniExportArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let self_ty: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let class_kw: Ident = input.parse()?;
+        if class_kw != "class" {
+            return Err(syn::Error::new(class_kw.span(), "expected `class = \"JavaClassName\"`"));
+        }
+        input.parse::<Token![=]>()?;
+        let class_lit: LitStr = input.parse()?;
+
+        let mut name = None;
+        if input.parse::<Token![,]>().is_ok() {
+            let name_kw: Ident = input.parse()?;
+            if name_kw != "name" {
+                return Err(syn::Error::new(name_kw.span(), "expected `name = \"NativeMethodName\"`"));
+            }
+            input.parse::<Token![=]>()?;
+            let name_lit: LitStr = input.parse()?;
+            name = Some(name_lit.value());
+        }
+
+        Ok(JniExportArgs { self_ty, class: class_lit.value(), name })
+    }
+}
+
+#[proc_macro_attribute]
+pub fn jni_export(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as JniExportArgs);
+    let method = parse_macro_input!(item as ItemFn);
+
+    match expand(&args, &method) {
+        Ok(generated) => {
+            let mut out = proc_macro2::TokenStream::new();
+            out.extend(quote! { #method });
+            out.extend(generated);
+            out.into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(args: &JniExportArgs, method: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let self_ty = &args.self_ty;
+    let native_name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| pascal_case(&method.sig.ident.to_string()));
+    let jni_fn_name = format_ident!("Java_{}_{}_native{}", JNI_PACKAGE, args.class, native_name);
+    let method_name = &method.sig.ident;
+
+    let mut jni_arg_names = Vec::new();
+    let mut jni_arg_types = Vec::new();
+    let mut convert_stmts = Vec::new();
+    let mut locals = Vec::new();
+    let mut needs_env = false;
+    let (ret_jni_ty, ok_conversion, default_ret) = return_kind(&method.sig.output)?;
+
+    for input in method.sig.inputs.iter() {
+        let FnArg::Typed(pat_type) = input else { continue };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            return Err(syn::Error::new_spanned(pat_type, "jni_export requires named arguments"));
+        };
+        let name = &pat_ident.ident;
+        let jni_name = format_ident!("jni_{}", name);
+        let local = format_ident!("arg_{}", name);
+
+        let (jni_ty, stmt, is_jni_type) = match arg_kind(&pat_type.ty)? {
+            ArgKind::String => (
+                quote! { jni::objects::JString },
+                quote! {
+                    let #local: String = match env.get_string(&#jni_name) {
+                        Ok(s) => String::from(s),
+                        Err(_) => {
+                            let _ = env.throw_new("java/lang/IllegalArgumentException", "invalid UTF-8 string argument");
+                            return #default_ret;
+                        }
+                    };
+                },
+                true,
+            ),
+            ArgKind::Bytes => (
+                quote! { jni::objects::JByteArray },
+                quote! {
+                    let #local: Vec<u8> = match env.convert_byte_array(&#jni_name) {
+                        Ok(b) => b,
+                        Err(_) => {
+                            let _ = env.throw_new("java/lang/IllegalArgumentException", "invalid byte array argument");
+                            return #default_ret;
+                        }
+                    };
+                },
+                true,
+            ),
+            ArgKind::Bool => (quote! { jni::sys::jboolean }, quote! { let #local: bool = #jni_name != 0; }, false),
+            ArgKind::I32 => (quote! { jni::sys::jint }, quote! { let #local: i32 = #jni_name; }, false),
+            ArgKind::I64 => (quote! { jni::sys::jlong }, quote! { let #local: i64 = #jni_name; }, false),
+        };
+
+        jni_arg_names.push(jni_name);
+        jni_arg_types.push(jni_ty);
+        convert_stmts.push(stmt);
+        locals.push(local);
+        needs_env |= is_jni_type;
+    }
+
+    let env_mut = if needs_env { quote! { mut env: jni::JNIEnv } } else { quote! { env: jni::JNIEnv } };
+
+    Ok(quote! {
+        #[no_mangle]
+        pub extern "system" fn #jni_fn_name(
+            #env_mut,
+            _obj: jni::objects::JObject,
+            handle: jni::sys::jlong,
+            #(#jni_arg_names: #jni_arg_types),*
+        ) -> #ret_jni_ty {
+            #[allow(unused_mut)]
+            let mut env = env;
+            if handle == 0 {
+                let _ = env.throw_new("java/lang/IllegalStateException", "Invalid handle");
+                return #default_ret;
+            }
+            let node: &#self_ty = unsafe { &*(handle as *mut #self_ty) };
+
+            #(#convert_stmts)*
+
+            match node.#method_name(#(#locals),*) {
+                Ok(value) => { #ok_conversion }
+                Err(e) => {
+                    let _ = env.throw_new("java/lang/IllegalStateException", e.to_string());
+                    #default_ret
+                }
+            }
+        }
+    })
+}
+
+enum ArgKind {
+    String,
+    Bytes,
+    Bool,
+    I32,
+    I64,
+}
+
+fn arg_kind(ty: &Type) -> syn::Result<ArgKind> {
+    let Type::Path(type_path) = ty else {
+        return Err(syn::Error::new_spanned(ty, "unsupported jni_export argument type"));
+    };
+    let segment = type_path.path.segments.last().unwrap();
+    match segment.ident.to_string().as_str() {
+        "String" => Ok(ArgKind::String),
+        "bool" => Ok(ArgKind::Bool),
+        "i32" => Ok(ArgKind::I32),
+        "i64" | "u64" => Ok(ArgKind::I64),
+        "Vec" => Ok(ArgKind::Bytes),
+        other => Err(syn::Error::new_spanned(ty, format!("unsupported jni_export argument type `{}`", other))),
+    }
+}
+
+/// What the generated function returns for the `Ok` arm of the method's
+/// `Result<T, E>`: the JNI return type, the expression converting a
+/// successful `T` into it, and the value returned on a thrown exception
+/// (Java ignores it once an exception is pending, but the native function
+/// still has to return something of the right type).
+fn return_kind(output: &ReturnType) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let ty = match output {
+        ReturnType::Default => return Ok((quote! { () }, quote! {}, quote! {})),
+        ReturnType::Type(_, ty) => ty.as_ref(),
+    };
+
+    let Type::Path(type_path) = ty else {
+        return Err(syn::Error::new_spanned(ty, "unsupported jni_export return type"));
+    };
+    let segment = type_path.path.segments.last().unwrap();
+    if segment.ident != "Result" {
+        return Err(syn::Error::new_spanned(ty, "jni_export methods must return Result<_, E>"));
+    }
+    let syn::PathArguments::AngleBracketed(generics) = &segment.arguments else {
+        return Err(syn::Error::new_spanned(ty, "Result must be written out as Result<T, E>"));
+    };
+    let syn::GenericArgument::Type(ok_ty) = generics.args.first().unwrap() else {
+        return Err(syn::Error::new_spanned(ty, "Result must be written out as Result<T, E>"));
+    };
+
+    match ok_ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => {
+            Ok((quote! { () }, quote! { return; }, quote! { return; }))
+        }
+        Type::Path(p) => match p.path.segments.last().unwrap().ident.to_string().as_str() {
+            "String" => Ok((
+                quote! { jni::sys::jstring },
+                quote! { return env.new_string(value).map(|s| s.into_raw()).unwrap_or(std::ptr::null_mut()); },
+                quote! { std::ptr::null_mut() },
+            )),
+            "bool" => Ok((quote! { jni::sys::jboolean }, quote! { return value as jni::sys::jboolean; }, quote! { false as jni::sys::jboolean })),
+            "i32" => Ok((quote! { jni::sys::jint }, quote! { return value; }, quote! { -1 })),
+            "i64" | "u64" => Ok((quote! { jni::sys::jlong }, quote! { return value as jni::sys::jlong; }, quote! { -1 })),
+            other => Err(syn::Error::new_spanned(ok_ty, format!("unsupported jni_export Ok type `{}`", other))),
+        },
+        _ => Err(syn::Error::new_spanned(ok_ty, "unsupported jni_export Ok type")),
+    }
+}
+
+/// `register_capability` -> `RegisterCapability`, so it can be spliced into
+/// `nativeRegisterCapability`. `AndroidNode`'s methods are already
+/// `snake_case`, same as every other Rust method name, so this is the only
+/// translation the macro needs to derive the Java-side name.
+fn pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pascal_case_single_word() {
+        assert_eq!(pascal_case("start"), "Start");
+    }
+
+    #[test]
+    fn pascal_case_multiple_words() {
+        assert_eq!(pascal_case("register_capability"), "RegisterCapability");
+        assert_eq!(pascal_case("connect_to_peer"), "ConnectToPeer");
+    }
+
+    #[test]
+    fn pascal_case_skips_empty_segments() {
+        assert_eq!(pascal_case("is__running"), "IsRunning");
+    }
+}
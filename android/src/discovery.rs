@@ -0,0 +1,220 @@
+//! mDNS/DNS-SD LAN discovery, used as an offline fallback to the mesh server
+//!
+//! `discover_peers` only ever asks the mesh coordinator for its peer list,
+//! so two devices on the same Wi-Fi have no way to find each other when the
+//! coordinator is unreachable - or before one has ever been reached at all.
+//! This advertises the node over mDNS/DNS-SD under `_atmosphere._udp`, with
+//! a TXT record carrying its `node_id`, display name, listen address, and a
+//! comma-joined capability list, while simultaneously browsing for other
+//! `_atmosphere` instances. Resolved peers are merged into
+//! `AndroidNode.peers` tagged `PeerSource::Lan`, which both seeds the
+//! routing table before any coordinator is reached and lets the UI tell LAN
+//! peers apart from mesh ones.
+//!
+//! mDNS gives no guarantee a peer announces before it drops off the
+//! network, so a peer that stops being re-resolved is swept out of
+//! `AndroidNode.peers` after `PEER_TTL` rather than lingering forever.
+
+use crate::{Peer, PeerSource, PeerState};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::HashMap;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SERVICE_TYPE: &str = "_atmosphere._udp.local.";
+const TXT_NODE_ID: &str = "node_id";
+const TXT_NAME: &str = "name";
+const TXT_ADDRESS: &str = "address";
+const TXT_CAPS: &str = "caps";
+
+/// How long a LAN peer is kept after its last mDNS resolution before it's
+/// swept out of `AndroidNode.peers` as stale.
+const PEER_TTL: Duration = Duration::from_secs(30);
+
+/// How often the background worker checks for peers past `PEER_TTL`.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Owns the mDNS daemon and the background worker that keeps LAN peers in
+/// `AndroidNode.peers` in sync with what's actually still advertising.
+pub struct LocalDiscovery {
+    daemon: Option<ServiceDaemon>,
+    running: Arc<AtomicBool>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl LocalDiscovery {
+    pub fn new() -> Self {
+        Self {
+            daemon: None,
+            running: Arc::new(AtomicBool::new(false)),
+            worker: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Advertise this node over mDNS and start browsing for peers,
+    /// merging/expiring matches into `peers` until `stop()` is called.
+    pub fn start(
+        &mut self,
+        node_id: String,
+        name: String,
+        port: u16,
+        capabilities: Vec<String>,
+        peers: Arc<RwLock<Vec<Peer>>>,
+    ) -> Result<(), String> {
+        if self.is_running() {
+            return Ok(());
+        }
+
+        let daemon = ServiceDaemon::new().map_err(|e| format!("mDNS daemon failed: {}", e))?;
+
+        let host_ip = local_ipv4().unwrap_or_else(|| "0.0.0.0".to_string());
+        let address = format!("{}:{}", host_ip, port);
+
+        let mut properties = HashMap::new();
+        properties.insert(TXT_NODE_ID.to_string(), node_id.clone());
+        properties.insert(TXT_NAME.to_string(), name.clone());
+        properties.insert(TXT_ADDRESS.to_string(), address);
+        properties.insert(TXT_CAPS.to_string(), capabilities.join(","));
+
+        let service_info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &node_id,
+            &format!("{}.local.", node_id),
+            host_ip.as_str(),
+            port,
+            properties,
+        )
+        .map_err(|e| format!("Invalid mDNS service info: {}", e))?;
+
+        daemon
+            .register(service_info)
+            .map_err(|e| format!("mDNS registration failed: {}", e))?;
+
+        let receiver = daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| format!("mDNS browse failed: {}", e))?;
+
+        self.running.store(true, Ordering::Relaxed);
+        let running = self.running.clone();
+
+        self.worker = Some(thread::spawn(move || {
+            let mut last_seen: HashMap<String, Instant> = HashMap::new();
+            let mut last_sweep = Instant::now();
+
+            while running.load(Ordering::Relaxed) {
+                if let Ok(event) = receiver.recv_timeout(SWEEP_INTERVAL) {
+                    match event {
+                        ServiceEvent::ServiceResolved(info) => {
+                            if let Some(peer) = peer_from_service_info(&info) {
+                                last_seen.insert(peer.node_id.clone(), Instant::now());
+                                upsert_lan_peer(&peers, peer);
+                            }
+                        }
+                        ServiceEvent::ServiceRemoved(_, fullname) => {
+                            if let Some(removed_id) = node_id_from_fullname(&fullname) {
+                                last_seen.remove(&removed_id);
+                                remove_lan_peer(&peers, &removed_id);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                if last_sweep.elapsed() >= SWEEP_INTERVAL {
+                    let stale: Vec<String> = last_seen
+                        .iter()
+                        .filter(|(_, seen)| seen.elapsed() >= PEER_TTL)
+                        .map(|(id, _)| id.clone())
+                        .collect();
+                    for stale_id in stale {
+                        last_seen.remove(&stale_id);
+                        remove_lan_peer(&peers, &stale_id);
+                    }
+                    last_sweep = Instant::now();
+                }
+            }
+        }));
+
+        self.daemon = Some(daemon);
+        Ok(())
+    }
+
+    /// Stop advertising and browsing, and drop any LAN peers this node
+    /// contributed from `AndroidNode.peers`.
+    pub fn stop(&mut self, peers: &Arc<RwLock<Vec<Peer>>>) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.worker.take() {
+            let _ = handle.join();
+        }
+        if let Some(daemon) = self.daemon.take() {
+            let _ = daemon.shutdown();
+        }
+        peers.write().unwrap().retain(|p| p.source != PeerSource::Lan);
+    }
+}
+
+fn peer_from_service_info(info: &ServiceInfo) -> Option<Peer> {
+    let props = info.get_properties();
+    let node_id = props.get(TXT_NODE_ID)?.to_string();
+    let name = props
+        .get(TXT_NAME)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Unknown Node".to_string());
+    let address = props
+        .get(TXT_ADDRESS)
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+    let capabilities = props
+        .get(TXT_CAPS)
+        .map(|s| s.split(',').filter(|s| !s.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    Some(Peer {
+        node_id,
+        name,
+        address,
+        connected: true,
+        latency_ms: None,
+        capabilities,
+        source: PeerSource::Lan,
+        state: PeerState::Connected,
+        features: 0,
+    })
+}
+
+/// mDNS reports removals by the service's full instance name
+/// (`<node_id>._atmosphere._udp.local.`), so pull the `node_id` back out of it.
+fn node_id_from_fullname(fullname: &str) -> Option<String> {
+    fullname.split('.').next().map(str::to_string)
+}
+
+fn upsert_lan_peer(peers: &Arc<RwLock<Vec<Peer>>>, peer: Peer) {
+    let mut peers = peers.write().unwrap();
+    match peers.iter_mut().find(|p| p.node_id == peer.node_id) {
+        Some(existing) => *existing = peer,
+        None => peers.push(peer),
+    }
+}
+
+fn remove_lan_peer(peers: &Arc<RwLock<Vec<Peer>>>, node_id: &str) {
+    peers
+        .write()
+        .unwrap()
+        .retain(|p| !(p.source == PeerSource::Lan && p.node_id == node_id));
+}
+
+/// Best-effort local IPv4 address to advertise, found by opening a UDP
+/// socket toward a public address without sending anything - this never
+/// touches the network, it just asks the OS which interface would be used.
+fn local_ipv4() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip().to_string())
+}
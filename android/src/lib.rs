@@ -3,49 +3,92 @@
 //! Native JNI interface for the Atmosphere mesh network core library.
 //! Provides the bridge between Kotlin and the Rust implementation.
 
-use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_long};
-use std::sync::{Arc, RwLock};
+mod discovery;
+mod listener;
+mod looper;
+mod noise;
+mod peer_conn;
+mod protocol;
+mod routing;
+mod streaming;
+mod wire_pb;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, RwLock};
 use std::ptr;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
 use atmosphere_core::{NodeId, Capability, CapabilityRegistry};
+use discovery::LocalDiscovery;
+use jni::objects::{JByteArray, JClass, JObject, JString};
+use jni::sys::{jboolean, jint, jlong, jstring};
+use jni::JNIEnv;
+use jni_toolbox::jni_export;
+use listener::MeshListener;
+use looper::LooperSink;
+use noise::{KnownHosts, NoiseIdentity, NoiseSession};
+use peer_conn::{PeerConnShared, PeerConnectionManager};
+use protocol::{MeshMessage, Peer, PeerSource, PeerState, StatusReport, WireFormat};
+use routing::RoutingTable;
+use streaming::{StreamId, StreamManager};
 use tungstenite::{connect, Message, WebSocket};
 use tungstenite::stream::MaybeTlsStream;
 use url::Url;
 use parking_lot::Mutex;
+use uuid::Uuid;
+
+/// How long the reader thread blocks on a single socket read before
+/// checking for queued outbound writes and the shutdown flag.
+const READER_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How many times a dropped connection is retried (with a fixed backoff)
+/// before the reader thread gives up and reports itself disconnected.
+const RECONNECT_ATTEMPTS: u32 = 3;
+const RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Port advertised in this node's mDNS TXT record for LAN discovery.
+const LOCAL_DISCOVERY_PORT: u16 = 7420;
+
+/// How long `route_intent_json` waits for a remote capability call before
+/// giving up on it.
+const DEFAULT_RPC_TIMEOUT_MS: u64 = 5_000;
 
 // Re-export core types for external use
 pub use atmosphere_core;
 
 // ============================================================================
-// Peer Structure
+// Mesh Events
 // ============================================================================
 
-#[derive(Clone, Debug)]
-pub struct Peer {
-    pub node_id: String,
-    pub name: String,
-    pub address: String,
-    pub connected: bool,
-    pub latency_ms: Option<u32>,
-    pub capabilities: Vec<String>,
+/// A server-pushed frame buffered for the Kotlin side to drain via
+/// `nativePollEvents`. Peer churn also mutates `AndroidNode.peers` directly
+/// so `nativeGetPeers` stays current without requiring the event to be
+/// polled first.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MeshEvent {
+    PeerJoined { peer: Peer },
+    PeerLeft { node_id: String },
+    Gossip { from: String, payload: String },
+    Disconnected { reason: String },
+    /// A `send_stream` transfer from `from` finished reassembling; `data`
+    /// is base64-encoded, same as `MeshMessage::Stream` over JSON.
+    StreamComplete {
+        id: StreamId,
+        from: String,
+        #[serde(serialize_with = "protocol::b64::serialize")]
+        data: Vec<u8>,
+    },
+    /// An inbound stream was given up on - its `fin` chunk never arrived
+    /// within the receive timeout.
+    StreamError { id: StreamId, from: String, reason: String },
 }
 
-impl Peer {
+impl MeshEvent {
     fn to_json(&self) -> String {
-        let caps_json: Vec<String> = self.capabilities.iter()
-            .map(|c| format!("\"{}\"", c))
-            .collect();
-        format!(
-            r#"{{"node_id":"{}","name":"{}","address":"{}","connected":{},"latency_ms":{},"capabilities":[{}]}}"#,
-            self.node_id,
-            self.name,
-            self.address,
-            self.connected,
-            self.latency_ms.map(|l| l.to_string()).unwrap_or("null".to_string()),
-            caps_json.join(",")
-        )
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
     }
 }
 
@@ -58,8 +101,17 @@ pub struct MeshConnection {
     token: String,
     mesh_id: Option<String>,
     mesh_name: Option<String>,
-    ws: Option<WebSocket<MaybeTlsStream<TcpStream>>>,
-    connected: bool,
+    /// Outbound frames are handed to the reader/writer thread through this
+    /// channel rather than written synchronously, so `send_gossip` and
+    /// `discover_peers` never block on (or steal a frame from) the
+    /// connection's single receive loop. Boxed in a slot because the
+    /// reader thread swaps in a fresh sender on every reconnect.
+    writer: Arc<Mutex<Option<mpsc::Sender<MeshMessage>>>>,
+    /// Shared with the background thread so both sides agree on whether the
+    /// connection is up; the thread clears it on a fatal read error and
+    /// `disconnect_mesh` clears it to ask the thread to stop.
+    connected: Arc<AtomicBool>,
+    reader_thread: Option<thread::JoinHandle<()>>,
 }
 
 impl MeshConnection {
@@ -69,12 +121,382 @@ impl MeshConnection {
             token: String::new(),
             mesh_id: None,
             mesh_name: None,
-            ws: None,
-            connected: false,
+            writer: Arc::new(Mutex::new(None)),
+            connected: Arc::new(AtomicBool::new(false)),
+            reader_thread: None,
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
+
+/// In-flight request/response calls keyed by a monotonically increasing
+/// `request_id`. `call_remote` registers a slot before sending its
+/// `"request"` frame and blocks on it; the reader thread resolves the slot
+/// when the matching `"response"` frame arrives, and `call_remote` itself
+/// tears it down if the per-request timeout fires first.
+struct RpcTable {
+    next_id: std::sync::atomic::AtomicU64,
+    pending: Mutex<HashMap<u64, mpsc::Sender<serde_json::Value>>>,
+}
+
+impl RpcTable {
+    fn new() -> Self {
+        Self {
+            next_id: std::sync::atomic::AtomicU64::new(1),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn next_request_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Register a slot for `id`, returning the receiving half the caller
+    /// blocks on until the response arrives or it gives up and cancels.
+    fn register(&self, id: u64) -> mpsc::Receiver<serde_json::Value> {
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().insert(id, tx);
+        rx
+    }
+
+    /// Resolve the slot for `id` with an inbound `"response"` frame. A
+    /// miss - the id is unknown, or its caller already timed out - is
+    /// simply dropped.
+    fn complete(&self, id: u64, response: serde_json::Value) {
+        if let Some(tx) = self.pending.lock().remove(&id) {
+            let _ = tx.send(response);
+        }
+    }
+
+    /// Drop a slot the caller is no longer waiting on, e.g. after its
+    /// timeout fires.
+    fn cancel(&self, id: u64) {
+        self.pending.lock().remove(&id);
+    }
+}
+
+/// State the background reader thread needs direct access to, shared with
+/// the owning `AndroidNode` so inbound frames can be applied without a
+/// round-trip back through JNI.
+struct MeshShared {
+    peers: Arc<RwLock<Vec<Peer>>>,
+    events: Arc<Mutex<VecDeque<MeshEvent>>>,
+    connected: Arc<AtomicBool>,
+    routing: Arc<RwLock<RoutingTable>>,
+    rpc: Arc<RpcTable>,
+    streams: Arc<StreamManager>,
+    /// Registered via `nativeRegisterListener`; `None` until a Kotlin
+    /// caller opts in, so the default remains poll-only via
+    /// `nativePollEvents`.
+    listener: Arc<Mutex<Option<MeshListener>>>,
+    /// Bound via `nativeBindLooper`; `None` until a Kotlin caller opts in,
+    /// in which case events are additionally posted onto that `Looper`
+    /// rather than only delivered synchronously through `listener`.
+    looper: Arc<Mutex<Option<LooperSink>>>,
+}
+
+/// Best-effort parse of a peer's self-reported `node_id` string into the
+/// id space the routing table is keyed on. Peers that don't report a valid
+/// UUID (e.g. a stub/test coordinator) are simply never routed to.
+fn parse_routing_id(node_id: &str) -> Option<NodeId> {
+    Uuid::parse_str(node_id).ok().map(NodeId::from_uuid)
+}
+
+/// Everything needed to redo the `join` handshake against the same
+/// coordinator after the connection drops.
+struct ReconnectInfo {
+    endpoint: String,
+    token: String,
+    node_id: String,
+    capabilities: Vec<String>,
+    identity: Arc<NoiseIdentity>,
+    known_hosts: Arc<Mutex<KnownHosts>>,
+    wire_format: WireFormat,
+}
+
+fn set_read_timeout(stream: &MaybeTlsStream<TcpStream>, timeout: Option<Duration>) {
+    if let MaybeTlsStream::Plain(tcp) = stream {
+        let _ = tcp.set_read_timeout(timeout);
+    }
+}
+
+/// Encode `msg` per `format` and send it as an encrypted binary WebSocket
+/// message.
+fn send_encrypted(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    session: &mut NoiseSession,
+    format: WireFormat,
+    msg: &MeshMessage,
+) -> Result<(), String> {
+    let bytes = protocol::encode_message(msg, format)?;
+    let ciphertext = session.encrypt(&bytes).map_err(|e| e.to_string())?;
+    socket
+        .send(Message::Binary(ciphertext))
+        .map_err(|e| format!("Failed to send frame: {}", e))
+}
+
+/// Read one encrypted binary WebSocket message, decrypt it, and decode it
+/// per `format`.
+fn recv_encrypted(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    session: &mut NoiseSession,
+    format: WireFormat,
+) -> Result<MeshMessage, String> {
+    match socket.read().map_err(|e| format!("Failed to read frame: {}", e))? {
+        Message::Binary(ciphertext) => {
+            let plaintext = session.decrypt(&ciphertext).map_err(|e| e.to_string())?;
+            protocol::decode_message(&plaintext, format)
+        }
+        _ => Err("Expected an encrypted binary frame".to_string()),
+    }
+}
+
+/// Connect to the coordinator, run the Noise XX handshake, and perform the
+/// `join` handshake over the resulting encrypted session, returning the
+/// socket and transport state once the coordinator has acknowledged
+/// membership. Every frame from here on - including `join` itself - travels
+/// as an encrypted binary frame; nothing but the three handshake messages is
+/// ever sent in the clear.
+fn handshake(
+    info: &ReconnectInfo,
+) -> Result<(WebSocket<MaybeTlsStream<TcpStream>>, NoiseSession), String> {
+    let ws_url = if info.endpoint.starts_with("ws://") || info.endpoint.starts_with("wss://") {
+        info.endpoint.clone()
+    } else if info.endpoint.starts_with("http://") {
+        info.endpoint.replace("http://", "ws://")
+    } else if info.endpoint.starts_with("https://") {
+        info.endpoint.replace("https://", "wss://")
+    } else {
+        format!("ws://{}", info.endpoint)
+    };
+
+    let ws_url = if ws_url.contains("/api/ws") {
+        ws_url
+    } else {
+        format!("{}/api/ws", ws_url.trim_end_matches('/'))
+    };
+
+    let url = Url::parse(&ws_url).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    let (mut socket, _response): (WebSocket<MaybeTlsStream<TcpStream>>, _) = connect(url)
+        .map_err(|e| format!("WebSocket connection failed: {}", e))?;
+
+    let (mut session, remote_key) = noise::run_xx_handshake(&mut socket, &info.identity)
+        .map_err(|e| format!("Noise handshake failed: {}", e))?;
+
+    info.known_hosts
+        .lock()
+        .verify_or_pin(&info.endpoint, remote_key)
+        .map_err(|e| e.to_string())?;
+
+    let join_msg = MeshMessage::Join {
+        token: info.token.clone(),
+        node_id: info.node_id.clone(),
+        capabilities: info.capabilities.clone(),
+    };
+
+    send_encrypted(&mut socket, &mut session, info.wire_format, &join_msg)?;
+    let resp = recv_encrypted(&mut socket, &mut session, info.wire_format)?;
+
+    match resp {
+        MeshMessage::Joined { .. } | MeshMessage::Welcome { .. } => Ok((socket, session)),
+        MeshMessage::Error { message } => Err(message),
+        _ => Err("Unexpected response from server".to_string()),
+    }
+}
+
+/// Apply one decoded server frame: peer-list snapshots and churn mutate
+/// `shared.peers` directly, gossip and connection-state changes are
+/// buffered as events for `nativePollEvents`, and `"response"` frames wake
+/// whichever `call_remote` is waiting on that `id` in `shared.rpc`. A frame
+/// that doesn't decode as a `MeshMessage` is simply dropped.
+fn dispatch_frame(
+    bytes: &[u8],
+    format: WireFormat,
+    writer: &Arc<Mutex<Option<mpsc::Sender<MeshMessage>>>>,
+    shared: &MeshShared,
+) {
+    let msg = match protocol::decode_message(bytes, format) {
+        Ok(msg) => msg,
+        Err(_) => return,
+    };
+
+    match msg {
+        MeshMessage::Peers { peers } => {
+            let mut routing = shared.routing.write().unwrap();
+            for peer in &peers {
+                if let Some(id) = parse_routing_id(&peer.node_id) {
+                    routing.insert(id, peer.address.clone());
+                }
+            }
+            drop(routing);
+            *shared.peers.write().unwrap() = peers;
+        }
+        MeshMessage::PeerJoined { peer } => {
+            if let Some(id) = parse_routing_id(&peer.node_id) {
+                shared.routing.write().unwrap().insert(id, peer.address.clone());
+            }
+            shared.peers.write().unwrap().push(peer.clone());
+            if let Some(listener) = shared.listener.lock().as_ref() {
+                listener.notify_peer_connected(&peer.node_id);
+            }
+            let event = MeshEvent::PeerJoined { peer };
+            if let Some(looper) = shared.looper.lock().as_ref() {
+                looper.post_event(&event);
+            }
+            shared.events.lock().push_back(event);
+        }
+        MeshMessage::PeerLeft { node_id } => {
+            if let Some(id) = parse_routing_id(&node_id) {
+                shared.routing.write().unwrap().remove(&id);
+            }
+            shared.peers.write().unwrap().retain(|p| p.node_id != node_id);
+            if let Some(listener) = shared.listener.lock().as_ref() {
+                listener.notify_peer_disconnected(&node_id);
+            }
+            let event = MeshEvent::PeerLeft { node_id };
+            if let Some(looper) = shared.looper.lock().as_ref() {
+                looper.post_event(&event);
+            }
+            shared.events.lock().push_back(event);
+        }
+        MeshMessage::Gossip { from, payload } => {
+            if let Some(listener) = shared.listener.lock().as_ref() {
+                listener.notify_message(&from, payload.as_bytes());
+            }
+            let event = MeshEvent::Gossip { from, payload };
+            if let Some(looper) = shared.looper.lock().as_ref() {
+                looper.post_event(&event);
+            }
+            shared.events.lock().push_back(event);
+        }
+        MeshMessage::Response { id, payload } => {
+            shared.rpc.complete(id, serde_json::json!({ "type": "response", "id": id, "payload": payload }));
+        }
+        MeshMessage::Error { message } => {
+            shared.events.lock().push_back(MeshEvent::Disconnected { reason: message });
+        }
+        MeshMessage::Stream { id, seq, from, to: _, data, fin } => {
+            let (ack_seq, complete) = shared.streams.receive_chunk(id, seq, from.clone(), data, fin);
+            if let Some(tx) = writer.lock().as_ref() {
+                let _ = tx.send(MeshMessage::StreamAck { id, seq: ack_seq });
+            }
+            if let Some(data) = complete {
+                shared.events.lock().push_back(MeshEvent::StreamComplete { id, from, data });
+            }
+        }
+        MeshMessage::StreamAck { id, seq } => {
+            shared.streams.ack(id, seq);
+        }
+        MeshMessage::Join { .. }
+        | MeshMessage::Joined { .. }
+        | MeshMessage::Welcome { .. }
+        | MeshMessage::Discover { .. }
+        | MeshMessage::Request { .. } => {
+            // Not expected from the coordinator once the session is
+            // established; nothing to apply.
+        }
+    }
+}
+
+/// Owns the `WebSocket` for the lifetime of the connection: drains queued
+/// outbound writes, dispatches inbound frames, and reconnects with a fixed
+/// backoff when the socket errors out or the coordinator closes it. Every
+/// frame crossing the socket is encrypted/decrypted through `session`, the
+/// transport state produced by the Noise handshake in `handshake()`.
+fn run_reader_loop(
+    mut socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    mut session: NoiseSession,
+    mut rx: mpsc::Receiver<MeshMessage>,
+    writer_slot: Arc<Mutex<Option<mpsc::Sender<MeshMessage>>>>,
+    shared: MeshShared,
+    reconnect: ReconnectInfo,
+) {
+    loop {
+        if !shared.connected.load(Ordering::Relaxed) {
+            let _ = socket.close(None);
+            return;
+        }
+
+        for (id, from) in shared.streams.sweep_timeouts() {
+            shared.events.lock().push_back(MeshEvent::StreamError {
+                id,
+                from,
+                reason: "stream timed out waiting for the final chunk".to_string(),
+            });
+        }
+
+        while let Ok(msg) = rx.try_recv() {
+            if send_encrypted(&mut socket, &mut session, reconnect.wire_format, &msg).is_err() {
+                break;
+            }
+        }
+
+        set_read_timeout(socket.get_ref(), Some(READER_POLL_INTERVAL));
+        match socket.read() {
+            Ok(Message::Binary(ciphertext)) => match session.decrypt(&ciphertext) {
+                Ok(plaintext) => dispatch_frame(&plaintext, reconnect.wire_format, &writer_slot, &shared),
+                Err(_) => {
+                    if !try_reconnect(&mut socket, &mut session, &mut rx, &writer_slot, &shared, &reconnect) {
+                        return;
+                    }
+                }
+            },
+            Ok(Message::Close(_)) => {
+                if !try_reconnect(&mut socket, &mut session, &mut rx, &writer_slot, &shared, &reconnect) {
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(ref e)) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(_) => {
+                if !try_reconnect(&mut socket, &mut session, &mut rx, &writer_slot, &shared, &reconnect) {
+                    return;
+                }
+            }
         }
     }
 }
 
+/// Attempts `RECONNECT_ATTEMPTS` handshakes with `RECONNECT_BACKOFF` between
+/// them, swapping in the new socket/session/channel on success. Returns
+/// `false` once retries are exhausted, at which point the caller gives up on
+/// the thread.
+fn try_reconnect(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    session: &mut NoiseSession,
+    rx: &mut mpsc::Receiver<MeshMessage>,
+    writer_slot: &Arc<Mutex<Option<mpsc::Sender<MeshMessage>>>>,
+    shared: &MeshShared,
+    reconnect: &ReconnectInfo,
+) -> bool {
+    shared.connected.store(false, Ordering::Relaxed);
+    shared.events.lock().push_back(MeshEvent::Disconnected {
+        reason: "mesh connection lost, reconnecting".to_string(),
+    });
+
+    for _ in 0..RECONNECT_ATTEMPTS {
+        thread::sleep(RECONNECT_BACKOFF);
+        if let Ok((new_socket, new_session)) = handshake(reconnect) {
+            let (tx, new_rx) = mpsc::channel();
+            *writer_slot.lock() = Some(tx);
+            *socket = new_socket;
+            *session = new_session;
+            *rx = new_rx;
+            shared.connected.store(true, Ordering::Relaxed);
+            return true;
+        }
+    }
+
+    shared.events.lock().push_back(MeshEvent::Disconnected {
+        reason: "mesh reconnect failed, giving up".to_string(),
+    });
+    false
+}
+
 // ============================================================================
 // Android Node Wrapper
 // ============================================================================
@@ -90,35 +512,147 @@ pub struct AndroidNode {
     cap_name_to_id: RwLock<HashMap<String, uuid::Uuid>>,
     // Mesh connection state
     mesh: Mutex<MeshConnection>,
-    // Discovered/connected peers
-    peers: RwLock<Vec<Peer>>,
+    // Discovered/connected peers; shared with the reader thread so it can
+    // apply peer-list snapshots and churn without round-tripping through JNI
+    peers: Arc<RwLock<Vec<Peer>>>,
+    // Gossip and connection-state frames buffered for `nativePollEvents`
+    events: Arc<Mutex<VecDeque<MeshEvent>>>,
+    // Kademlia-style routing table keyed on NodeId, populated as peers are
+    // discovered; used to route capability lookups toward a provider that
+    // isn't registered locally.
+    routing: Arc<RwLock<RoutingTable>>,
+    // This node's persisted Noise static keypair, loaded once at startup so
+    // the same identity survives restarts and reconnects.
+    identity: Arc<NoiseIdentity>,
+    // Trust-on-first-use pins of the static key each mesh endpoint has
+    // presented, so a later session to the same endpoint can't be
+    // impersonated by a different key.
+    known_hosts: Arc<Mutex<KnownHosts>>,
+    // mDNS/DNS-SD advertise+browse worker, used as an offline fallback to
+    // the mesh coordinator for peer discovery on the local network.
+    discovery: Mutex<LocalDiscovery>,
+    local_discovery_enabled: AtomicBool,
+    // In-flight request/response calls made through `call_remote`, resolved
+    // by the reader thread as matching "response" frames arrive.
+    rpc: Arc<RpcTable>,
+    // Binary framing used for the next `join_mesh` call (and every
+    // reconnect it triggers); defaults to JSON. Selectable per connection
+    // via `set_wire_format`, since it only takes effect on the next join.
+    wire_format: Mutex<WireFormat>,
+    // Outbound/inbound chunked transfers started by `send_stream`, shared
+    // with the reader thread so inbound `Stream`/`StreamAck` frames can be
+    // applied without a round-trip through JNI.
+    streams: Arc<StreamManager>,
+    // Java callback registered via `nativeRegisterListener`, shared with the
+    // reader thread so peer/gossip events can be pushed into Kotlin as they
+    // arrive instead of only on the next `nativePollEvents`. Cleared (and
+    // its `GlobalRef` released) on `disconnect_mesh` and node teardown.
+    listener: Arc<Mutex<Option<MeshListener>>>,
+    // Dials and supervises peers reached through `connect_to_peer`, giving
+    // each one a handshake, negotiated feature set, and background
+    // reconnection instead of a one-shot address record.
+    peer_conns: Arc<PeerConnectionManager>,
+    // `android.os.Handler` bound via `nativeBindLooper`, shared with the
+    // reader thread and `peer_conns` so events are posted onto its `Looper`
+    // as they happen instead of only invoking `listener` synchronously.
+    looper: Arc<Mutex<Option<LooperSink>>>,
 }
 
 impl AndroidNode {
-    pub fn new(node_id: String, data_dir: String) -> Self {
-        Self {
+    pub fn new(node_id: String, data_dir: String) -> Result<Self, String> {
+        let routing_id = parse_routing_id(&node_id).unwrap_or_default();
+        let identity = NoiseIdentity::load_or_generate(&data_dir).map_err(|e| e.to_string())?;
+        let known_hosts = KnownHosts::load(&data_dir);
+        let peers = Arc::new(RwLock::new(Vec::new()));
+        let events = Arc::new(Mutex::new(VecDeque::new()));
+        let listener = Arc::new(Mutex::new(None));
+        let looper = Arc::new(Mutex::new(None));
+        let peer_conns = Arc::new(PeerConnectionManager::new(
+            node_id.clone(),
+            PeerConnShared {
+                peers: peers.clone(),
+                events: events.clone(),
+                listener: listener.clone(),
+                looper: looper.clone(),
+            },
+        ));
+        Ok(Self {
             node_id,
             data_dir,
             running: RwLock::new(false),
             capabilities: Arc::new(CapabilityRegistry::new()),
             cap_name_to_id: RwLock::new(HashMap::new()),
             mesh: Mutex::new(MeshConnection::new()),
-            peers: RwLock::new(Vec::new()),
-        }
+            peers,
+            events,
+            routing: Arc::new(RwLock::new(RoutingTable::new(routing_id))),
+            identity: Arc::new(identity),
+            known_hosts: Arc::new(Mutex::new(known_hosts)),
+            discovery: Mutex::new(LocalDiscovery::new()),
+            local_discovery_enabled: AtomicBool::new(false),
+            rpc: Arc::new(RpcTable::new()),
+            wire_format: Mutex::new(WireFormat::Json),
+            streams: Arc::new(StreamManager::new()),
+            listener,
+            peer_conns,
+            looper,
+        })
     }
-    
+
+    /// Resolve `listener`'s `onPeerConnected`/`onPeerDisconnected`/`onMessage`
+    /// method ids and keep a `GlobalRef` to it so the mesh reader thread can
+    /// call back into Kotlin as events arrive. Replaces any previously
+    /// registered listener, releasing its `GlobalRef`.
+    pub fn register_listener(&self, env: &mut JNIEnv, obj: &JObject) -> Result<(), String> {
+        let listener = MeshListener::new(env, obj)?;
+        *self.listener.lock() = Some(listener);
+        Ok(())
+    }
+
+    /// Drop the registered listener, if any, releasing its `GlobalRef`.
+    fn clear_listener(&self) {
+        *self.listener.lock() = None;
+    }
+
+    /// Resolve `handler`'s `sendMessage` method id and keep a `GlobalRef` to
+    /// it so mesh/peer events are additionally posted onto its `Looper`
+    /// instead of only invoked synchronously through `listener`. Replaces
+    /// any previously bound `Handler`, releasing its `GlobalRef`.
+    pub fn bind_looper(&self, env: &mut JNIEnv, handler: &JObject) -> Result<(), String> {
+        let sink = LooperSink::new(env, handler)?;
+        *self.looper.lock() = Some(sink);
+        Ok(())
+    }
+
+    /// Select the binary framing used for the mesh connection established by
+    /// the next `join_mesh` call. JSON is the default and the only format
+    /// the coordinator has ever had to support; protobuf trades that
+    /// readability for meaningfully smaller frames over a phone radio.
+    pub fn set_wire_format(&self, protobuf: bool) {
+        *self.wire_format.lock() = if protobuf { WireFormat::Protobuf } else { WireFormat::Json };
+    }
+
     pub fn node_id(&self) -> &str {
         &self.node_id
     }
-    
+
     pub fn data_dir(&self) -> &str {
         &self.data_dir
     }
+
+    /// Hex-encoded static public key this node proves ownership of during
+    /// the Noise handshake, for display or out-of-band confirmation. No
+    /// `node_id` is checked against a static key; the only check is the
+    /// per-endpoint pin in `noise::KnownHosts`.
+    pub fn local_public_key_hex(&self) -> String {
+        self.identity.public_key_hex()
+    }
     
     pub fn is_running(&self) -> bool {
         *self.running.read().unwrap()
     }
     
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
     pub fn start(&self) -> Result<(), String> {
         let mut running = self.running.write().unwrap();
         if *running {
@@ -131,27 +665,30 @@ impl AndroidNode {
     pub fn stop(&self) {
         // Disconnect from mesh first
         self.disconnect_mesh();
+        self.stop_local_discovery();
         let mut running = self.running.write().unwrap();
         *running = false;
     }
-    
+
     pub fn status_json(&self) -> String {
-        let running = self.is_running();
-        let cap_count = self.cap_name_to_id.read().unwrap().len();
         let mesh = self.mesh.lock();
-        let peer_count = self.peers.read().unwrap().len();
-        format!(
-            r#"{{"node_id":"{}","is_running":{},"capabilities_count":{},"connected_peers":{},"mesh_connected":{},"mesh_id":{},"mesh_name":{}}}"#,
-            self.node_id, running, cap_count, peer_count,
-            mesh.connected,
-            mesh.mesh_id.as_ref().map(|s| format!("\"{}\"", s)).unwrap_or("null".to_string()),
-            mesh.mesh_name.as_ref().map(|s| format!("\"{}\"", s)).unwrap_or("null".to_string())
-        )
+        let report = StatusReport {
+            node_id: self.node_id.clone(),
+            is_running: self.is_running(),
+            capabilities_count: self.cap_name_to_id.read().unwrap().len(),
+            connected_peers: self.peers.read().unwrap().len(),
+            mesh_connected: mesh.is_connected(),
+            mesh_id: mesh.mesh_id.clone(),
+            mesh_name: mesh.mesh_name.clone(),
+            local_discovery: self.is_local_discovery_enabled(),
+        };
+        report.to_json()
     }
     
-    pub fn register_capability_json(&self, json: &str) -> Result<(), String> {
+    #[jni_export(AndroidNode, class = "AtmosphereNode", name = "RegisterCapability")]
+    pub fn register_capability_json(&self, json: String) -> Result<(), String> {
         // Parse capability from JSON
-        let cap: serde_json::Value = serde_json::from_str(json)
+        let cap: serde_json::Value = serde_json::from_str(&json)
             .map_err(|e| format!("Invalid JSON: {}", e))?;
         
         let name = cap["name"].as_str().ok_or("Missing name")?;
@@ -167,13 +704,14 @@ impl AndroidNode {
         Ok(())
     }
     
-    pub fn route_intent_json(&self, json: &str) -> Result<String, String> {
+    #[jni_export(AndroidNode, class = "AtmosphereNode", name = "RouteIntent")]
+    pub fn route_intent_json(&self, json: String) -> Result<String, String> {
         // Parse intent
-        let intent: serde_json::Value = serde_json::from_str(json)
+        let intent: serde_json::Value = serde_json::from_str(&json)
             .map_err(|e| format!("Invalid JSON: {}", e))?;
-        
+
         let capability_name = intent["capability"].as_str().ok_or("Missing capability")?;
-        
+
         // Check if we have the capability locally by name
         let cap_map = self.cap_name_to_id.read().unwrap();
         if let Some(uuid) = cap_map.get(capability_name) {
@@ -184,143 +722,210 @@ impl AndroidNode {
                 ));
             }
         }
-        
+        drop(cap_map);
+
+        // No local provider: fall back to the closest peer in the routing
+        // table. If we're actually connected to the mesh, send the intent
+        // to the coordinator as an RPC call for the capability and wait for
+        // its result - the request frame carries no target node, so
+        // `closest` is only reported alongside the response; otherwise we
+        // can only name the target, since there's no connection to forward
+        // it over.
+        let target_id = routing::capability_id(capability_name);
+        let routing = self.routing.read().unwrap();
+        let closest = routing.closest(&target_id, 1).into_iter().next();
+        drop(routing);
+
+        if let Some(closest) = closest {
+            if self.mesh.lock().is_connected() {
+                return match self.call_remote(capability_name.to_string(), intent.to_string(), DEFAULT_RPC_TIMEOUT_MS as i64) {
+                    Ok(response) => {
+                        let response: serde_json::Value =
+                            serde_json::from_str(&response).unwrap_or(serde_json::Value::String(response));
+                        Ok(serde_json::json!({
+                            "status": "routed_remote",
+                            "target_node": closest.node_id.to_string(),
+                            "capability": capability_name,
+                            "response": response,
+                        })
+                        .to_string())
+                    }
+                    Err(e) => Err(format!("Remote call to {} failed: {}", closest.node_id, e)),
+                };
+            }
+
+            return Ok(serde_json::json!({
+                "status": "forwarded",
+                "target_node": closest.node_id.to_string(),
+                "capability": capability_name,
+            })
+            .to_string());
+        }
+
         Err(format!("Capability not found: {}", capability_name))
     }
+
+    /// Rank known peers by XOR distance to `capability`'s hashed id, for
+    /// `nativeFindProviders`. A local match (if registered) is reported
+    /// first since it needs no hop at all.
+    pub fn find_providers_json(&self, capability: &str) -> String {
+        let has_local = self
+            .cap_name_to_id
+            .read()
+            .unwrap()
+            .get(capability)
+            .map(|uuid| self.capabilities.get(*uuid).is_some())
+            .unwrap_or(false);
+
+        let target_id = routing::capability_id(capability);
+        let routing = self.routing.read().unwrap();
+        let closest = routing.closest(&target_id, 20);
+
+        let mut entries = Vec::new();
+        if has_local {
+            entries.push(serde_json::json!({ "node_id": self.node_id, "address": "local", "hops": 0 }));
+        }
+        for peer in closest {
+            entries.push(serde_json::json!({
+                "node_id": peer.node_id.to_string(),
+                "address": peer.address,
+                "hops": 1,
+            }));
+        }
+
+        serde_json::Value::Array(entries).to_string()
+    }
     
     /// Join a mesh network via WebSocket
-    pub fn join_mesh(&self, endpoint: &str, token: &str) -> Result<(), String> {
+    ///
+    /// Performs the `join` handshake synchronously (so the caller learns
+    /// right away whether the token was accepted), then hands the socket off
+    /// to a dedicated reader thread that owns it for the rest of the
+    /// connection's life: it drains queued outbound writes, applies inbound
+    /// peer/gossip frames directly, and reconnects on its own if the socket
+    /// drops. This is what lets peer churn and gossip arrive as push
+    /// notifications instead of only on the next explicit poll.
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn join_mesh(&self, endpoint: String, token: String) -> Result<(), String> {
         let mut mesh = self.mesh.lock();
-        
-        // Parse the endpoint URL
-        let ws_url = if endpoint.starts_with("ws://") || endpoint.starts_with("wss://") {
-            endpoint.to_string()
-        } else if endpoint.starts_with("http://") {
-            endpoint.replace("http://", "ws://")
-        } else if endpoint.starts_with("https://") {
-            endpoint.replace("https://", "wss://")
-        } else {
-            format!("ws://{}", endpoint)
+
+        let reconnect = ReconnectInfo {
+            endpoint: endpoint.clone(),
+            token: token.clone(),
+            node_id: self.node_id.clone(),
+            capabilities: self.get_capability_names(),
+            identity: self.identity.clone(),
+            known_hosts: self.known_hosts.clone(),
+            wire_format: *self.wire_format.lock(),
         };
-        
-        // Add /api/ws if not present
-        let ws_url = if ws_url.contains("/api/ws") {
-            ws_url
-        } else {
-            format!("{}/api/ws", ws_url.trim_end_matches('/'))
+
+        let (socket, session) = handshake(&reconnect)?;
+
+        // The handshake response only tells us the connection was accepted;
+        // mesh_id/mesh_name come back on the same "joined"/"welcome" frame,
+        // so re-read them here rather than threading them out of handshake().
+        mesh.endpoint = endpoint;
+        mesh.token = token;
+
+        let (tx, rx) = mpsc::channel();
+        *mesh.writer.lock() = Some(tx);
+        mesh.connected.store(true, Ordering::Relaxed);
+
+        let shared = MeshShared {
+            peers: self.peers.clone(),
+            events: self.events.clone(),
+            connected: mesh.connected.clone(),
+            routing: self.routing.clone(),
+            rpc: self.rpc.clone(),
+            streams: self.streams.clone(),
+            listener: self.listener.clone(),
+            looper: self.looper.clone(),
         };
-        
-        // Parse URL
-        let url = Url::parse(&ws_url)
-            .map_err(|e| format!("Invalid URL: {}", e))?;
-        
-        // Connect with timeout
-        let (mut socket, _response): (WebSocket<MaybeTlsStream<TcpStream>>, _) = connect(url)
-            .map_err(|e| format!("WebSocket connection failed: {}", e))?;
-        
-        // Send join message
-        let join_msg = serde_json::json!({
-            "type": "join",
-            "token": token,
-            "node_id": self.node_id,
-            "capabilities": self.get_capability_names()
-        });
-        
-        socket.send(Message::Text(join_msg.to_string()))
-            .map_err(|e| format!("Failed to send join message: {}", e))?;
-        
-        // Wait for response
-        let response = socket.read()
-            .map_err(|e| format!("Failed to read response: {}", e))?;
-        
-        if let Message::Text(text) = response {
-            let resp: serde_json::Value = serde_json::from_str(&text)
-                .map_err(|e| format!("Invalid response JSON: {}", e))?;
-            
-            if resp["type"].as_str() == Some("joined") || resp["type"].as_str() == Some("welcome") {
-                mesh.endpoint = endpoint.to_string();
-                mesh.token = token.to_string();
-                mesh.mesh_id = resp["mesh_id"].as_str().map(|s| s.to_string());
-                mesh.mesh_name = resp["mesh_name"].as_str().map(|s| s.to_string());
-                mesh.ws = Some(socket);
-                mesh.connected = true;
-                
-                return Ok(());
-            } else if resp["type"].as_str() == Some("error") {
-                return Err(resp["message"].as_str().unwrap_or("Unknown error").to_string());
-            }
-        }
-        
-        Err("Unexpected response from server".to_string())
+        let writer_slot = mesh.writer.clone();
+
+        mesh.reader_thread = Some(thread::spawn(move || {
+            run_reader_loop(socket, session, rx, writer_slot, shared, reconnect);
+        }));
+
+        Ok(())
     }
-    
+
     /// Disconnect from mesh
     pub fn disconnect_mesh(&self) {
         let mut mesh = self.mesh.lock();
-        if let Some(mut ws) = mesh.ws.take() {
-            let _ = ws.close(None);
-        }
-        mesh.connected = false;
+        mesh.connected.store(false, Ordering::Relaxed);
+        *mesh.writer.lock() = None;
         mesh.mesh_id = None;
         mesh.mesh_name = None;
-        
+        if let Some(handle) = mesh.reader_thread.take() {
+            drop(mesh);
+            let _ = handle.join();
+        }
+
         // Clear peers
         self.peers.write().unwrap().clear();
+        self.clear_listener();
     }
-    
-    /// Discover peers on the mesh
+
+    /// Ask the mesh for its current peer list. The response is applied to
+    /// `self.peers` by the reader thread as soon as it arrives rather than
+    /// being read back here, so this returns as soon as the request is
+    /// queued - callers should re-read `get_peers_json` after a short delay
+    /// or in response to a `peer_joined`/`peer_left` event.
     pub fn discover_peers(&self) -> Result<(), String> {
-        let mut mesh = self.mesh.lock();
-        
-        if !mesh.connected {
+        let mesh = self.mesh.lock();
+
+        if !mesh.is_connected() {
             return Err("Not connected to mesh".to_string());
         }
-        
-        let ws = mesh.ws.as_mut().ok_or("WebSocket not available")?;
-        
-        // Send discover message
-        let discover_msg = serde_json::json!({
-            "type": "discover",
-            "node_id": self.node_id
-        });
-        
-        ws.send(Message::Text(discover_msg.to_string()))
-            .map_err(|e| format!("Failed to send discover message: {}", e))?;
-        
-        // Read response (with timeout)
-        let response = ws.read()
-            .map_err(|e| format!("Failed to read discover response: {}", e))?;
-        
-        if let Message::Text(text) = response {
-            let resp: serde_json::Value = serde_json::from_str(&text)
-                .map_err(|e| format!("Invalid response JSON: {}", e))?;
-            
-            if resp["type"].as_str() == Some("peers") {
-                if let Some(peers_arr) = resp["peers"].as_array() {
-                    let mut peers = self.peers.write().unwrap();
-                    peers.clear();
-                    
-                    for p in peers_arr {
-                        peers.push(Peer {
-                            node_id: p["node_id"].as_str().unwrap_or("unknown").to_string(),
-                            name: p["name"].as_str().unwrap_or("Unknown Node").to_string(),
-                            address: p["address"].as_str().unwrap_or("").to_string(),
-                            connected: p["connected"].as_bool().unwrap_or(false),
-                            latency_ms: p["latency_ms"].as_u64().map(|l| l as u32),
-                            capabilities: p["capabilities"].as_array()
-                                .map(|arr| arr.iter()
-                                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                    .collect())
-                                .unwrap_or_default(),
-                        });
-                    }
-                }
-            }
-        }
-        
+
+        let discover_msg = MeshMessage::Discover { node_id: self.node_id.clone() };
+
+        self.enqueue(&mesh, discover_msg)
+    }
+
+    /// Start advertising this node over mDNS/DNS-SD and browsing for other
+    /// `_atmosphere` instances on the local network, merging matches into
+    /// `self.peers` tagged `PeerSource::Lan`. A no-op if already running.
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn start_local_discovery(&self) -> Result<(), String> {
+        let name = format!("atmosphere-{}", &self.node_id[..8.min(self.node_id.len())]);
+        self.discovery.lock().start(
+            self.node_id.clone(),
+            name,
+            LOCAL_DISCOVERY_PORT,
+            self.get_capability_names(),
+            self.peers.clone(),
+        )?;
+        self.local_discovery_enabled.store(true, Ordering::Relaxed);
         Ok(())
     }
-    
+
+    /// Stop mDNS advertising/browsing and drop any LAN peers it contributed.
+    pub fn stop_local_discovery(&self) {
+        self.discovery.lock().stop(&self.peers);
+        self.local_discovery_enabled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_local_discovery_enabled(&self) -> bool {
+        self.local_discovery_enabled.load(Ordering::Relaxed)
+    }
+
+    fn enqueue(&self, mesh: &MeshConnection, msg: MeshMessage) -> Result<(), String> {
+        let writer = mesh.writer.lock();
+        match writer.as_ref() {
+            Some(tx) => tx.send(msg).map_err(|_| "Mesh writer channel closed".to_string()),
+            None => Err("WebSocket not available".to_string()),
+        }
+    }
+
+    /// Drain buffered gossip/connection-state events for the Kotlin side.
+    pub fn poll_events_json(&self) -> String {
+        let mut events = self.events.lock();
+        let drained: Vec<String> = events.drain(..).map(|e| e.to_json()).collect();
+        format!("[{}]", drained.join(","))
+    }
+
     /// Get peers as JSON array
     pub fn get_peers_json(&self) -> String {
         let peers = self.peers.read().unwrap();
@@ -328,53 +933,113 @@ impl AndroidNode {
         format!("[{}]", peers_json.join(","))
     }
     
-    /// Connect to a specific peer by address
-    pub fn connect_to_peer(&self, address: &str) -> Result<(), String> {
-        let mut peers = self.peers.write().unwrap();
-        
-        // Find and update peer status
-        for peer in peers.iter_mut() {
-            if peer.address == address {
-                peer.connected = true;
-                return Ok(());
-            }
-        }
-        
-        // If not found, add as new peer
-        peers.push(Peer {
-            node_id: format!("peer_{}", address.replace(".", "_").replace(":", "_")),
-            name: format!("Peer at {}", address),
-            address: address.to_string(),
-            connected: true,
-            latency_ms: None,
-            capabilities: vec![],
-        });
-        
+    /// Dial a peer by address. Returns as soon as the dial is queued; the
+    /// actual handshake, feature negotiation, and any reconnection happen on
+    /// `peer_conns`'s background thread for this address, visible afterward
+    /// through that peer's `state`/`features` in `get_peers_json`.
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn connect_to_peer(&self, address: String) -> Result<(), String> {
+        self.peer_conns.connect(address);
         Ok(())
     }
-    
+
+    /// Stop retrying a peer dialed via `connect_to_peer` and drop its entry.
+    /// A no-op for peers learned from the mesh coordinator or mDNS, which
+    /// aren't tracked by `peer_conns`.
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn remove_peer(&self, address: String) -> Result<(), String> {
+        self.peer_conns.remove(&address);
+        Ok(())
+    }
+
     /// Send a gossip message to the mesh
-    pub fn send_gossip(&self, message: &str) -> Result<(), String> {
-        let mut mesh = self.mesh.lock();
-        
-        if !mesh.connected {
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn send_gossip(&self, message: String) -> Result<(), String> {
+        let mesh = self.mesh.lock();
+
+        if !mesh.is_connected() {
             return Err("Not connected to mesh".to_string());
         }
-        
-        let ws = mesh.ws.as_mut().ok_or("WebSocket not available")?;
-        
-        let gossip_msg = serde_json::json!({
-            "type": "gossip",
-            "from": self.node_id,
-            "payload": message
-        });
-        
-        ws.send(Message::Text(gossip_msg.to_string()))
-            .map_err(|e| format!("Failed to send gossip: {}", e))?;
-        
-        Ok(())
+
+        let gossip_msg = MeshMessage::Gossip {
+            from: self.node_id.clone(),
+            payload: message,
+        };
+
+        self.enqueue(&mesh, gossip_msg)
     }
-    
+
+    /// Split `data` into chunks and send them to `peer_node_id` as a new
+    /// stream, throttled by `streaming::StreamManager`'s sliding window so a
+    /// fast sender can't overrun a slow mobile link. Returns the new
+    /// stream's id immediately; the transfer itself proceeds on a
+    /// background thread. No event is reported to the sender when it
+    /// finishes; `StreamComplete`/`StreamError` events appear in
+    /// `poll_events_json` on the receiving node only.
+    pub fn send_stream(&self, peer_node_id: &str, data: Vec<u8>) -> Result<StreamId, String> {
+        let mesh = self.mesh.lock();
+
+        if !mesh.is_connected() {
+            return Err("Not connected to mesh".to_string());
+        }
+
+        let writer = mesh.writer.clone();
+        let from = self.node_id.clone();
+        let to = peer_node_id.to_string();
+
+        Ok(self.streams.send(from, to, data, move |msg| match writer.lock().as_ref() {
+            Some(tx) => tx.send(msg).map_err(|_| "Mesh writer channel closed".to_string()),
+            None => Err("WebSocket not available".to_string()),
+        }))
+    }
+
+    /// Cancel an in-flight outbound stream started by `send_stream`; its
+    /// background thread notices on its next wakeup and sends no further
+    /// chunks.
+    pub fn cancel_stream(&self, stream_id: StreamId) {
+        self.streams.cancel(stream_id);
+    }
+
+    /// Invoke a capability on a remote peer and wait for its result. Sends
+    /// `{"type":"request","id":<id>,...}` with a fresh, monotonically
+    /// increasing `request_id` and blocks on `self.rpc`'s slot for it until
+    /// the matching `"response"` frame arrives or `timeout_ms` elapses, at
+    /// which point the slot is torn down and this returns an error.
+    #[jni_export(AndroidNode, class = "AtmosphereNode")]
+    pub fn call_remote(&self, capability: String, payload: String, timeout_ms: i64) -> Result<String, String> {
+        let mesh = self.mesh.lock();
+
+        if !mesh.is_connected() {
+            return Err("Not connected to mesh".to_string());
+        }
+
+        let payload_value: serde_json::Value =
+            serde_json::from_str(&payload).unwrap_or_else(|_| serde_json::Value::String(payload.clone()));
+
+        let id = self.rpc.next_request_id();
+        let rx = self.rpc.register(id);
+
+        let request_msg = MeshMessage::Request {
+            id,
+            capability,
+            payload: payload_value,
+        };
+
+        if let Err(e) = self.enqueue(&mesh, request_msg) {
+            self.rpc.cancel(id);
+            return Err(e);
+        }
+        drop(mesh);
+
+        match rx.recv_timeout(Duration::from_millis(timeout_ms.max(0) as u64)) {
+            Ok(response) => Ok(response.to_string()),
+            Err(_) => {
+                self.rpc.cancel(id);
+                Err("timeout".to_string())
+            }
+        }
+    }
+
     fn get_capability_names(&self) -> Vec<String> {
         self.cap_name_to_id.read().unwrap().keys().cloned().collect()
     }
@@ -383,6 +1048,13 @@ impl AndroidNode {
 /// Opaque handle to an AndroidNode
 type NodeHandle = *mut AndroidNode;
 
+/// Throw a Java `IllegalStateException` carrying `msg`. Unlike a Rust panic,
+/// this doesn't unwind - the native method must still return its own
+/// error/default value immediately afterwards.
+fn throw_illegal_state(env: &mut JNIEnv, msg: &str) {
+    let _ = env.throw_new("java/lang/IllegalStateException", msg);
+}
+
 // ============================================================================
 // Static Functions (called from Kotlin companion object)
 // ============================================================================
@@ -390,49 +1062,36 @@ type NodeHandle = *mut AndroidNode;
 /// Create a new Atmosphere node
 /// Returns a handle (pointer) to the node, or 0 on failure
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_00024Companion_nativeCreateNode(
-    _env: *mut std::ffi::c_void,
-    _class: *mut std::ffi::c_void,
-    node_id: *const c_char,
-    data_dir: *const c_char,
-) -> c_long {
-    // Safety: Convert C strings to Rust strings
-    let node_id = unsafe {
-        if node_id.is_null() {
-            return 0;
-        }
-        match CStr::from_ptr(node_id).to_str() {
-            Ok(s) => s.to_string(),
-            Err(_) => return 0,
-        }
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_00024Companion_nativeCreateNode(
+    env: JNIEnv,
+    _class: JClass,
+    node_id: JString,
+    data_dir: JString,
+) -> jlong {
+    let node_id: String = match env.get_string(&node_id) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
     };
-    
-    let data_dir = unsafe {
-        if data_dir.is_null() {
-            return 0;
-        }
-        match CStr::from_ptr(data_dir).to_str() {
-            Ok(s) => s.to_string(),
-            Err(_) => return 0,
-        }
+    let data_dir: String = match env.get_string(&data_dir) {
+        Ok(s) => s.into(),
+        Err(_) => return 0,
     };
-    
-    // Create the node
-    let node = AndroidNode::new(node_id, data_dir);
-    let ptr = Box::into_raw(Box::new(node));
-    
-    ptr as c_long
+
+    let node = match AndroidNode::new(node_id, data_dir) {
+        Ok(node) => node,
+        Err(_) => return 0,
+    };
+    Box::into_raw(Box::new(node)) as jlong
 }
 
 /// Generate a new random node ID
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_00024Companion_nativeGenerateNodeId(
-    _env: *mut std::ffi::c_void,
-    _class: *mut std::ffi::c_void,
-) -> *mut c_char {
-    let id = NodeId::new().to_string();
-    match CString::new(id) {
-        Ok(cstr) => cstr.into_raw(),
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_00024Companion_nativeGenerateNodeId(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    match env.new_string(NodeId::new().to_string()) {
+        Ok(s) => s.into_raw(),
         Err(_) => ptr::null_mut(),
     }
 }
@@ -441,33 +1100,14 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_00024Com
 // Instance Methods (called on node handle)
 // ============================================================================
 
-/// Start the node
-/// Returns 0 on success, non-zero on error
-#[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStart(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> i32 {
-    let node = unsafe {
-        if handle == 0 {
-            return -1;
-        }
-        &*(handle as NodeHandle)
-    };
-    
-    match node.start() {
-        Ok(_) => 0,
-        Err(_) => -2,
-    }
-}
+// `nativeStart` is generated by `#[jni_export]` on `AndroidNode::start`.
 
 /// Stop the node
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStop(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStop(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
 ) {
     let node = unsafe {
         if handle == 0 {
@@ -475,324 +1115,340 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeSt
         }
         &*(handle as NodeHandle)
     };
-    
+
     node.stop();
 }
 
 /// Check if node is running
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeIsRunning(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> bool {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeIsRunning(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jboolean {
     let node = unsafe {
         if handle == 0 {
-            return false;
+            return false as jboolean;
         }
         &*(handle as NodeHandle)
     };
-    
-    node.is_running()
+
+    node.is_running() as jboolean
 }
 
 /// Get node ID
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeNodeId(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeNodeId(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
             return ptr::null_mut();
         }
         &*(handle as NodeHandle)
     };
-    
-    match CString::new(node.node_id()) {
-        Ok(cstr) => cstr.into_raw(),
+
+    match env.new_string(node.node_id()) {
+        Ok(s) => s.into_raw(),
         Err(_) => ptr::null_mut(),
     }
 }
 
 /// Get data directory
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDataDir(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDataDir(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
             return ptr::null_mut();
         }
         &*(handle as NodeHandle)
     };
-    
-    match CString::new(node.data_dir()) {
-        Ok(cstr) => cstr.into_raw(),
+
+    match env.new_string(node.data_dir()) {
+        Ok(s) => s.into_raw(),
         Err(_) => ptr::null_mut(),
     }
 }
 
-/// Get status as JSON
+/// Get this node's hex-encoded Noise static public key, so the app can
+/// display/share a verifiable identity for out-of-band confirmation.
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStatusJson(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeLocalPublicKey(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
             return ptr::null_mut();
         }
         &*(handle as NodeHandle)
     };
-    
-    let status = node.status_json();
-    match CString::new(status) {
-        Ok(cstr) => cstr.into_raw(),
+
+    match env.new_string(node.local_public_key_hex()) {
+        Ok(s) => s.into_raw(),
         Err(_) => ptr::null_mut(),
     }
 }
 
-/// Register a capability
-/// Returns 0 on success, non-zero on error
+/// Get status as JSON
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeRegisterCapability(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-    json: *const c_char,
-) -> i32 {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStatusJson(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
-            return -1;
+            return ptr::null_mut();
         }
         &*(handle as NodeHandle)
     };
-    
-    let json_str = unsafe {
-        if json.is_null() {
-            return -2;
-        }
-        match CStr::from_ptr(json).to_str() {
-            Ok(s) => s,
-            Err(_) => return -3,
-        }
-    };
-    
-    match node.register_capability_json(json_str) {
-        Ok(_) => 0,
-        Err(_) => -4,
+
+    match env.new_string(node.status_json()) {
+        Ok(s) => s.into_raw(),
+        Err(_) => ptr::null_mut(),
     }
 }
 
-/// Route an intent
-/// Returns JSON result or "ERROR:message" on failure
+// `nativeRegisterCapability`, `nativeRouteIntent` and `nativeCallRemote` are
+// generated by `#[jni_export]` on `AndroidNode::register_capability_json`,
+// `::route_intent_json` and `::call_remote` respectively. They now throw
+// `IllegalStateException` on failure instead of the old "ERROR:..." string
+// sentinel / non-zero status code.
+
+/// Rank known peers (and, if registered, ourselves) by distance to a
+/// capability's hashed id in the local routing table.
+/// Returns a JSON array, nearest-first.
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeRouteIntent(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-    json: *const c_char,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeFindProviders(
+    mut env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+    capability: JString,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
-            return CString::new("ERROR:Invalid handle").unwrap().into_raw();
+            return env.new_string("[]").unwrap().into_raw();
         }
         &*(handle as NodeHandle)
     };
-    
-    let json_str = unsafe {
-        if json.is_null() {
-            return CString::new("ERROR:Null JSON").unwrap().into_raw();
-        }
-        match CStr::from_ptr(json).to_str() {
-            Ok(s) => s,
-            Err(_) => return CString::new("ERROR:Invalid UTF-8").unwrap().into_raw(),
-        }
+
+    let capability_str: String = match env.get_string(&capability) {
+        Ok(s) => s.into(),
+        Err(_) => return env.new_string("[]").unwrap().into_raw(),
     };
-    
-    match node.route_intent_json(json_str) {
-        Ok(result) => {
-            match CString::new(result) {
-                Ok(cstr) => cstr.into_raw(),
-                Err(_) => CString::new("ERROR:Encoding error").unwrap().into_raw(),
-            }
-        }
-        Err(e) => {
-            let msg = format!("ERROR:{}", e);
-            CString::new(msg).unwrap().into_raw()
-        }
-    }
+
+    let providers_json = node.find_providers_json(&capability_str);
+    env.new_string(providers_json).map(|s| s.into_raw()).unwrap_or(ptr::null_mut())
 }
 
 // ============================================================================
 // NEW: Networking Functions
 // ============================================================================
 
-/// Join a mesh network
-/// Returns 0 on success, non-zero on error
+// `nativeJoinMesh` is generated by `#[jni_export]` on `AndroidNode::join_mesh`.
+
+/// Register a Kotlin listener to receive `onPeerConnected`/
+/// `onPeerDisconnected`/`onMessage` callbacks as mesh events happen,
+/// instead of only learning about them through `nativePollEvents`. Throws
+/// `IllegalStateException` if `listener` is missing one of those methods.
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeJoinMesh(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-    endpoint: *const c_char,
-    token: *const c_char,
-) -> i32 {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeRegisterListener(
+    mut env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+    listener: JObject,
+) {
     let node = unsafe {
         if handle == 0 {
-            return -1;
+            throw_illegal_state(&mut env, "Invalid handle");
+            return;
         }
         &*(handle as NodeHandle)
     };
-    
-    let endpoint_str = unsafe {
-        if endpoint.is_null() {
-            return -2;
-        }
-        match CStr::from_ptr(endpoint).to_str() {
-            Ok(s) => s,
-            Err(_) => return -3,
-        }
-    };
-    
-    let token_str = unsafe {
-        if token.is_null() {
-            return -4;
-        }
-        match CStr::from_ptr(token).to_str() {
-            Ok(s) => s,
-            Err(_) => return -5,
+
+    if let Err(e) = node.register_listener(&mut env, &listener) {
+        throw_illegal_state(&mut env, &e);
+    }
+}
+
+/// Bind an `android.os.Handler` so mesh/peer events are posted as
+/// `android.os.Message`s onto its `Looper` rather than only invoked
+/// synchronously through a registered `nativeRegisterListener` listener.
+/// Throws `IllegalStateException` if `handler` isn't actually a `Handler`.
+#[no_mangle]
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeBindLooper(
+    mut env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+    handler: JObject,
+) {
+    let node = unsafe {
+        if handle == 0 {
+            throw_illegal_state(&mut env, "Invalid handle");
+            return;
         }
+        &*(handle as NodeHandle)
     };
-    
-    match node.join_mesh(endpoint_str, token_str) {
-        Ok(_) => 0,
-        Err(_) => -6,
+
+    if let Err(e) = node.bind_looper(&mut env, &handler) {
+        throw_illegal_state(&mut env, &e);
     }
 }
 
 /// Discover peers on the mesh
 /// Returns JSON array of peers
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDiscoverPeers(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDiscoverPeers(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
-            return CString::new("[]").unwrap().into_raw();
+            return env.new_string("[]").unwrap().into_raw();
         }
         &*(handle as NodeHandle)
     };
-    
+
     // Try to discover, then return current peers
     let _ = node.discover_peers();
-    let peers_json = node.get_peers_json();
-    
-    match CString::new(peers_json) {
-        Ok(cstr) => cstr.into_raw(),
-        Err(_) => CString::new("[]").unwrap().into_raw(),
-    }
+    env.new_string(node.get_peers_json()).map(|s| s.into_raw()).unwrap_or(ptr::null_mut())
 }
 
-/// Connect to a specific peer
-/// Returns 0 on success, non-zero on error
+// `nativeStartLocalDiscovery` is generated by `#[jni_export]` on
+// `AndroidNode::start_local_discovery`. It now throws `IllegalStateException`
+// on failure instead of the old non-zero status code.
+
+/// Stop mDNS/DNS-SD discovery and drop any LAN peers it contributed.
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeConnectToPeer(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-    address: *const c_char,
-) -> i32 {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeStopLocalDiscovery(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) {
     let node = unsafe {
         if handle == 0 {
-            return -1;
+            return;
         }
         &*(handle as NodeHandle)
     };
-    
-    let address_str = unsafe {
-        if address.is_null() {
-            return -2;
-        }
-        match CStr::from_ptr(address).to_str() {
-            Ok(s) => s,
-            Err(_) => return -3,
-        }
-    };
-    
-    match node.connect_to_peer(address_str) {
-        Ok(_) => 0,
-        Err(_) => -4,
-    }
+
+    node.stop_local_discovery();
 }
 
+// `nativeConnectToPeer` is generated by `#[jni_export]` on
+// `AndroidNode::connect_to_peer`.
+
+// `nativeRemovePeer` is generated by `#[jni_export]` on
+// `AndroidNode::remove_peer`.
+
 /// Get connected peers as JSON array
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeGetPeers(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-) -> *mut c_char {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeGetPeers(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
     let node = unsafe {
         if handle == 0 {
-            return CString::new("[]").unwrap().into_raw();
+            return env.new_string("[]").unwrap().into_raw();
         }
         &*(handle as NodeHandle)
     };
-    
-    let peers_json = node.get_peers_json();
-    
-    match CString::new(peers_json) {
-        Ok(cstr) => cstr.into_raw(),
-        Err(_) => CString::new("[]").unwrap().into_raw(),
-    }
+
+    env.new_string(node.get_peers_json()).map(|s| s.into_raw()).unwrap_or(ptr::null_mut())
 }
 
-/// Send a gossip message to the mesh
-/// Returns 0 on success, non-zero on error
+// `nativeSendGossip` is generated by `#[jni_export]` on
+// `AndroidNode::send_gossip`. It now throws `IllegalStateException` on
+// failure instead of the old non-zero status code.
+
+/// Split `bytes` into chunks and send them to `peer_node_id` as a new
+/// stream. Returns the new stream's id (>= 0) on success, or a negative
+/// error code. The sender gets no completion event; the receiving node
+/// reports the reassembled payload through its own `nativePollEvents`.
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeSendGossip(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
-    message: *const c_char,
-) -> i32 {
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeSendStream(
+    mut env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+    peer_node_id: JString,
+    bytes: JByteArray,
+) -> jlong {
     let node = unsafe {
         if handle == 0 {
             return -1;
         }
         &*(handle as NodeHandle)
     };
-    
-    let message_str = unsafe {
-        if message.is_null() {
-            return -2;
-        }
-        match CStr::from_ptr(message).to_str() {
-            Ok(s) => s,
-            Err(_) => return -3,
-        }
+
+    let peer_str: String = match env.get_string(&peer_node_id) {
+        Ok(s) => s.into(),
+        Err(_) => return -2,
     };
-    
-    match node.send_gossip(message_str) {
-        Ok(_) => 0,
+
+    let data = match env.convert_byte_array(&bytes) {
+        Ok(data) => data,
+        Err(_) => return -3,
+    };
+
+    match node.send_stream(&peer_str, data) {
+        Ok(stream_id) => stream_id as jlong,
         Err(_) => -4,
     }
 }
 
+/// Cancel an in-flight outbound stream started by `nativeSendStream`.
+#[no_mangle]
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeCancelStream(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+    stream_id: jlong,
+) {
+    let node = unsafe {
+        if handle == 0 {
+            return;
+        }
+        &*(handle as NodeHandle)
+    };
+
+    node.cancel_stream(stream_id as StreamId);
+}
+
+/// Drain buffered mesh events (peer churn, gossip, disconnects) pushed by
+/// the background reader thread since the last poll.
+/// Returns a JSON array of events.
+#[no_mangle]
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativePollEvents(
+    env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
+) -> jstring {
+    let node = unsafe {
+        if handle == 0 {
+            return env.new_string("[]").unwrap().into_raw();
+        }
+        &*(handle as NodeHandle)
+    };
+
+    env.new_string(node.poll_events_json()).map(|s| s.into_raw()).unwrap_or(ptr::null_mut())
+}
+
 /// Disconnect from mesh
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDisconnectMesh(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDisconnectMesh(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
 ) {
     let node = unsafe {
         if handle == 0 {
@@ -800,16 +1456,16 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDi
         }
         &*(handle as NodeHandle)
     };
-    
+
     node.disconnect_mesh();
 }
 
 /// Destroy/free a node handle
 #[no_mangle]
-pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDestroy(
-    _env: *mut std::ffi::c_void,
-    _obj: *mut std::ffi::c_void,
-    handle: c_long,
+pub extern "system" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDestroy(
+    _env: JNIEnv,
+    _obj: JObject,
+    handle: jlong,
 ) {
     if handle != 0 {
         unsafe {
@@ -825,10 +1481,10 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_bindings_AtmosphereNode_nativeDe
 
 /// Called when the library is loaded
 #[no_mangle]
-pub extern "C" fn JNI_OnLoad(
+pub extern "system" fn JNI_OnLoad(
     _vm: *mut std::ffi::c_void,
     _reserved: *mut std::ffi::c_void,
-) -> i32 {
+) -> jint {
     // JNI version 1.6
     0x00010006
 }
@@ -839,7 +1495,7 @@ mod tests {
 
     #[test]
     fn test_android_node() {
-        let node = AndroidNode::new("test-node".to_string(), "/tmp".to_string());
+        let node = AndroidNode::new("test-node".to_string(), "/tmp".to_string()).unwrap();
         assert!(!node.is_running());
         
         node.start().unwrap();
@@ -860,15 +1516,59 @@ mod tests {
     
     #[test]
     fn test_peers_json() {
-        let node = AndroidNode::new("test-node".to_string(), "/tmp".to_string());
+        let node = AndroidNode::new("test-node".to_string(), "/tmp".to_string()).unwrap();
         
         // Empty peers
         let json = node.get_peers_json();
         assert_eq!(json, "[]");
         
         // Add a peer
-        node.connect_to_peer("192.168.1.1:11451").unwrap();
+        node.connect_to_peer("192.168.1.1:11451".to_string()).unwrap();
         let json = node.get_peers_json();
         assert!(json.contains("192.168.1.1:11451"));
     }
+
+    #[test]
+    fn test_poll_events_drains_queue() {
+        let node = AndroidNode::new("test-node".to_string(), "/tmp".to_string()).unwrap();
+
+        assert_eq!(node.poll_events_json(), "[]");
+
+        node.events.lock().push_back(MeshEvent::Gossip {
+            from: "peer-1".to_string(),
+            payload: "hello".to_string(),
+        });
+        node.events.lock().push_back(MeshEvent::PeerLeft { node_id: "peer-2".to_string() });
+
+        let drained = node.poll_events_json();
+        assert!(drained.contains("\"type\":\"gossip\""));
+        assert!(drained.contains("\"type\":\"peer_left\""));
+
+        // Polling again returns nothing new.
+        assert_eq!(node.poll_events_json(), "[]");
+    }
+
+    #[test]
+    fn test_find_providers_includes_local_match() {
+        let node = AndroidNode::new(Uuid::new_v4().to_string(), "/tmp".to_string()).unwrap();
+        node.register_capability_json(r#"{"name":"llm.chat"}"#.to_string()).unwrap();
+
+        let json = node.find_providers_json("llm.chat");
+        assert!(json.contains("\"address\":\"local\""));
+        assert!(json.contains("\"hops\":0"));
+    }
+
+    #[test]
+    fn test_route_intent_forwards_to_routing_table_when_no_local_match() {
+        let node = AndroidNode::new(Uuid::new_v4().to_string(), "/tmp".to_string()).unwrap();
+        let remote_id = Uuid::new_v4();
+        node.routing.write().unwrap().insert(
+            NodeId::from_uuid(remote_id),
+            "10.0.0.2:9000".to_string(),
+        );
+
+        let result = node.route_intent_json(r#"{"capability":"llm.chat"}"#.to_string()).unwrap();
+        assert!(result.contains("\"status\":\"forwarded\""));
+        assert!(result.contains(&remote_id.to_string()));
+    }
 }
@@ -0,0 +1,123 @@
+//! Push mesh events into a Kotlin-side listener object
+//!
+//! Without this, a caller only learns about peer churn and gossip by
+//! polling `nativePollEvents`, which costs a round-trip every time the UI
+//! wants to stay current. `nativeRegisterListener` takes a Java object
+//! implementing `onPeerConnected(String)`/`onPeerDisconnected(String)`/
+//! `onMessage(String, byte[])`, resolves its `jmethodID`s once, and stashes
+//! a `GlobalRef` plus the process `JavaVM` so the background mesh reader
+//! thread (which never runs with a `JNIEnv` of its own) can attach, invoke
+//! the callback, and let the attach guard detach again afterwards.
+//!
+//! `MeshListener` is dropped - and its `GlobalRef` released - whenever the
+//! node clears its listener slot, which happens on `disconnect_mesh`, or
+//! once the last `Arc` to that slot is dropped: the node's own on
+//! `nativeDestroy`, plus any still held by a running mesh reader or peer
+//! connection thread.
+
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::signature::{Primitive, ReturnType};
+use jni::{JNIEnv, JavaVM};
+use jni::sys::jvalue;
+
+/// A Java listener plus everything needed to call back into it from a
+/// native thread with no `JNIEnv` of its own.
+pub struct MeshListener {
+    vm: JavaVM,
+    listener: GlobalRef,
+    on_peer_connected: jni::objects::JMethodID,
+    on_peer_disconnected: jni::objects::JMethodID,
+    on_message: jni::objects::JMethodID,
+}
+
+// `JavaVM`, `GlobalRef` and `JMethodID` are all safe to share across the
+// mesh reader thread: the method IDs are process-lifetime constants and
+// `attach_current_thread` is how the jni crate expects cross-thread calls
+// to happen in the first place.
+unsafe impl Send for MeshListener {}
+unsafe impl Sync for MeshListener {}
+
+impl MeshListener {
+    /// Resolve and cache the listener's method IDs up front, so every
+    /// subsequent callback is just an attach + `CallVoidMethod`.
+    pub fn new(env: &mut JNIEnv, listener: &JObject) -> Result<Self, String> {
+        let vm = env.get_java_vm().map_err(|e| e.to_string())?;
+        let listener = env.new_global_ref(listener).map_err(|e| e.to_string())?;
+        let class = env.get_object_class(&listener).map_err(|e| e.to_string())?;
+
+        let on_peer_connected = env
+            .get_method_id(&class, "onPeerConnected", "(Ljava/lang/String;)V")
+            .map_err(|e| format!("onPeerConnected not found: {}", e))?;
+        let on_peer_disconnected = env
+            .get_method_id(&class, "onPeerDisconnected", "(Ljava/lang/String;)V")
+            .map_err(|e| format!("onPeerDisconnected not found: {}", e))?;
+        let on_message = env
+            .get_method_id(&class, "onMessage", "(Ljava/lang/String;[B)V")
+            .map_err(|e| format!("onMessage not found: {}", e))?;
+
+        Ok(Self { vm, listener, on_peer_connected, on_peer_disconnected, on_message })
+    }
+
+    pub fn notify_peer_connected(&self, node_id: &str) {
+        self.call(|env| {
+            let node_id = env.new_string(node_id)?;
+            let args = [JValue::from(&node_id).as_jni()];
+            unsafe {
+                env.call_method_unchecked(
+                    &self.listener,
+                    self.on_peer_connected,
+                    ReturnType::Primitive(Primitive::Void),
+                    &args,
+                )
+            }
+            .map(|_| ())
+        });
+    }
+
+    pub fn notify_peer_disconnected(&self, node_id: &str) {
+        self.call(|env| {
+            let node_id = env.new_string(node_id)?;
+            let args = [JValue::from(&node_id).as_jni()];
+            unsafe {
+                env.call_method_unchecked(
+                    &self.listener,
+                    self.on_peer_disconnected,
+                    ReturnType::Primitive(Primitive::Void),
+                    &args,
+                )
+            }
+            .map(|_| ())
+        });
+    }
+
+    pub fn notify_message(&self, from: &str, payload: &[u8]) {
+        self.call(|env| {
+            let from = env.new_string(from)?;
+            let bytes = env.byte_array_from_slice(payload)?;
+            let args: [jvalue; 2] = [JValue::from(&from).as_jni(), JValue::from(&bytes).as_jni()];
+            unsafe {
+                env.call_method_unchecked(
+                    &self.listener,
+                    self.on_message,
+                    ReturnType::Primitive(Primitive::Void),
+                    &args,
+                )
+            }
+            .map(|_| ())
+        });
+    }
+
+    /// Attach the calling thread, run `f`, and swallow/clear any JNI
+    /// exception or attach failure rather than propagate it - a listener
+    /// callback firing from the mesh reader thread has no caller to return
+    /// an error to.
+    fn call(&self, f: impl FnOnce(&mut JNIEnv) -> Result<(), jni::errors::Error>) {
+        let mut env = match self.vm.attach_current_thread() {
+            Ok(env) => env,
+            Err(_) => return,
+        };
+        if f(&mut env).is_err() {
+            let _ = env.exception_clear();
+        }
+    }
+}
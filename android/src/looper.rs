@@ -0,0 +1,105 @@
+//! Deliver mesh events onto a bound `android.os.Looper` in addition to the
+//! listener's synchronous callbacks
+//!
+//! `MeshListener` calls `onPeerConnected`/`onPeerDisconnected`/`onMessage`
+//! directly from the mesh reader thread (or a `peer_conn` connection
+//! thread), so a consumer that touches UI from one of those callbacks has to
+//! hop to the main thread itself every time. `nativeBindLooper` lets a
+//! caller also hand over an `android.os.Handler`; every peer-joined,
+//! peer-left and gossip event is then wrapped as an `android.os.Message`
+//! (`what` always `MSG_MESH_EVENT`, `obj` the same JSON `MeshEvent` encoding
+//! `nativePollEvents` already produces) and handed to `Handler.sendMessage`,
+//! so it's dispatched on whatever thread owns that Handler's `Looper`.
+//! Binding a `Handler` does not stop a registered listener from being
+//! called synchronously on the thread that noticed the event.
+
+use crate::MeshEvent;
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::signature::{Primitive, ReturnType};
+use jni::{JNIEnv, JavaVM};
+
+/// `Message.what` for every event posted through a bound `Looper` - a
+/// single code, since `Message.obj` already carries the typed, tagged JSON
+/// payload and there's nothing a per-variant `what` would add.
+pub const MSG_MESH_EVENT: i32 = 1;
+
+/// A bound `Handler` plus the cached method IDs needed to post a `Message`
+/// to it without re-resolving them on every event.
+pub struct LooperSink {
+    vm: JavaVM,
+    handler: GlobalRef,
+    message_class: GlobalRef,
+    message_obtain: jni::objects::JStaticMethodID,
+    handler_send_message: jni::objects::JMethodID,
+}
+
+// Same reasoning as `MeshListener`: `JavaVM`, `GlobalRef` and `JMethodID`
+// are process-lifetime and safe to call from any thread that attaches
+// first.
+unsafe impl Send for LooperSink {}
+unsafe impl Sync for LooperSink {}
+
+impl LooperSink {
+    /// Resolve `Handler.sendMessage`/`Message.obtain`'s IDs up front, so
+    /// every subsequent post is just an attach + a handful of JNI calls.
+    pub fn new(env: &mut JNIEnv, handler: &JObject) -> Result<Self, String> {
+        let vm = env.get_java_vm().map_err(|e| e.to_string())?;
+        let handler = env.new_global_ref(handler).map_err(|e| e.to_string())?;
+
+        let handler_class = env.get_object_class(&handler).map_err(|e| e.to_string())?;
+        let handler_send_message = env
+            .get_method_id(&handler_class, "sendMessage", "(Landroid/os/Message;)Z")
+            .map_err(|e| format!("Handler.sendMessage not found: {}", e))?;
+
+        let message_class = env
+            .find_class("android/os/Message")
+            .map_err(|e| format!("android.os.Message not found: {}", e))?;
+        let message_obtain = env
+            .get_static_method_id(&message_class, "obtain", "()Landroid/os/Message;")
+            .map_err(|e| format!("Message.obtain not found: {}", e))?;
+        let message_class = env.new_global_ref(message_class).map_err(|e| e.to_string())?;
+
+        Ok(Self { vm, handler, message_class, message_obtain, handler_send_message })
+    }
+
+    /// Post `event` as a `Message` onto the bound `Handler`'s `Looper`,
+    /// using the same JSON encoding `nativePollEvents` uses.
+    pub fn post_event(&self, event: &MeshEvent) {
+        let payload = event.to_json();
+        self.call(|env| {
+            let message = unsafe {
+                env.call_static_method_unchecked(&self.message_class, self.message_obtain, ReturnType::Object, &[])
+            }?
+            .l()?;
+
+            env.set_field(&message, "what", "I", JValue::Int(MSG_MESH_EVENT))?;
+            let json = env.new_string(&payload)?;
+            env.set_field(&message, "obj", "Ljava/lang/Object;", JValue::from(&json))?;
+
+            let args = [JValue::from(&message).as_jni()];
+            unsafe {
+                env.call_method_unchecked(
+                    &self.handler,
+                    self.handler_send_message,
+                    ReturnType::Primitive(Primitive::Boolean),
+                    &args,
+                )
+            }
+            .map(|_| ())
+        });
+    }
+
+    /// Attach the calling thread, run `f`, and swallow/clear any JNI
+    /// exception or attach failure rather than propagate it - same
+    /// reasoning as `MeshListener::call`: a post fired from a background
+    /// thread has no caller to return an error to.
+    fn call(&self, f: impl FnOnce(&mut JNIEnv) -> Result<(), jni::errors::Error>) {
+        let mut env = match self.vm.attach_current_thread() {
+            Ok(env) => env,
+            Err(_) => return,
+        };
+        if f(&mut env).is_err() {
+            let _ = env.exception_clear();
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Noise XX handshake and persisted static identity for mesh sessions
+//!
+//! The mesh transport used to be a plaintext WebSocket secured only by a
+//! bearer token, with `Peer.node_id` nothing more than a self-reported
+//! string. This layers a Noise XX handshake over the socket established in
+//! `join_mesh`: each node carries a static X25519 keypair persisted under
+//! its data directory, the three XX handshake messages are exchanged as
+//! binary frames before any `join`/`gossip` JSON flows, and every frame
+//! after that is encrypted/decrypted through the resulting transport state
+//! (one cipher state per direction, each with its own nonce counter).
+//!
+//! Because the coordinator endpoint - not a specific peer `NodeId` - is the
+//! only thing we know before the handshake completes, authentication is
+//! trust-on-first-use: the remote static key we see the first time we dial
+//! a given endpoint is pinned in `known_hosts`, and a later session to the
+//! same endpoint presenting a different key is rejected outright, the same
+//! way an SSH host key pin works.
+
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use snow::{Builder, TransportState};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const NOISE_PATTERN: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+const IDENTITY_FILE: &str = "noise_identity.key";
+const KNOWN_HOSTS_FILE: &str = "noise_known_hosts.json";
+
+#[derive(Debug)]
+pub enum NoiseError {
+    Io(io::Error),
+    Handshake(String),
+    KeyMismatch { endpoint: String },
+    UnexpectedFrame,
+}
+
+impl std::fmt::Display for NoiseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoiseError::Io(e) => write!(f, "noise identity I/O error: {}", e),
+            NoiseError::Handshake(msg) => write!(f, "noise handshake failed: {}", msg),
+            NoiseError::KeyMismatch { endpoint } => write!(
+                f,
+                "remote static key for {} does not match the previously pinned key",
+                endpoint
+            ),
+            NoiseError::UnexpectedFrame => write!(f, "unexpected frame during noise handshake"),
+        }
+    }
+}
+
+impl std::error::Error for NoiseError {}
+
+impl From<io::Error> for NoiseError {
+    fn from(e: io::Error) -> Self {
+        NoiseError::Io(e)
+    }
+}
+
+impl From<snow::Error> for NoiseError {
+    fn from(e: snow::Error) -> Self {
+        NoiseError::Handshake(e.to_string())
+    }
+}
+
+/// This node's static X25519 keypair, persisted under its data directory so
+/// the same identity survives process restarts and reconnects.
+pub struct NoiseIdentity {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl NoiseIdentity {
+    pub fn load_or_generate(data_dir: &str) -> Result<Self, NoiseError> {
+        let path = Self::key_path(data_dir);
+        let secret = match fs::read(&path) {
+            Ok(bytes) if bytes.len() == 32 => {
+                let mut raw = [0u8; 32];
+                raw.copy_from_slice(&bytes);
+                StaticSecret::from(raw)
+            }
+            _ => {
+                let secret = StaticSecret::random_from_rng(OsRng);
+                if let Some(parent) = path.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                fs::write(&path, secret.to_bytes())?;
+                secret
+            }
+        };
+        let public = PublicKey::from(&secret);
+        Ok(Self { secret, public })
+    }
+
+    fn key_path(data_dir: &str) -> PathBuf {
+        Path::new(data_dir).join(IDENTITY_FILE)
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex_encode(self.public.as_bytes())
+    }
+}
+
+/// Trust-on-first-use pinning of the remote static key seen on first
+/// contact with a given mesh endpoint.
+pub struct KnownHosts {
+    data_dir: String,
+    pinned: HashMap<String, [u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KnownHostsFile(HashMap<String, String>);
+
+impl KnownHosts {
+    pub fn load(data_dir: &str) -> Self {
+        let path = Path::new(data_dir).join(KNOWN_HOSTS_FILE);
+        let pinned = fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<KnownHostsFile>(&s).ok())
+            .map(|raw| {
+                raw.0
+                    .into_iter()
+                    .filter_map(|(endpoint, hex)| hex_decode(&hex).map(|key| (endpoint, key)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { data_dir: data_dir.to_string(), pinned }
+    }
+
+    /// Verify `remote_key` against any key already pinned for `endpoint`,
+    /// pinning it (and persisting the pin) the first time we see this
+    /// endpoint.
+    pub fn verify_or_pin(&mut self, endpoint: &str, remote_key: [u8; 32]) -> Result<(), NoiseError> {
+        match self.pinned.get(endpoint) {
+            Some(expected) if *expected != remote_key => Err(NoiseError::KeyMismatch {
+                endpoint: endpoint.to_string(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.pinned.insert(endpoint.to_string(), remote_key);
+                self.save();
+                Ok(())
+            }
+        }
+    }
+
+    fn save(&self) {
+        let path = Path::new(&self.data_dir).join(KNOWN_HOSTS_FILE);
+        let raw = KnownHostsFile(
+            self.pinned
+                .iter()
+                .map(|(endpoint, key)| (endpoint.clone(), hex_encode(key)))
+                .collect(),
+        );
+        if let Ok(json) = serde_json::to_string(&raw) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Option<[u8; 32]> {
+    if s.len() != 64 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+/// Established Noise transport: one cipher state per direction, each with
+/// its own nonce counter, produced once the XX handshake completes.
+pub struct NoiseSession {
+    transport: TransportState,
+}
+
+impl NoiseSession {
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let mut buf = vec![0u8; plaintext.len() + 16];
+        let len = self.transport.write_message(plaintext, &mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let mut buf = vec![0u8; ciphertext.len()];
+        let len = self.transport.read_message(ciphertext, &mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+/// Run the Noise XX handshake as the initiator - we always dial out to the
+/// coordinator, so we're never the responder here - exchanging the three
+/// handshake messages as binary WebSocket frames. Returns the resulting
+/// transport session plus the remote's static public key, which the caller
+/// checks against `KnownHosts` before trusting anything that follows.
+///
+/// `HandshakeState` zeroizes its ephemeral keys on drop, so once this
+/// returns (dropping `state` in favor of the completed `TransportState`)
+/// nothing but the long-lived static keys and the derived transport
+/// ciphers remain in memory.
+pub fn run_xx_handshake(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    identity: &NoiseIdentity,
+) -> Result<(NoiseSession, [u8; 32]), NoiseError> {
+    let params = NOISE_PATTERN
+        .parse()
+        .map_err(|_| NoiseError::Handshake("invalid noise pattern".to_string()))?;
+    let mut state = Builder::new(params)
+        .local_private_key(&identity.secret.to_bytes())
+        .build_initiator()?;
+
+    let mut buf = vec![0u8; 1024];
+
+    // -> e
+    let len = state.write_message(&[], &mut buf)?;
+    send_binary(socket, &buf[..len])?;
+
+    // <- e, ee, s, es
+    let inbound = read_binary(socket)?;
+    let mut discard = vec![0u8; inbound.len()];
+    state.read_message(&inbound, &mut discard)?;
+
+    // -> s, se
+    let len = state.write_message(&[], &mut buf)?;
+    send_binary(socket, &buf[..len])?;
+
+    let remote_static = state
+        .get_remote_static()
+        .ok_or_else(|| NoiseError::Handshake("peer did not present a static key".to_string()))?;
+    let mut remote_key = [0u8; 32];
+    remote_key.copy_from_slice(remote_static);
+
+    let transport = state.into_transport_mode()?;
+    Ok((NoiseSession { transport }, remote_key))
+}
+
+fn send_binary(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>, bytes: &[u8]) -> Result<(), NoiseError> {
+    socket
+        .send(Message::Binary(bytes.to_vec()))
+        .map_err(|e| NoiseError::Handshake(e.to_string()))
+}
+
+fn read_binary(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>) -> Result<Vec<u8>, NoiseError> {
+    match socket.read().map_err(|e| NoiseError::Handshake(e.to_string()))? {
+        Message::Binary(bytes) => Ok(bytes),
+        _ => Err(NoiseError::UnexpectedFrame),
+    }
+}
@@ -0,0 +1,462 @@
+//! Typed wire protocol for the mesh connection
+//!
+//! `Peer::to_json`, `status_json`, and every `join`/`discover`/`gossip`
+//! message used to be built with `format!` string interpolation, which
+//! silently produces invalid JSON the moment a `name`, `address`, or gossip
+//! `payload` contains a quote, backslash, or newline. Everything that
+//! crosses the wire - or the JNI boundary as a JSON string - is now a
+//! `serde::Serialize`/`Deserialize` type instead, so encoding and decoding
+//! are correct by construction.
+//!
+//! `MeshMessage` is the single type for every frame exchanged with the
+//! coordinator, tagged by its `"type"` field. It can be encoded either as
+//! JSON (the default, and the only format the coordinator has ever had to
+//! support) or as the compact protobuf framing in `wire_pb`, selected per
+//! connection via `WireFormat` - gossip and capability lists add up fast
+//! over a phone radio, and the binary framing is meaningfully smaller for
+//! the same information.
+
+use crate::wire_pb;
+use serde::{Deserialize, Serialize};
+
+/// Where a `Peer` entry was learned from, so the UI can tell a peer reached
+/// through the mesh coordinator apart from one found on the local network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerSource {
+    Mesh,
+    Lan,
+}
+
+impl PeerSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeerSource::Mesh => "mesh",
+            PeerSource::Lan => "lan",
+        }
+    }
+}
+
+fn default_node_id() -> String {
+    "unknown".to_string()
+}
+
+fn default_name() -> String {
+    "Unknown Node".to_string()
+}
+
+/// Peer frames only ever arrive over the mesh connection; LAN peers are
+/// constructed directly by `discovery` instead, so this is the right
+/// default for anything decoded off the wire.
+fn default_peer_source() -> PeerSource {
+    PeerSource::Mesh
+}
+
+/// Connection lifecycle of a directly-dialed peer (`peer_conn::PeerConnectionManager`).
+/// Peers learned from the mesh coordinator or mDNS are never anything but
+/// `Connected`/`Disconnected` - there's no handshake to negotiate with them,
+/// since the coordinator already vouches for them and mDNS just reports
+/// reachability - so this only really moves through all four states for a
+/// peer reached via `connect_to_peer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerState {
+    Disconnected,
+    Connecting,
+    HandshakeSent,
+    Connected,
+}
+
+fn default_peer_state() -> PeerState {
+    PeerState::Disconnected
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Peer {
+    #[serde(default = "default_node_id")]
+    pub node_id: String,
+    #[serde(default = "default_name")]
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub connected: bool,
+    #[serde(default)]
+    pub latency_ms: Option<u32>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default = "default_peer_source")]
+    pub source: PeerSource,
+    /// Lifecycle state; see `PeerState`. mDNS peers are `Connected`; a peer
+    /// decoded from a JSON frame without a `state` field is `Disconnected`,
+    /// and one decoded from protobuf takes its state from `connected`.
+    #[serde(default = "default_peer_state")]
+    pub state: PeerState,
+    /// Feature bitfield negotiated during the handshake (`peer_conn`'s
+    /// `FEATURE_*` constants ANDed with the peer's advertised set); `0` until
+    /// a handshake has completed, and always `0` for non-direct peers.
+    #[serde(default)]
+    pub features: u32,
+}
+
+impl Peer {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// One line of the handshake `connect_to_peer` performs with a freshly
+/// dialed peer: each side sends its protocol version, `node_id`, and
+/// supported feature bitfield as a single JSON line, so a peer running an
+/// incompatible version can be rejected with a clear error before it's ever
+/// marked `Connected`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandshakeInit {
+    pub version: u32,
+    pub node_id: String,
+    pub features: u32,
+}
+
+impl HandshakeInit {
+    pub fn to_line(&self) -> String {
+        format!("{}\n", serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string()))
+    }
+
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line.trim_end()).map_err(|e| e.to_string())
+    }
+}
+
+/// Snapshot returned by `AndroidNode::status_json`.
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub node_id: String,
+    pub is_running: bool,
+    pub capabilities_count: usize,
+    pub connected_peers: usize,
+    pub mesh_connected: bool,
+    pub mesh_id: Option<String>,
+    pub mesh_name: Option<String>,
+    pub local_discovery: bool,
+}
+
+impl StatusReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Every frame exchanged with the mesh coordinator, tagged by its `"type"`
+/// field. Request/response capability payloads stay as an untyped
+/// `serde_json::Value`, since their shape is defined by whatever capability
+/// is being called, not by this protocol.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MeshMessage {
+    Join {
+        token: String,
+        node_id: String,
+        capabilities: Vec<String>,
+    },
+    Joined {
+        #[serde(default)]
+        mesh_id: Option<String>,
+        #[serde(default)]
+        mesh_name: Option<String>,
+    },
+    Welcome {
+        #[serde(default)]
+        mesh_id: Option<String>,
+        #[serde(default)]
+        mesh_name: Option<String>,
+    },
+    Discover {
+        node_id: String,
+    },
+    Peers {
+        peers: Vec<Peer>,
+    },
+    PeerJoined {
+        peer: Peer,
+    },
+    PeerLeft {
+        node_id: String,
+    },
+    Gossip {
+        from: String,
+        payload: String,
+    },
+    Request {
+        id: u64,
+        capability: String,
+        payload: serde_json::Value,
+    },
+    Response {
+        id: u64,
+        payload: serde_json::Value,
+    },
+    Error {
+        message: String,
+    },
+    /// One chunk of a `streaming::StreamManager` transfer. `data` is
+    /// base64-encoded in the JSON encoding (raw `bytes` in the protobuf one)
+    /// so an arbitrary binary payload survives the trip intact.
+    Stream {
+        id: u64,
+        seq: u64,
+        from: String,
+        to: String,
+        #[serde(with = "b64")]
+        data: Vec<u8>,
+        fin: bool,
+    },
+    /// Cumulative ack from the receiver of a `Stream` transfer: every chunk
+    /// `0..=seq` has been received, letting the sender advance its window.
+    StreamAck {
+        id: u64,
+        seq: u64,
+    },
+}
+
+/// Base64 encoding for `Stream::data`, so a gossip-sized binary chunk
+/// survives a round trip through the JSON encoding intact rather than
+/// being mangled as if it were UTF-8 text.
+pub(crate) mod b64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&super::base64_encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        super::base64_decode(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+const B64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        out.push(B64_ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(B64_ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            B64_ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            B64_ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+pub(crate) fn base64_decode(s: &str) -> Result<Vec<u8>, String> {
+    fn sextet(c: u8) -> Result<u8, String> {
+        match c {
+            b'A'..=b'Z' => Ok(c - b'A'),
+            b'a'..=b'z' => Ok(c - b'a' + 26),
+            b'0'..=b'9' => Ok(c - b'0' + 52),
+            b'+' => Ok(62),
+            b'/' => Ok(63),
+            _ => Err(format!("invalid base64 byte: {}", c)),
+        }
+    }
+
+    let bytes = s.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() * 3 / 4);
+    for group in bytes.chunks(4) {
+        let sextets: Vec<u8> = group.iter().map(|&c| sextet(c)).collect::<Result<_, _>>()?;
+        out.push((sextets[0] << 2) | (sextets.get(1).copied().unwrap_or(0) >> 4));
+        if sextets.len() > 2 {
+            out.push((sextets[1] << 4) | (sextets[2] >> 2));
+        }
+        if sextets.len() > 3 {
+            out.push((sextets[2] << 6) | sextets[3]);
+        }
+    }
+    Ok(out)
+}
+
+/// Binary framing selectable per mesh connection, as an alternative to JSON
+/// inside the Noise-encrypted payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireFormat {
+    Json,
+    Protobuf,
+}
+
+pub fn encode_message(msg: &MeshMessage, format: WireFormat) -> Result<Vec<u8>, String> {
+    match format {
+        WireFormat::Json => serde_json::to_vec(msg).map_err(|e| e.to_string()),
+        WireFormat::Protobuf => {
+            let proto: wire_pb::MeshMessage = msg.clone().into();
+            let mut buf = Vec::new();
+            prost::Message::encode(&proto, &mut buf).map_err(|e| e.to_string())?;
+            Ok(buf)
+        }
+    }
+}
+
+pub fn decode_message(bytes: &[u8], format: WireFormat) -> Result<MeshMessage, String> {
+    match format {
+        WireFormat::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+        WireFormat::Protobuf => {
+            let proto = <wire_pb::MeshMessage as prost::Message>::decode(bytes)
+                .map_err(|e| e.to_string())?;
+            proto.try_into()
+        }
+    }
+}
+
+impl From<PeerSource> for wire_pb::PeerSource {
+    fn from(source: PeerSource) -> Self {
+        match source {
+            PeerSource::Mesh => wire_pb::PeerSource::Mesh,
+            PeerSource::Lan => wire_pb::PeerSource::Lan,
+        }
+    }
+}
+
+impl From<wire_pb::PeerSource> for PeerSource {
+    fn from(source: wire_pb::PeerSource) -> Self {
+        match source {
+            wire_pb::PeerSource::Mesh => PeerSource::Mesh,
+            wire_pb::PeerSource::Lan => PeerSource::Lan,
+        }
+    }
+}
+
+impl From<Peer> for wire_pb::Peer {
+    fn from(peer: Peer) -> Self {
+        wire_pb::Peer {
+            node_id: peer.node_id,
+            name: peer.name,
+            address: peer.address,
+            connected: peer.connected,
+            latency_ms: peer.latency_ms,
+            capabilities: peer.capabilities,
+            source: wire_pb::PeerSource::from(peer.source) as i32,
+        }
+    }
+}
+
+impl TryFrom<wire_pb::Peer> for Peer {
+    type Error = String;
+
+    fn try_from(peer: wire_pb::Peer) -> Result<Self, String> {
+        let source = wire_pb::PeerSource::try_from(peer.source)
+            .unwrap_or(wire_pb::PeerSource::Mesh)
+            .into();
+        Ok(Peer {
+            node_id: peer.node_id,
+            name: peer.name,
+            address: peer.address,
+            connected: peer.connected,
+            latency_ms: peer.latency_ms,
+            capabilities: peer.capabilities,
+            source,
+            // The coordinator only ever reports peers it already considers
+            // joined; `state`/`features` are purely local bookkeeping for
+            // peers dialed directly via `connect_to_peer`, so a peer coming
+            // off the wire gets the state its `connected` flag implies and
+            // no negotiated features.
+            state: if peer.connected { PeerState::Connected } else { PeerState::Disconnected },
+            features: 0,
+        })
+    }
+}
+
+impl From<MeshMessage> for wire_pb::MeshMessage {
+    fn from(msg: MeshMessage) -> Self {
+        use wire_pb::mesh_message::Body;
+        let body = match msg {
+            MeshMessage::Join { token, node_id, capabilities } => {
+                Body::Join(wire_pb::Join { token, node_id, capabilities })
+            }
+            MeshMessage::Joined { mesh_id, mesh_name } => {
+                Body::Joined(wire_pb::Joined { mesh_id, mesh_name })
+            }
+            MeshMessage::Welcome { mesh_id, mesh_name } => {
+                Body::Welcome(wire_pb::Joined { mesh_id, mesh_name })
+            }
+            MeshMessage::Discover { node_id } => Body::Discover(wire_pb::Discover { node_id }),
+            MeshMessage::Peers { peers } => Body::Peers(wire_pb::Peers {
+                peers: peers.into_iter().map(Into::into).collect(),
+            }),
+            MeshMessage::PeerJoined { peer } => {
+                Body::PeerJoined(wire_pb::PeerJoined { peer: Some(peer.into()) })
+            }
+            MeshMessage::PeerLeft { node_id } => Body::PeerLeft(wire_pb::PeerLeft { node_id }),
+            MeshMessage::Gossip { from, payload } => Body::Gossip(wire_pb::Gossip { from, payload }),
+            MeshMessage::Request { id, capability, payload } => Body::Request(wire_pb::Request {
+                id,
+                capability,
+                payload_json: payload.to_string(),
+            }),
+            MeshMessage::Response { id, payload } => Body::Response(wire_pb::Response {
+                id,
+                payload_json: payload.to_string(),
+            }),
+            MeshMessage::Error { message } => Body::Error(wire_pb::Error { message }),
+            MeshMessage::Stream { id, seq, from, to, data, fin } => {
+                Body::Stream(wire_pb::Stream { id, seq, from, to, data, fin })
+            }
+            MeshMessage::StreamAck { id, seq } => Body::StreamAck(wire_pb::StreamAck { id, seq }),
+        };
+        wire_pb::MeshMessage { body: Some(body) }
+    }
+}
+
+impl TryFrom<wire_pb::MeshMessage> for MeshMessage {
+    type Error = String;
+
+    fn try_from(msg: wire_pb::MeshMessage) -> Result<Self, String> {
+        use wire_pb::mesh_message::Body;
+        match msg.body.ok_or("empty protobuf mesh message")? {
+            Body::Join(j) => Ok(MeshMessage::Join {
+                token: j.token,
+                node_id: j.node_id,
+                capabilities: j.capabilities,
+            }),
+            Body::Joined(j) => Ok(MeshMessage::Joined { mesh_id: j.mesh_id, mesh_name: j.mesh_name }),
+            Body::Welcome(j) => Ok(MeshMessage::Welcome { mesh_id: j.mesh_id, mesh_name: j.mesh_name }),
+            Body::Discover(d) => Ok(MeshMessage::Discover { node_id: d.node_id }),
+            Body::Peers(p) => Ok(MeshMessage::Peers {
+                peers: p.peers.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+            }),
+            Body::PeerJoined(p) => Ok(MeshMessage::PeerJoined {
+                peer: p.peer.ok_or("missing peer")?.try_into()?,
+            }),
+            Body::PeerLeft(p) => Ok(MeshMessage::PeerLeft { node_id: p.node_id }),
+            Body::Gossip(g) => Ok(MeshMessage::Gossip { from: g.from, payload: g.payload }),
+            Body::Request(r) => Ok(MeshMessage::Request {
+                id: r.id,
+                capability: r.capability,
+                payload: serde_json::from_str(&r.payload_json).map_err(|e| e.to_string())?,
+            }),
+            Body::Response(r) => Ok(MeshMessage::Response {
+                id: r.id,
+                payload: serde_json::from_str(&r.payload_json).map_err(|e| e.to_string())?,
+            }),
+            Body::Error(e) => Ok(MeshMessage::Error { message: e.message }),
+            Body::Stream(s) => Ok(MeshMessage::Stream {
+                id: s.id,
+                seq: s.seq,
+                from: s.from,
+                to: s.to,
+                data: s.data,
+                fin: s.fin,
+            }),
+            Body::StreamAck(s) => Ok(MeshMessage::StreamAck { id: s.id, seq: s.seq }),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Kademlia-style local routing table
+//!
+//! `route_intent_json` previously only ever succeeded for capabilities
+//! registered on this node, and `discover_peers` just cached whatever flat
+//! list the mesh coordinator handed back. This gives the node a proper
+//! distributed routing table keyed by XOR distance from our own `NodeId`,
+//! so a capability lookup can be aimed at the peer(s) most likely to know
+//! about - or host - a provider several hops away.
+//!
+//! Peers are bucketed by the position of the highest differing bit between
+//! their id and ours, exactly as in the original Kademlia paper: bucket 0
+//! holds the peers furthest from us (differ in the top bit), bucket 127
+//! holds the peers nearest to us.
+
+use atmosphere_core::NodeId;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Max peers retained per bucket.
+const K: usize = 20;
+
+/// Closest-known-peers fanned out to on each iterative lookup round.
+const ALPHA: usize = 3;
+
+/// Hard cap on lookup rounds so a partitioned or looping mesh can't spin
+/// forever chasing a "closer" node that never converges.
+const MAX_LOOKUP_ROUNDS: usize = 20;
+
+/// `NodeId` wraps a 128-bit UUID, so there are 128 possible buckets.
+const ID_BITS: usize = 128;
+
+#[derive(Clone, Debug)]
+pub struct RoutingPeer {
+    pub node_id: NodeId,
+    pub address: String,
+    pub last_seen: Instant,
+}
+
+struct KBucket {
+    peers: Vec<RoutingPeer>,
+}
+
+impl KBucket {
+    fn new() -> Self {
+        Self { peers: Vec::new() }
+    }
+
+    /// Refresh an existing entry to the back of the bucket (most-recently-seen),
+    /// or insert a new one - evicting the least-recently-seen entry first if
+    /// the bucket is already full.
+    fn touch_or_insert(&mut self, peer: RoutingPeer) {
+        if let Some(pos) = self.peers.iter().position(|p| p.node_id == peer.node_id) {
+            self.peers.remove(pos);
+            self.peers.push(peer);
+            return;
+        }
+
+        if self.peers.len() >= K {
+            // Least-recently-seen sits at the front; a full liveness-check
+            // eviction policy would ping it before dropping it, but we have
+            // no transport-level ping yet, so we evict optimistically.
+            self.peers.remove(0);
+        }
+        self.peers.push(peer);
+    }
+
+    fn remove(&mut self, node_id: &NodeId) {
+        self.peers.retain(|p| &p.node_id != node_id);
+    }
+}
+
+/// Local view of the mesh's address space, used to steer capability lookups
+/// toward the peers most likely to be near - or to know about - a provider.
+pub struct RoutingTable {
+    local_id: NodeId,
+    buckets: Vec<KBucket>,
+}
+
+impl RoutingTable {
+    pub fn new(local_id: NodeId) -> Self {
+        Self {
+            local_id,
+            buckets: (0..ID_BITS).map(|_| KBucket::new()).collect(),
+        }
+    }
+
+    /// Bucket index for `other`, or `None` if `other` is our own id (we
+    /// never insert ourselves into the table).
+    fn bucket_index(&self, other: &NodeId) -> Option<usize> {
+        if other == &self.local_id {
+            return None;
+        }
+        Some(highest_differing_bit(&self.local_id, other))
+    }
+
+    pub fn insert(&mut self, node_id: NodeId, address: String) {
+        if let Some(idx) = self.bucket_index(&node_id) {
+            self.buckets[idx].touch_or_insert(RoutingPeer {
+                node_id,
+                address,
+                last_seen: Instant::now(),
+            });
+        }
+    }
+
+    pub fn remove(&mut self, node_id: &NodeId) {
+        if let Some(idx) = self.bucket_index(node_id) {
+            self.buckets[idx].remove(node_id);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(|b| b.peers.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All known peers, nearest-to-`target`-first.
+    fn sorted_by_distance(&self, target: &NodeId) -> Vec<RoutingPeer> {
+        let mut all: Vec<RoutingPeer> = self
+            .buckets
+            .iter()
+            .flat_map(|b| b.peers.iter().cloned())
+            .collect();
+        all.sort_by_key(|p| xor_distance(&p.node_id, target));
+        all
+    }
+
+    /// Up to `count` peers closest to `target`.
+    pub fn closest(&self, target: &NodeId, count: usize) -> Vec<RoutingPeer> {
+        self.sorted_by_distance(target).into_iter().take(count).collect()
+    }
+
+    /// Iterative closest-node lookup toward `target`: start from the
+    /// `ALPHA` closest known peers and keep folding in newly-discovered
+    /// candidates (via `query`) until a round produces no peer closer than
+    /// the best one already known, or `MAX_LOOKUP_ROUNDS` is hit.
+    ///
+    /// `query` asks a single peer for the peers *it* knows that are closer
+    /// to `target`; it is expected to return an empty vec for peers we
+    /// can't currently reach over the RPC layer.
+    pub fn iterative_lookup<F>(&self, target: &NodeId, mut query: F) -> Vec<RoutingPeer>
+    where
+        F: FnMut(&RoutingPeer) -> Vec<RoutingPeer>,
+    {
+        let mut known = self.sorted_by_distance(target);
+        let mut queried = std::collections::HashSet::new();
+
+        for _ in 0..MAX_LOOKUP_ROUNDS {
+            let round_candidates: Vec<RoutingPeer> = known
+                .iter()
+                .filter(|p| !queried.contains(&p.node_id))
+                .take(ALPHA)
+                .cloned()
+                .collect();
+
+            if round_candidates.is_empty() {
+                break;
+            }
+
+            let closest_before = known.first().map(|p| xor_distance(&p.node_id, target));
+            let mut discovered_any_closer = false;
+
+            for peer in &round_candidates {
+                queried.insert(peer.node_id);
+                for candidate in query(peer) {
+                    if candidate.node_id == self.local_id {
+                        continue;
+                    }
+                    if !known.iter().any(|p| p.node_id == candidate.node_id) {
+                        known.push(candidate);
+                        discovered_any_closer = true;
+                    }
+                }
+            }
+
+            known.sort_by_key(|p| xor_distance(&p.node_id, target));
+
+            let closest_after = known.first().map(|p| xor_distance(&p.node_id, target));
+            if !discovered_any_closer || closest_after == closest_before {
+                break;
+            }
+        }
+
+        known
+    }
+}
+
+fn xor_bytes(a: &NodeId, b: &NodeId) -> [u8; 16] {
+    let a_bytes = a.as_uuid().as_bytes();
+    let b_bytes = b.as_uuid().as_bytes();
+    let mut out = [0u8; 16];
+    for i in 0..16 {
+        out[i] = a_bytes[i] ^ b_bytes[i];
+    }
+    out
+}
+
+/// XOR distance between two ids as a big-endian byte array, so comparing
+/// two distances with `Ord` matches comparing them as 128-bit integers.
+fn xor_distance(a: &NodeId, b: &NodeId) -> [u8; 16] {
+    xor_bytes(a, b)
+}
+
+/// Bucket index: the position (0 = most significant) of the highest bit at
+/// which `a` and `b` differ.
+fn highest_differing_bit(a: &NodeId, b: &NodeId) -> usize {
+    let dist = xor_bytes(a, b);
+    for (byte_idx, byte) in dist.iter().enumerate() {
+        if *byte != 0 {
+            return byte_idx * 8 + byte.leading_zeros() as usize;
+        }
+    }
+    // a == b; bucket_index() already filters this out, but fall back to the
+    // nearest bucket rather than panicking if it ever happens.
+    ID_BITS - 1
+}
+
+/// Deterministically maps a capability name onto the same 128-bit id space
+/// `NodeId` occupies, so routing table distance comparisons make sense for
+/// "find peers near this capability" the same way they do for peer ids.
+pub fn capability_id(capability: &str) -> NodeId {
+    NodeId::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, capability.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_id() -> NodeId {
+        NodeId::new()
+    }
+
+    #[test]
+    fn test_never_inserts_self() {
+        let local = peer_id();
+        let mut table = RoutingTable::new(local);
+        table.insert(local, "self:0".to_string());
+        assert_eq!(table.len(), 0);
+    }
+
+    #[test]
+    fn test_insert_and_closest() {
+        let local = peer_id();
+        let mut table = RoutingTable::new(local);
+        let a = peer_id();
+        let b = peer_id();
+        table.insert(a, "a:1".to_string());
+        table.insert(b, "b:1".to_string());
+
+        let target = capability_id("llm.chat");
+        let closest = table.closest(&target, 1);
+        assert_eq!(closest.len(), 1);
+        assert!(closest[0].node_id == a || closest[0].node_id == b);
+    }
+
+    #[test]
+    fn test_bucket_eviction_keeps_bucket_at_k() {
+        let local = NodeId::from_uuid(Uuid::from_bytes([0u8; 16]));
+        let mut table = RoutingTable::new(local);
+        // Every peer below shares the same highest differing bit (the top
+        // bit of the last byte), so they all land in the same bucket - this
+        // exercises the per-bucket K cap directly.
+        for i in 0..(K + 5) {
+            let mut bytes = [0u8; 16];
+            bytes[15] = 0x80 | (i as u8);
+            table.insert(NodeId::from_uuid(Uuid::from_bytes(bytes)), format!("peer:{i}"));
+        }
+        assert_eq!(table.len(), K);
+    }
+
+    #[test]
+    fn test_iterative_lookup_converges_without_remote_peers() {
+        let local = peer_id();
+        let mut table = RoutingTable::new(local);
+        table.insert(peer_id(), "a:1".to_string());
+
+        let target = capability_id("llm.chat");
+        let result = table.iterative_lookup(&target, |_peer| Vec::new());
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn test_remove() {
+        let local = peer_id();
+        let mut table = RoutingTable::new(local);
+        let a = peer_id();
+        table.insert(a, "a:1".to_string());
+        assert_eq!(table.len(), 1);
+        table.remove(&a);
+        assert_eq!(table.len(), 0);
+    }
+}
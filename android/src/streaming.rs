@@ -0,0 +1,312 @@
+//! Chunked streaming transfers with sliding-window flow control
+//!
+//! `send_gossip` only ever pushes a single frame, which is unusable for
+//! moving a file or a model weights blob between peers - a multi-megabyte
+//! payload doesn't fit in one WebSocket message, and a fast sender has no
+//! signal telling it to slow down for a slow mobile link. This splits an
+//! outbound payload into fixed-size chunks, sends each as a
+//! `MeshMessage::Stream` frame under a per-transfer id, and throttles
+//! further sends on a sliding window of unacknowledged chunks, advanced by
+//! `MeshMessage::StreamAck` frames from the receiver. The receiving side
+//! reassembles chunks by sequence, de-duplicating any retransmit, and
+//! gives up on a transfer that never sees its `fin` chunk.
+
+use crate::protocol::MeshMessage;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Payload bytes per `Stream` frame.
+pub const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Max unacknowledged chunks in flight at a time, bounding how far a fast
+/// sender can outrun a slow mobile link before it blocks.
+const WINDOW_SIZE: u64 = 8;
+
+/// How often a blocked sender re-checks the window and cancellation flag.
+const SEND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long the receiver waits for a new chunk before giving up on a
+/// stalled transfer and dropping its buffered chunks.
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub type StreamId = u64;
+
+struct OutboundState {
+    acked_seq: Mutex<Option<u64>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct InboundState {
+    chunks: BTreeMap<u64, Vec<u8>>,
+    fin_seq: Option<u64>,
+    from: String,
+    last_activity: Instant,
+}
+
+impl InboundState {
+    fn new(from: String) -> Self {
+        Self { chunks: BTreeMap::new(), fin_seq: None, from, last_activity: Instant::now() }
+    }
+
+    /// Highest seq such that every chunk `0..=seq` has already arrived.
+    fn contiguous_high(&self) -> Option<u64> {
+        let mut high = None;
+        for (i, &seq) in self.chunks.keys().enumerate() {
+            if seq != i as u64 {
+                break;
+            }
+            high = Some(seq);
+        }
+        high
+    }
+
+    /// The reassembled payload, once every chunk through `fin_seq` has
+    /// arrived contiguously.
+    fn try_reassemble(&self) -> Option<Vec<u8>> {
+        let fin_seq = self.fin_seq?;
+        if self.contiguous_high()? < fin_seq {
+            return None;
+        }
+        Some(self.chunks.values().flatten().copied().collect())
+    }
+}
+
+/// Per-connection outbound/inbound stream bookkeeping. Lives alongside the
+/// rest of `MeshShared` so the reader thread can apply inbound `Stream`/
+/// `StreamAck` frames, and the background thread `send` spawns can see acks
+/// land without round-tripping back through `AndroidNode`.
+#[derive(Default)]
+pub struct StreamManager {
+    next_id: AtomicU64,
+    outbound: Mutex<HashMap<StreamId, Arc<OutboundState>>>,
+    inbound: Mutex<HashMap<StreamId, InboundState>>,
+}
+
+impl StreamManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split `data` into `CHUNK_SIZE` chunks and send them as a new stream
+    /// from `from` to `to` via `enqueue`, on a background thread that
+    /// blocks whenever `WINDOW_SIZE` chunks are outstanding unacknowledged.
+    /// Returns the new stream's id immediately; the transfer itself
+    /// proceeds asynchronously and can be stopped early with `cancel`.
+    pub fn send(
+        self: &Arc<Self>,
+        from: String,
+        to: String,
+        data: Vec<u8>,
+        enqueue: impl Fn(MeshMessage) -> Result<(), String> + Send + 'static,
+    ) -> StreamId {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.outbound.lock().insert(
+            id,
+            Arc::new(OutboundState { acked_seq: Mutex::new(None), cancelled: cancelled.clone() }),
+        );
+
+        // An empty payload is still a one-chunk transfer: a single `fin`
+        // frame with no data, so the receiver sees a stream that completes
+        // to zero bytes rather than one that never starts.
+        let chunks: Vec<Vec<u8>> = if data.is_empty() {
+            vec![Vec::new()]
+        } else {
+            data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect()
+        };
+        let last_seq = (chunks.len() - 1) as u64;
+
+        let manager = self.clone();
+        thread::spawn(move || {
+            for (seq, chunk) in chunks.into_iter().enumerate() {
+                let seq = seq as u64;
+                loop {
+                    if cancelled.load(Ordering::Relaxed) {
+                        manager.outbound.lock().remove(&id);
+                        return;
+                    }
+                    let window_base = manager.acked_seq(id).map(|a| a + 1).unwrap_or(0);
+                    if seq < window_base + WINDOW_SIZE {
+                        break;
+                    }
+                    thread::sleep(SEND_POLL_INTERVAL);
+                }
+
+                let msg = MeshMessage::Stream {
+                    id,
+                    seq,
+                    from: from.clone(),
+                    to: to.clone(),
+                    data: chunk,
+                    fin: seq == last_seq,
+                };
+                if enqueue(msg).is_err() {
+                    break;
+                }
+            }
+            manager.outbound.lock().remove(&id);
+        });
+
+        id
+    }
+
+    fn acked_seq(&self, id: StreamId) -> Option<u64> {
+        self.outbound.lock().get(&id).and_then(|s| *s.acked_seq.lock())
+    }
+
+    /// Apply an inbound cumulative `StreamAck`, advancing the sender's
+    /// window. A miss - the stream already finished or was cancelled - is
+    /// simply dropped.
+    pub fn ack(&self, id: StreamId, seq: u64) {
+        if let Some(state) = self.outbound.lock().get(&id) {
+            let mut acked = state.acked_seq.lock();
+            if acked.map(|a| seq > a).unwrap_or(true) {
+                *acked = Some(seq);
+            }
+        }
+    }
+
+    /// Cancel an in-flight outbound stream; its background thread notices
+    /// on its next wakeup and sends no further chunks.
+    pub fn cancel(&self, id: StreamId) {
+        if let Some(state) = self.outbound.lock().get(&id) {
+            state.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Apply an inbound `Stream` chunk, de-duplicating by sequence number
+    /// in case the sender (or an intermediary) retransmits one. Returns the
+    /// cumulative seq to ack back to the sender, plus the reassembled
+    /// payload once `fin`'s chunk and everything before it have arrived -
+    /// at which point the stream's bookkeeping is torn down.
+    pub fn receive_chunk(
+        &self,
+        id: StreamId,
+        seq: u64,
+        from: String,
+        data: Vec<u8>,
+        fin: bool,
+    ) -> (u64, Option<Vec<u8>>) {
+        let mut inbound = self.inbound.lock();
+        let state = inbound.entry(id).or_insert_with(|| InboundState::new(from));
+        state.chunks.entry(seq).or_insert(data);
+        state.last_activity = Instant::now();
+        if fin {
+            state.fin_seq = Some(seq);
+        }
+
+        let ack = state.contiguous_high().unwrap_or(0);
+        let complete = state.try_reassemble();
+        if complete.is_some() {
+            inbound.remove(&id);
+        }
+        (ack, complete)
+    }
+
+    /// Drop any inbound stream that hasn't seen a new chunk in
+    /// `RECEIVE_TIMEOUT`, returning the id and sender of each one given up
+    /// on so the caller can report it.
+    pub fn sweep_timeouts(&self) -> Vec<(StreamId, String)> {
+        let mut inbound = self.inbound.lock();
+        let stale: Vec<StreamId> = inbound
+            .iter()
+            .filter(|(_, s)| s.last_activity.elapsed() > RECEIVE_TIMEOUT)
+            .map(|(id, _)| *id)
+            .collect();
+        stale
+            .into_iter()
+            .filter_map(|id| inbound.remove(&id).map(|s| (id, s.from)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_send_chunks_small_payload_without_blocking() {
+        let manager = Arc::new(StreamManager::new());
+        let (tx, rx) = mpsc::channel();
+        let id = manager.clone().send(
+            "a".to_string(),
+            "b".to_string(),
+            b"hello world".to_vec(),
+            move |msg| {
+                tx.send(msg).unwrap();
+                Ok(())
+            },
+        );
+
+        let msg = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        match msg {
+            MeshMessage::Stream { id: got_id, seq, fin, data, .. } => {
+                assert_eq!(got_id, id);
+                assert_eq!(seq, 0);
+                assert!(fin);
+                assert_eq!(data, b"hello world");
+            }
+            other => panic!("expected a Stream frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_send_blocks_until_window_advances() {
+        let manager = Arc::new(StreamManager::new());
+        let (tx, rx) = mpsc::channel();
+        let payload = vec![0u8; CHUNK_SIZE * (WINDOW_SIZE as usize + 3)];
+        let id = manager.clone().send("a".to_string(), "b".to_string(), payload, move |msg| {
+            tx.send(msg).unwrap();
+            Ok(())
+        });
+
+        // Only WINDOW_SIZE chunks should go out before the sender blocks
+        // waiting for acks.
+        for _ in 0..WINDOW_SIZE {
+            rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        }
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        manager.ack(id, 0);
+        rx.recv_timeout(Duration::from_secs(1)).unwrap();
+    }
+
+    #[test]
+    fn test_receive_chunk_dedupes_and_reassembles() {
+        let manager = StreamManager::new();
+        let (ack, complete) =
+            manager.receive_chunk(1, 0, "peer".to_string(), b"hel".to_vec(), false);
+        assert_eq!(ack, 0);
+        assert!(complete.is_none());
+
+        // Retransmitted duplicate of seq 0 - ignored.
+        let (ack, complete) =
+            manager.receive_chunk(1, 0, "peer".to_string(), b"XXX".to_vec(), false);
+        assert_eq!(ack, 0);
+        assert!(complete.is_none());
+
+        let (ack, complete) =
+            manager.receive_chunk(1, 1, "peer".to_string(), b"lo".to_vec(), true);
+        assert_eq!(ack, 1);
+        assert_eq!(complete.unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_receive_chunk_out_of_order_waits_for_gap() {
+        let manager = StreamManager::new();
+        let (ack, complete) =
+            manager.receive_chunk(1, 1, "peer".to_string(), b"lo".to_vec(), true);
+        assert_eq!(ack, 0);
+        assert!(complete.is_none());
+
+        let (ack, complete) =
+            manager.receive_chunk(1, 0, "peer".to_string(), b"hel".to_vec(), false);
+        assert_eq!(ack, 1);
+        assert_eq!(complete.unwrap(), b"hello");
+    }
+}
@@ -0,0 +1,5 @@
+//! Generated protobuf types for the compact binary mesh framing, compiled by
+//! `build.rs` from `proto/mesh.proto`. See `protocol::WireFormat`.
+#![allow(clippy::all)]
+
+include!(concat!(env!("OUT_DIR"), "/atmosphere.mesh.rs"));
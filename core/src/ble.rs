@@ -0,0 +1,280 @@
+//! BLE/GATT transport
+//!
+//! Models the two roles through which mesh nodes in physical proximity can
+//! gossip and share metrics without shared IP connectivity: a GATT
+//! peripheral (advertising an Atmosphere service) and a GATT central
+//! (scanning for and reading that service on other nodes), side by side
+//! with the existing WebSocket transport. Mirrors the Bumble
+//! battery-service client/peripheral split: one role publishes
+//! characteristics, the other discovers and reads them.
+//!
+//! These types keep the advertising and scanning state, build the
+//! characteristic payloads and hold the peers handed to
+//! `BleCentral::ingest_peer`. They make no Bluetooth platform calls, so
+//! nothing is advertised or scanned over the air by this module.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::capability::Capability;
+use crate::cost::NodeCost;
+use crate::mesh::PeerInfo;
+use crate::node::NodeId;
+
+/// 128-bit UUID for the Atmosphere GATT service.
+pub const ATMOSPHERE_SERVICE_UUID: &str = "6e400001-b5a3-f393-e0a9-e50e24dcca9e";
+
+/// Characteristic carrying a serialized `PeerInfo` advertisement.
+pub const PEER_INFO_CHARACTERISTIC_UUID: &str = "6e400002-b5a3-f393-e0a9-e50e24dcca9e";
+
+/// Characteristic carrying serialized `GossipMessage` frames.
+pub const GOSSIP_CHARACTERISTIC_UUID: &str = "6e400003-b5a3-f393-e0a9-e50e24dcca9e";
+
+/// Standard Bluetooth SIG Battery Service UUID, reported alongside the
+/// Atmosphere service so generic BLE battery widgets can also read it.
+pub const BATTERY_SERVICE_UUID: &str = "0000180f-0000-1000-8000-00805f9b34fb";
+
+/// Standard Battery Level characteristic (percentage, 0-100).
+pub const BATTERY_LEVEL_CHARACTERISTIC_UUID: &str = "00002a19-0000-1000-8000-00805f9b34fb";
+
+/// A peer discovered over BLE rather than IP.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlePeer {
+    /// Advertised peer identity.
+    pub info: PeerInfo,
+
+    /// Bluetooth address or platform-specific device handle, as a string.
+    pub device_address: String,
+
+    /// RSSI at last scan, if available.
+    pub rssi: Option<i16>,
+}
+
+/// GATT peripheral role: tracks whether this node is advertising and
+/// builds the peer-info and battery-level characteristic values.
+pub struct BlePeripheral {
+    node_id: NodeId,
+    advertising: RwLock<bool>,
+}
+
+impl BlePeripheral {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            node_id,
+            advertising: RwLock::new(false),
+        }
+    }
+
+    /// Mark the Atmosphere + Battery services as advertised and log it.
+    ///
+    /// No GATT server is registered through the platform's BLE peripheral
+    /// API (on Android, `BluetoothGattServer`) here.
+    pub fn start_advertising(&self) {
+        *self.advertising.write().unwrap() = true;
+        tracing::info!(node_id = %self.node_id, "Started BLE GATT advertising");
+    }
+
+    pub fn stop_advertising(&self) {
+        *self.advertising.write().unwrap() = false;
+        tracing::info!(node_id = %self.node_id, "Stopped BLE GATT advertising");
+    }
+
+    pub fn is_advertising(&self) -> bool {
+        *self.advertising.read().unwrap()
+    }
+
+    /// Build the bytes served from `PEER_INFO_CHARACTERISTIC_UUID`.
+    pub fn peer_info_characteristic_value(&self, info: &PeerInfo) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(info)
+    }
+
+    /// Build the single byte served from `BATTERY_LEVEL_CHARACTERISTIC_UUID`.
+    pub fn battery_level_characteristic_value(battery_percent: f32) -> u8 {
+        battery_percent.clamp(0.0, 100.0).round() as u8
+    }
+}
+
+/// GATT central role: scans for other Atmosphere peripherals and reads
+/// their advertised characteristics.
+pub struct BleCentral {
+    scanning: RwLock<bool>,
+    discovered: RwLock<HashMap<NodeId, BlePeer>>,
+}
+
+impl BleCentral {
+    pub fn new() -> Self {
+        Self {
+            scanning: RwLock::new(false),
+            discovered: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Mark scanning for the Atmosphere service UUID as started.
+    ///
+    /// No platform BLE scan is started here; peers enter the discovered
+    /// set only through `ingest_peer`.
+    pub fn start_scanning(&self) {
+        *self.scanning.write().unwrap() = true;
+    }
+
+    pub fn stop_scanning(&self) {
+        *self.scanning.write().unwrap() = false;
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        *self.scanning.read().unwrap()
+    }
+
+    /// Record a peer read from a nearby peripheral's characteristics.
+    pub fn ingest_peer(&self, peer: BlePeer) {
+        self.discovered
+            .write()
+            .unwrap()
+            .insert(peer.info.node_id, peer);
+    }
+
+    /// Remove a peer that is no longer seen.
+    pub fn forget_peer(&self, node_id: &NodeId) {
+        self.discovered.write().unwrap().remove(node_id);
+    }
+
+    /// Snapshot of peers discovered over BLE.
+    pub fn discovered_peers(&self) -> Vec<BlePeer> {
+        self.discovered.read().unwrap().values().cloned().collect()
+    }
+}
+
+impl Default for BleCentral {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Combined peripheral + central transport, run alongside the IP-based
+/// `MeshClient` so nodes in Bluetooth range but without shared IP
+/// connectivity can still exchange `PeerInfo` and `GossipMessage` data.
+pub struct BleTransport {
+    pub peripheral: BlePeripheral,
+    pub central: BleCentral,
+}
+
+impl BleTransport {
+    pub fn new(node_id: NodeId) -> Self {
+        Self {
+            peripheral: BlePeripheral::new(node_id),
+            central: BleCentral::new(),
+        }
+    }
+
+    /// Start both advertising and scanning, so this node is simultaneously
+    /// discoverable and discovering others.
+    pub fn start(&self) {
+        self.peripheral.start_advertising();
+        self.central.start_scanning();
+    }
+
+    pub fn stop(&self) {
+        self.peripheral.stop_advertising();
+        self.central.stop_scanning();
+    }
+
+    /// Peers discovered over BLE, unified into the same `PeerInfo` shape
+    /// used by IP peers so callers (capability registry, cost collector)
+    /// don't need to special-case the transport.
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        self.central
+            .discovered_peers()
+            .into_iter()
+            .map(|p| p.info)
+            .collect()
+    }
+
+    /// Capabilities advertised by a BLE peer, if known.
+    pub fn peer_capabilities(&self, node_id: &NodeId) -> Vec<Capability> {
+        self.central
+            .discovered_peers()
+            .into_iter()
+            .find(|p| &p.info.node_id == node_id)
+            .map(|p| p.info.capabilities)
+            .unwrap_or_default()
+    }
+
+    /// Cost advertised by a BLE peer, if known.
+    pub fn peer_cost(&self, node_id: &NodeId) -> Option<NodeCost> {
+        self.central
+            .discovered_peers()
+            .into_iter()
+            .find(|p| &p.info.node_id == node_id)
+            .and_then(|p| p.info.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_peer(node_id: NodeId) -> PeerInfo {
+        PeerInfo {
+            node_id,
+            public_key: String::new(),
+            name: "ble-peer".to_string(),
+            address: "ble://aa:bb:cc:dd:ee:ff".to_string(),
+            connected_at_ms: 0,
+            last_seen_ms: 0,
+            capabilities: Vec::new(),
+            cost: None,
+        }
+    }
+
+    #[test]
+    fn test_peripheral_advertising_toggle() {
+        let peripheral = BlePeripheral::new(NodeId::new());
+        assert!(!peripheral.is_advertising());
+
+        peripheral.start_advertising();
+        assert!(peripheral.is_advertising());
+
+        peripheral.stop_advertising();
+        assert!(!peripheral.is_advertising());
+    }
+
+    #[test]
+    fn test_battery_characteristic_clamped() {
+        assert_eq!(BlePeripheral::battery_level_characteristic_value(150.0), 100);
+        assert_eq!(BlePeripheral::battery_level_characteristic_value(-10.0), 0);
+        assert_eq!(BlePeripheral::battery_level_characteristic_value(42.0), 42);
+    }
+
+    #[test]
+    fn test_central_ingest_and_forget() {
+        let central = BleCentral::new();
+        let node_id = NodeId::new();
+
+        central.ingest_peer(BlePeer {
+            info: sample_peer(node_id),
+            device_address: "aa:bb:cc:dd:ee:ff".to_string(),
+            rssi: Some(-60),
+        });
+        assert_eq!(central.discovered_peers().len(), 1);
+
+        central.forget_peer(&node_id);
+        assert_eq!(central.discovered_peers().len(), 0);
+    }
+
+    #[test]
+    fn test_transport_unifies_peers_into_peer_info() {
+        let node_id = NodeId::new();
+        let transport = BleTransport::new(NodeId::new());
+        transport.central.ingest_peer(BlePeer {
+            info: sample_peer(node_id),
+            device_address: "aa:bb:cc:dd:ee:ff".to_string(),
+            rssi: None,
+        });
+
+        let peers = transport.peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].node_id, node_id);
+    }
+}
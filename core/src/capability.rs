@@ -3,13 +3,14 @@
 //! Manages the capabilities that a node can provide to the mesh network.
 //! Capabilities represent services like camera access, compute resources, etc.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::RwLock;
-use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{AtmosphereError, Result};
 use crate::node::NodeId;
+use crate::ring::HashRing;
 
 /// A capability that a node can provide
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +88,11 @@ pub struct CapabilityRegistry {
 
     /// Remote capabilities (advertised by peers)
     remote: RwLock<HashMap<NodeId, Vec<Capability>>>,
+
+    /// Consistent-hash ring over remote peers, used by `route_capability`
+    /// for stable, balanced assignment that only minimally reshuffles as
+    /// peers join or leave.
+    ring: HashRing,
 }
 
 impl CapabilityRegistry {
@@ -136,12 +142,14 @@ impl CapabilityRegistry {
     /// Update remote capabilities for a peer
     pub fn update_remote(&self, node_id: NodeId, capabilities: Vec<Capability>) {
         self.remote.write().unwrap().insert(node_id, capabilities);
+        self.ring.add_node(node_id);
         tracing::debug!(node_id = %node_id, "Updated remote capabilities");
     }
 
     /// Remove a peer's capabilities
     pub fn remove_remote(&self, node_id: &NodeId) {
         self.remote.write().unwrap().remove(node_id);
+        self.ring.remove_node(node_id);
     }
 
     /// Get capabilities for a specific peer
@@ -183,6 +191,28 @@ impl CapabilityRegistry {
         let remote_count: usize = self.remote.read().unwrap().values().map(|v| v.len()).sum();
         local_count + remote_count
     }
+
+    /// Route a key (e.g. an intent ID) to the remote peers that should
+    /// handle it, via the consistent-hash ring: the first node walking
+    /// clockwise from `key`'s ring position that advertises
+    /// `capability_type`, followed by up to `replicas` more distinct nodes
+    /// as ordered fallbacks for retry or replication. Stable across calls
+    /// for the same key, and only reshuffles a small fraction of keys when
+    /// the peer set changes.
+    pub fn route_capability(
+        &self,
+        capability_type: &str,
+        key: &str,
+        replicas: usize,
+    ) -> Vec<NodeId> {
+        let remote = self.remote.read().unwrap();
+        self.ring.walk(key, replicas + 1, |node_id| {
+            remote.get(node_id).map_or(false, |caps| {
+                caps.iter()
+                    .any(|c| c.capability_type == capability_type && c.available)
+            })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -207,13 +237,13 @@ mod tests {
     #[test]
     fn test_registry_register_unregister() {
         let registry = CapabilityRegistry::new();
-        
+
         let cap = Capability::new("compute", "GPU Compute");
         let id = registry.register(cap.clone());
-        
+
         assert!(registry.get(id).is_some());
         assert_eq!(registry.list_local().len(), 1);
-        
+
         registry.unregister(id);
         assert!(registry.get(id).is_none());
         assert_eq!(registry.list_local().len(), 0);
@@ -222,7 +252,7 @@ mod tests {
     #[test]
     fn test_registry_find_by_type() {
         let registry = CapabilityRegistry::new();
-        
+
         registry.register(Capability::new("camera", "Front Camera"));
         registry.register(Capability::new("camera", "Back Camera"));
         registry.register(Capability::new("compute", "CPU"));
@@ -248,7 +278,7 @@ mod tests {
         ];
 
         registry.update_remote(peer_id, caps);
-        
+
         let peer_caps = registry.get_remote(&peer_id);
         assert_eq!(peer_caps.len(), 2);
 
@@ -257,14 +287,63 @@ mod tests {
         assert_eq!(camera_peers[0].0, peer_id);
     }
 
+    #[test]
+    fn test_route_capability_prefers_eligible_nodes() {
+        let registry = CapabilityRegistry::new();
+
+        let camera_peer = NodeId::new();
+        let storage_peer = NodeId::new();
+        registry.update_remote(camera_peer, vec![Capability::new("camera", "Peer Camera")]);
+        registry.update_remote(
+            storage_peer,
+            vec![Capability::new("storage", "Peer Storage")],
+        );
+
+        let route = registry.route_capability("camera", "intent-1", 2);
+
+        assert_eq!(route, vec![camera_peer]);
+    }
+
+    #[test]
+    fn test_route_capability_is_stable_for_the_same_key() {
+        let registry = CapabilityRegistry::new();
+        for _ in 0..5 {
+            let peer_id = NodeId::new();
+            registry.update_remote(peer_id, vec![Capability::new("compute", "GPU")]);
+        }
+
+        let first = registry.route_capability("compute", "intent-42", 3);
+        let second = registry.route_capability("compute", "intent-42", 3);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 4);
+    }
+
+    #[test]
+    fn test_route_capability_drops_removed_peer() {
+        let registry = CapabilityRegistry::new();
+        let peer_id = NodeId::new();
+        registry.update_remote(peer_id, vec![Capability::new("camera", "Peer Camera")]);
+        assert_eq!(
+            registry.route_capability("camera", "intent-9", 0),
+            vec![peer_id]
+        );
+
+        registry.remove_remote(&peer_id);
+
+        assert!(registry
+            .route_capability("camera", "intent-9", 0)
+            .is_empty());
+    }
+
     #[test]
     fn test_registry_has_capability() {
         let registry = CapabilityRegistry::new();
-        
+
         assert!(!registry.has_local_capability("camera"));
-        
+
         registry.register(Capability::new("camera", "Test Camera"));
-        
+
         assert!(registry.has_local_capability("camera"));
         assert!(!registry.has_local_capability("compute"));
     }
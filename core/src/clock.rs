@@ -0,0 +1,108 @@
+//! Injectable Clock
+//!
+//! `CostCollector` used to timestamp every cost calculation with
+//! `SystemTime::now()` directly, which made multi-node cost-propagation
+//! behavior impossible to test deterministically - two calculations a
+//! millisecond apart in real wall-clock time could land on either side of
+//! an assertion, and a test exercising several simulated gossip rounds had
+//! no way to fast-forward between them. `Clock` abstracts "what time is it"
+//! behind a trait the same way `PlatformMetrics` abstracts "what does the
+//! hardware say": `SystemClock` is the real default, `SimClock` lets a
+//! test (or `MeshSimulation`) advance time in controlled, deterministic
+//! steps instead.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time in milliseconds since the Unix epoch.
+/// Implemented by `SystemClock` for real wall-clock time and `SimClock`
+/// for deterministic, manually-advanced time in tests and `MeshSimulation`.
+pub trait Clock: Send + Sync {
+    /// Current time in milliseconds since the Unix epoch.
+    fn now_ms(&self) -> u64;
+}
+
+/// Real wall-clock time, via `SystemTime::now()`. The default `Clock` for
+/// every `CostCollector`/`AtmosphereNode` unless overridden.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Deterministic clock for tests and `MeshSimulation`, starting at `0` and
+/// advanced only by explicit calls to `advance`/`set` - never by wall-clock
+/// time passing.
+#[derive(Debug, Default)]
+pub struct SimClock {
+    now_ms: AtomicU64,
+}
+
+impl SimClock {
+    /// A clock starting at `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A clock starting at `start_ms`.
+    pub fn starting_at(start_ms: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(start_ms),
+        }
+    }
+
+    /// Move the clock forward by `delta_ms`.
+    pub fn advance(&self, delta_ms: u64) {
+        self.now_ms.fetch_add(delta_ms, Ordering::SeqCst);
+    }
+
+    /// Jump the clock directly to `now_ms`.
+    pub fn set(&self, now_ms: u64) {
+        self.now_ms.store(now_ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock_is_roughly_now() {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        assert!(SystemClock.now_ms() >= before);
+    }
+
+    #[test]
+    fn test_sim_clock_starts_at_zero_and_advances_deterministically() {
+        let clock = SimClock::new();
+        assert_eq!(clock.now_ms(), 0);
+
+        clock.advance(1_000);
+        assert_eq!(clock.now_ms(), 1_000);
+
+        clock.set(5_000);
+        assert_eq!(clock.now_ms(), 5_000);
+    }
+
+    #[test]
+    fn test_sim_clock_starting_at_custom_offset() {
+        let clock = SimClock::starting_at(42);
+        assert_eq!(clock.now_ms(), 42);
+    }
+}
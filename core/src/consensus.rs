@@ -0,0 +1,164 @@
+//! Replicated Intent-Assignment Log
+//!
+//! `IntentRouter::route` is purely local greedy state, so two nodes racing
+//! to route the same kind of work can both land on the same remote peer
+//! and double-dispatch it. `RoutingMode::Coordinated` routes the chosen
+//! target through this instead: claiming a target for an intent becomes a
+//! proposal broadcast over the mesh, and only the claim a quorum of peers
+//! acknowledges is committed and allowed to proceed to `Executing`. This
+//! models the propose/ack/commit shape of Raft's single log without full
+//! leader election or term tracking - enough to arbitrate "who owns this
+//! intent" without needing mesh-wide agreement on every unrelated claim.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::node::NodeId;
+
+/// A committed claim: `target` is the node that owns `intent_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub intent_id: Uuid,
+    pub target: NodeId,
+    pub capability_id: Uuid,
+}
+
+/// Broadcasts claim proposals over the mesh and reports whether a quorum
+/// of peers acknowledged one. Implemented by `MeshClient` so `IntentRouter`
+/// doesn't need to depend on the transport directly - the same shape as
+/// `PlatformMetrics` decoupling `CostCollector` from any one platform.
+#[async_trait::async_trait]
+pub trait ClaimTransport: Send + Sync {
+    /// Broadcast `claim` to connected peers and wait up to `timeout` for a
+    /// quorum of acks (a majority of connected peers, including this
+    /// node). Returns `true` if quorum was reached.
+    async fn propose_claim(&self, claim: &Claim, timeout: Duration) -> bool;
+
+    /// Broadcast that `claim` has committed, so peers that lost the
+    /// proposal round learn the outcome without polling.
+    async fn announce_claim(&self, claim: &Claim);
+}
+
+/// Replicated log of committed claims, keyed by `intent_id` since only the
+/// latest claim per intent matters for "who owns it" arbitration.
+#[derive(Debug, Default)]
+pub struct AssignmentLog {
+    committed: RwLock<HashMap<Uuid, Claim>>,
+}
+
+impl AssignmentLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The currently committed claimant for `intent_id`, if any.
+    pub async fn claimant(&self, intent_id: Uuid) -> Option<Claim> {
+        self.committed.read().await.get(&intent_id).cloned()
+    }
+
+    /// Record `claim` as committed, overwriting any prior claim for the
+    /// same intent.
+    pub async fn commit(&self, claim: Claim) {
+        self.committed.write().await.insert(claim.intent_id, claim);
+    }
+
+    /// Record a claim announced by another node. Whichever claim for an
+    /// intent is observed first wins - a later conflicting claim for the
+    /// same intent is a stale loser's retry and is ignored.
+    pub async fn observe(&self, claim: Claim) {
+        self.committed
+            .write()
+            .await
+            .entry(claim.intent_id)
+            .or_insert(claim);
+    }
+
+    /// Drop committed claims for intents no longer active, so the log
+    /// doesn't grow without bound as intents complete.
+    pub async fn truncate(&self, still_active: &HashSet<Uuid>) {
+        self.committed
+            .write()
+            .await
+            .retain(|intent_id, _| still_active.contains(intent_id));
+    }
+
+    /// Number of committed claims currently retained.
+    pub async fn len(&self) -> usize {
+        self.committed.read().await.len()
+    }
+
+    /// Whether the log is empty.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_commit_and_claimant_roundtrip() {
+        let log = AssignmentLog::new();
+        let intent_id = Uuid::new_v4();
+        let claim = Claim {
+            intent_id,
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        assert!(log.claimant(intent_id).await.is_none());
+        log.commit(claim.clone()).await;
+        assert_eq!(log.claimant(intent_id).await, Some(claim));
+    }
+
+    #[tokio::test]
+    async fn test_observe_keeps_first_claim_on_conflict() {
+        let log = AssignmentLog::new();
+        let intent_id = Uuid::new_v4();
+        let first = Claim {
+            intent_id,
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+        let second = Claim {
+            intent_id,
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        log.observe(first.clone()).await;
+        log.observe(second).await;
+
+        assert_eq!(log.claimant(intent_id).await, Some(first));
+    }
+
+    #[tokio::test]
+    async fn test_truncate_drops_inactive_intents() {
+        let log = AssignmentLog::new();
+        let active_id = Uuid::new_v4();
+        let done_id = Uuid::new_v4();
+        log.commit(Claim {
+            intent_id: active_id,
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        })
+        .await;
+        log.commit(Claim {
+            intent_id: done_id,
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        })
+        .await;
+
+        let still_active: HashSet<Uuid> = [active_id].into_iter().collect();
+        log.truncate(&still_active).await;
+
+        assert!(log.claimant(active_id).await.is_some());
+        assert!(log.claimant(done_id).await.is_none());
+        assert_eq!(log.len().await, 1);
+    }
+}
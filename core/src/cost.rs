@@ -3,12 +3,15 @@
 //! Collects and calculates costs for executing tasks on a node.
 //! Uses platform metrics to determine current resource costs.
 
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::collections::HashMap;
 use std::sync::RwLock;
-use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use crate::metrics::PlatformMetrics;
+use crate::clock::{Clock, SystemClock};
+use crate::cost_store::{CostSnapshot, CostStore};
+use crate::metrics::{BatteryHealth, ChargingSource, PlatformMetrics};
 use crate::node::NodeId;
 
 /// Cost metrics for a node
@@ -58,7 +61,7 @@ pub struct CostWeights {
 impl Default for CostWeights {
     fn default() -> Self {
         Self {
-            battery: 0.4,  // Battery is most important on mobile
+            battery: 0.4, // Battery is most important on mobile
             cpu: 0.25,
             memory: 0.2,
             network: 0.15,
@@ -66,6 +69,98 @@ impl Default for CostWeights {
     }
 }
 
+/// Reference wall-clock duration for an average capability invocation
+/// under lightly-loaded conditions. A task's observed duration is
+/// normalized against this to get a `0.0..=1.0` load ratio comparable
+/// across very different capability types.
+const REFERENCE_TASK_DURATION_MS: u64 = 2_000;
+
+/// Smoothing factor for the observed-load EWMA - how much weight a new
+/// sample gets versus the running estimate.
+const LOAD_EWMA_ALPHA: f32 = 0.1;
+
+/// Ring buffer capacity for the recent-sample distribution report.
+const LOAD_SAMPLE_WINDOW: usize = 50;
+
+/// Smoothed observed-load estimate plus a rough distribution of recent
+/// samples, as reported by `CostCollector::load_distribution`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadDistribution {
+    pub smoothed: f32,
+    pub min: f32,
+    pub median: f32,
+    pub p95: f32,
+}
+
+/// Tracks observed task-execution duration as a smoothed load estimate,
+/// fed by `IntentRouter::complete` and `IntentRouter::fail`.
+/// Exists because `cpu_load()`/memory ratios are instantaneous and miss a
+/// node that looks idle moment-to-moment but is actually backed up with
+/// slow in-flight work - `LoadTimer` gives `CostCollector` a load signal
+/// derived from how long tasks are actually taking to run.
+struct LoadTimer {
+    /// `(1-α)*smoothed + α*clamp(observed_load, 0, 1)`, updated on every
+    /// `record`.
+    smoothed: f32,
+
+    /// Bounded history of recent `observed_load` samples, for reporting a
+    /// min/median/p95 distribution alongside the smoothed value.
+    samples: VecDeque<f32>,
+}
+
+impl LoadTimer {
+    fn new() -> Self {
+        Self {
+            smoothed: 0.0,
+            samples: VecDeque::with_capacity(LOAD_SAMPLE_WINDOW),
+        }
+    }
+
+    /// Normalize `duration_ms` against the reference duration and fold it
+    /// into the smoothed estimate and the sample window.
+    fn record(&mut self, duration_ms: u64) {
+        let observed_load =
+            (duration_ms as f32 / REFERENCE_TASK_DURATION_MS as f32).clamp(0.0, 1.0);
+        self.smoothed = (1.0 - LOAD_EWMA_ALPHA) * self.smoothed + LOAD_EWMA_ALPHA * observed_load;
+
+        if self.samples.len() == LOAD_SAMPLE_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(observed_load);
+    }
+
+    fn distribution(&self) -> LoadDistribution {
+        let mut sorted: Vec<f32> = self.samples.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        LoadDistribution {
+            smoothed: self.smoothed,
+            min: sorted.first().copied().unwrap_or(0.0),
+            median: percentile(&sorted, 0.5),
+            p95: percentile(&sorted, 0.95),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an already-sorted slice, or `0.0` if empty.
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[idx]
+}
+
+/// Starting and ceiling credit balance for a peer's admission-control
+/// budget - a balance saturates here instead of growing unbounded across
+/// gossip rounds.
+const MAX_CREDIT_BALANCE: f32 = 10.0;
+
+/// Scales a peer's `(1 - total_cost)` into a per-round credit refill,
+/// tuned so a peer at `total_cost` 0.0 refills from empty over a handful
+/// of rounds rather than instantly.
+const CREDIT_REPLENISH_RATE: f32 = 2.0;
+
 /// Collects and calculates node costs based on platform metrics
 pub struct CostCollector {
     /// Platform metrics provider
@@ -76,6 +171,23 @@ pub struct CostCollector {
 
     /// Cached peer costs
     peer_costs: RwLock<HashMap<NodeId, NodeCost>>,
+
+    /// Smoothed observed load from completed/failed task durations
+    load: RwLock<LoadTimer>,
+
+    /// Per-peer replenishing credit balance for admission control. A peer
+    /// not yet present here is treated as freshly replenished
+    /// (`MAX_CREDIT_BALANCE`) the first time it's looked up.
+    credits: RwLock<HashMap<NodeId, f32>>,
+
+    /// Persists `weights`/`peer_costs` across restarts. `None` means
+    /// in-memory only - a restart loses learned routing state outright.
+    store: Option<Arc<dyn CostStore>>,
+
+    /// Source of "what time is it" for `calculate_local_cost`'s
+    /// timestamp. `SystemClock` unless overridden via `with_clock`, e.g.
+    /// by `MeshSimulation` to drive deterministic multi-node tests.
+    clock: Arc<dyn Clock>,
 }
 
 impl CostCollector {
@@ -85,9 +197,60 @@ impl CostCollector {
             metrics,
             weights: RwLock::new(CostWeights::default()),
             peer_costs: RwLock::new(HashMap::new()),
+            load: RwLock::new(LoadTimer::new()),
+            credits: RwLock::new(HashMap::new()),
+            store: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Persist weight and peer-cost changes to `store` in the background,
+    /// and make it available to `load_from_store` for rehydration at
+    /// boot.
+    pub fn with_store(mut self, store: Arc<dyn CostStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Override the clock `calculate_local_cost` timestamps against,
+    /// e.g. with a `SimClock` for deterministic tests.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Rehydrate `weights` and `peer_costs` from the configured
+    /// `CostStore`, discarding any peer entry older than `max_age`. A
+    /// no-op if none is configured. Called once by `AtmosphereNode::start`
+    /// before the node begins routing.
+    pub async fn load_from_store(&self, max_age: Duration) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+
+        if let Some(snapshot) = store.load(max_age).await {
+            *self.weights.write().unwrap() = snapshot.weights;
+            *self.peer_costs.write().unwrap() = snapshot.peer_costs;
         }
     }
 
+    /// Queue the current weights/peer-costs for a background flush, if a
+    /// `CostStore` is configured. A no-op otherwise, so the common
+    /// in-memory-only collector pays nothing for this.
+    fn flush_to_store(&self) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+
+        let snapshot = CostSnapshot {
+            weights: self.weights.read().unwrap().clone(),
+            peer_costs: self.peer_costs.read().unwrap().clone(),
+        };
+        tokio::spawn(async move {
+            store.save(snapshot).await;
+        });
+    }
+
     /// Calculate the current cost for this node
     pub fn calculate_local_cost(&self) -> NodeCost {
         let weights = self.weights.read().unwrap();
@@ -101,8 +264,11 @@ impl CostCollector {
         // Calculate memory cost
         let memory_cost = self.calculate_memory_cost();
 
-        // Network cost (simplified - could be enhanced)
-        let network_cost = 0.2; // Base network cost
+        // Network cost: a base figure for being on a metered link, nudged up
+        // by how much data has already been pushed over it this session, so
+        // a node that has been hammering cellular looks costlier than one
+        // that just switched onto it.
+        let network_cost = self.calculate_network_cost();
 
         // Calculate weighted total
         let total_cost = (battery_cost * weights.battery)
@@ -110,10 +276,7 @@ impl CostCollector {
             + (memory_cost * weights.memory)
             + (network_cost * weights.network);
 
-        let timestamp_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0);
+        let timestamp_ms = self.clock.now_ms();
 
         NodeCost {
             battery_cost,
@@ -127,30 +290,115 @@ impl CostCollector {
 
     /// Calculate battery cost factor
     fn calculate_battery_cost(&self) -> f32 {
-        if !self.metrics.is_on_battery() {
+        let base_cost = if !self.metrics.is_on_battery() {
             // Plugged in - low battery cost
-            return 0.1;
-        }
-
-        match self.metrics.battery_percent() {
-            Some(percent) => {
-                // Higher cost when battery is lower
-                // At 100%: cost = 0.2
-                // At 50%: cost = 0.5
-                // At 20%: cost = 0.8
-                // At 10%: cost = 1.0
-                if percent <= 10.0 {
-                    1.0
-                } else if percent <= 20.0 {
-                    0.8
-                } else {
-                    1.0 - (percent / 100.0) * 0.8
+            0.1
+        } else {
+            match self.metrics.battery_percent() {
+                Some(percent) => {
+                    // Higher cost when battery is lower
+                    // At 100%: cost = 0.2
+                    // At 50%: cost = 0.6
+                    // At 20%: cost = 0.8
+                    // At 10%: cost = 1.0
+                    if percent <= 10.0 {
+                        1.0
+                    } else if percent <= 20.0 {
+                        0.8
+                    } else {
+                        1.0 - (percent / 100.0) * 0.8
+                    }
                 }
+                None => 0.5, // Unknown - use middle value
             }
-            None => 0.5, // Unknown - use middle value
+        };
+
+        (base_cost + self.thermal_penalty() + self.charging_source_penalty()).clamp(0.0, 1.0)
+    }
+
+    /// Extra cost for devices running hot or with failing batteries, so
+    /// thermally stressed nodes migrate work elsewhere before they throttle
+    /// or shut down.
+    fn thermal_penalty(&self) -> f32 {
+        let health_penalty = match self.metrics.battery_health() {
+            BatteryHealth::Overheat | BatteryHealth::Dead | BatteryHealth::OverVoltage => 0.3,
+            BatteryHealth::Cold => 0.15,
+            BatteryHealth::Good | BatteryHealth::Unknown => 0.0,
+        };
+
+        let temperature_penalty = match self.metrics.battery_temperature_tenths_celsius() {
+            // Above 45C is where Android starts considering a device
+            // thermally stressed for sustained workloads.
+            Some(tenths) if tenths >= 450 => 0.3,
+            Some(tenths) if tenths >= 400 => 0.15,
+            _ => 0.0,
+        };
+
+        health_penalty.max(temperature_penalty)
+    }
+
+    /// Small penalty for charging over a weak source (wireless is slower and
+    /// runs hotter than wired AC/USB), so the node doesn't look artificially
+    /// cheap just because it's plugged into something.
+    fn charging_source_penalty(&self) -> f32 {
+        if self.metrics.is_on_battery() {
+            return 0.0;
+        }
+
+        match self.metrics.charging_source() {
+            ChargingSource::Wireless => 0.05,
+            ChargingSource::Ac | ChargingSource::Usb | ChargingSource::None => 0.0,
         }
     }
 
+    /// Calculate network cost factor from connection type, data already
+    /// consumed this session, and the smoothed observed-load estimate.
+    fn calculate_network_cost(&self) -> f32 {
+        let base = if !self.metrics.network_type().is_metered() {
+            0.1
+        } else {
+            // Reference budget above which a metered link is considered
+            // heavily used already; tune down further bias as more data
+            // has moved.
+            const REFERENCE_BUDGET_BYTES: u64 = 500 * 1024 * 1024;
+            let consumed = self
+                .metrics
+                .total_tx_bytes()
+                .saturating_add(self.metrics.total_rx_bytes());
+            let usage_ratio = (consumed as f32 / REFERENCE_BUDGET_BYTES as f32).clamp(0.0, 1.0);
+
+            // Metered base of 0.5 plus up to 0.5 more as consumption
+            // approaches the reference budget.
+            0.5 + usage_ratio * 0.5
+        };
+
+        // A node under sustained real load should advertise rising cost
+        // even if it looks idle moment-to-moment, so the smoothed figure
+        // can only push the data-volume estimate up, never mask it.
+        base.max(self.load.read().unwrap().smoothed)
+    }
+
+    /// Fold an observed task's wall-clock duration into the smoothed load
+    /// estimate. Called by `IntentRouter::complete` for the winning
+    /// attempt and by `IntentRouter::fail` for a still-pending one; failed,
+    /// preempted, cancelled or timed-out attempts are not recorded.
+    pub fn record_task_duration(&self, duration_ms: u64) {
+        self.load.write().unwrap().record(duration_ms);
+    }
+
+    /// The current smoothed load estimate plus a rough min/median/p95
+    /// distribution of recent samples, for diagnostics.
+    pub fn load_distribution(&self) -> LoadDistribution {
+        self.load.read().unwrap().distribution()
+    }
+
+    /// Whether this node's own network connection is metered, used by the
+    /// intent router to avoid pushing a remote payload over a cellular link
+    /// when local execution is available.
+    pub fn is_local_metered(&self) -> bool {
+        self.metrics.network_type().is_metered()
+    }
+
     /// Calculate memory cost factor
     fn calculate_memory_cost(&self) -> f32 {
         let available = self.metrics.available_memory_mb() as f32;
@@ -167,6 +415,7 @@ impl CostCollector {
     /// Update the cost weights
     pub fn set_weights(&self, weights: CostWeights) {
         *self.weights.write().unwrap() = weights;
+        self.flush_to_store();
     }
 
     /// Get current weights
@@ -177,6 +426,7 @@ impl CostCollector {
     /// Store a peer's cost information
     pub fn update_peer_cost(&self, node_id: NodeId, cost: NodeCost) {
         self.peer_costs.write().unwrap().insert(node_id, cost);
+        self.flush_to_store();
     }
 
     /// Get a peer's cost information
@@ -191,7 +441,13 @@ impl CostCollector {
 
     /// Get all peer costs, sorted by total cost (lowest first)
     pub fn get_sorted_peer_costs(&self) -> Vec<(NodeId, NodeCost)> {
-        let mut costs: Vec<_> = self.peer_costs.read().unwrap().clone().into_iter().collect();
+        let mut costs: Vec<_> = self
+            .peer_costs
+            .read()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .collect();
         costs.sort_by(|a, b| a.1.total_cost.partial_cmp(&b.1.total_cost).unwrap());
         costs
     }
@@ -205,6 +461,48 @@ impl CostCollector {
             .min_by(|a, b| a.1.total_cost.partial_cmp(&b.1.total_cost).unwrap())
             .map(|(id, _)| *id)
     }
+
+    /// Attempt to reserve `weight` credits against `node_id`'s balance
+    /// ahead of routing an intent to it. A peer not yet seen starts at
+    /// `MAX_CREDIT_BALANCE`. Returns `false` (reserving nothing) if the
+    /// balance would go negative, so the caller can fall through to the
+    /// next-cheapest peer instead of hammering this one to saturation.
+    pub fn try_reserve_credits(&self, node_id: NodeId, weight: f32) -> bool {
+        let mut credits = self.credits.write().unwrap();
+        let balance = credits.entry(node_id).or_insert(MAX_CREDIT_BALANCE);
+
+        if *balance - weight < 0.0 {
+            return false;
+        }
+
+        *balance -= weight;
+        true
+    }
+
+    /// Refund `weight` credits to `node_id`, e.g. when a reservation's
+    /// attempt never panned out (cancelled as a straggler, or failed and
+    /// about to be retried elsewhere). Clamped at `MAX_CREDIT_BALANCE`.
+    pub fn release_credits(&self, node_id: NodeId, weight: f32) {
+        let mut credits = self.credits.write().unwrap();
+        let balance = credits.entry(node_id).or_insert(MAX_CREDIT_BALANCE);
+        *balance = (*balance + weight).min(MAX_CREDIT_BALANCE);
+    }
+
+    /// Refill every known peer's credit balance - called once per gossip
+    /// round. Low-cost peers refill faster: the refill amount is
+    /// proportional to `(1 - total_cost)`, so a peer already measured as
+    /// expensive regains headroom more slowly, smoothing load across
+    /// peers of similar cost instead of hammering the single cheapest one.
+    pub fn replenish_all(&self) {
+        let peer_costs = self.peer_costs.read().unwrap();
+        let mut credits = self.credits.write().unwrap();
+
+        for (node_id, cost) in peer_costs.iter() {
+            let balance = credits.entry(*node_id).or_insert(MAX_CREDIT_BALANCE);
+            *balance = (*balance + (1.0 - cost.total_cost) * CREDIT_REPLENISH_RATE)
+                .min(MAX_CREDIT_BALANCE);
+        }
+    }
 }
 
 impl std::fmt::Debug for CostCollector {
@@ -229,6 +527,7 @@ mod tests {
             cpu: 0.3,
             memory_mb: 2048,
             total_memory_mb: 4096,
+            ..Default::default()
         });
 
         let collector = CostCollector::new(metrics);
@@ -247,6 +546,7 @@ mod tests {
             cpu: 0.5,
             memory_mb: 1024,
             total_memory_mb: 4096,
+            ..Default::default()
         });
 
         let collector = CostCollector::new(metrics);
@@ -264,6 +564,7 @@ mod tests {
             cpu: 0.0,
             memory_mb: 1024, // 25% available
             total_memory_mb: 4096,
+            ..Default::default()
         });
 
         let collector = CostCollector::new(metrics);
@@ -273,6 +574,101 @@ mod tests {
         assert!((cost.memory_cost - 0.75).abs() < 0.01);
     }
 
+    #[test]
+    fn test_overheating_battery_raises_cost() {
+        let cool = Arc::new(MockMetrics {
+            battery_temperature_tenths_celsius: Some(300),
+            ..Default::default()
+        });
+        let hot = Arc::new(MockMetrics {
+            battery_temperature_tenths_celsius: Some(480),
+            ..Default::default()
+        });
+
+        let cool_cost = CostCollector::new(cool).calculate_local_cost();
+        let hot_cost = CostCollector::new(hot).calculate_local_cost();
+
+        assert!(hot_cost.battery_cost > cool_cost.battery_cost);
+    }
+
+    #[test]
+    fn test_dead_battery_health_raises_cost() {
+        let good = Arc::new(MockMetrics {
+            battery_health: BatteryHealth::Good,
+            ..Default::default()
+        });
+        let dead = Arc::new(MockMetrics {
+            battery_health: BatteryHealth::Dead,
+            ..Default::default()
+        });
+
+        let good_cost = CostCollector::new(good).calculate_local_cost();
+        let dead_cost = CostCollector::new(dead).calculate_local_cost();
+
+        assert!(dead_cost.battery_cost > good_cost.battery_cost);
+    }
+
+    #[test]
+    fn test_wireless_charging_penalty() {
+        let wired = Arc::new(MockMetrics {
+            on_battery: false,
+            charging_source: ChargingSource::Usb,
+            ..Default::default()
+        });
+        let wireless = Arc::new(MockMetrics {
+            on_battery: false,
+            charging_source: ChargingSource::Wireless,
+            ..Default::default()
+        });
+
+        let wired_cost = CostCollector::new(wired).calculate_local_cost();
+        let wireless_cost = CostCollector::new(wireless).calculate_local_cost();
+
+        assert!(wireless_cost.battery_cost > wired_cost.battery_cost);
+    }
+
+    #[test]
+    fn test_metered_network_raises_cost() {
+        use crate::metrics::NetworkType;
+
+        let wifi = Arc::new(MockMetrics {
+            network_type: NetworkType::Wifi,
+            ..Default::default()
+        });
+        let cellular = Arc::new(MockMetrics {
+            network_type: NetworkType::Cellular,
+            ..Default::default()
+        });
+
+        let wifi_cost = CostCollector::new(wifi).calculate_local_cost();
+        let cellular_cost = CostCollector::new(cellular).calculate_local_cost();
+
+        assert!(cellular_cost.network_cost > wifi_cost.network_cost);
+    }
+
+    #[test]
+    fn test_heavy_cellular_usage_raises_cost_further() {
+        use crate::metrics::NetworkType;
+
+        let light = Arc::new(MockMetrics {
+            network_type: NetworkType::Cellular,
+            total_tx_bytes: 0,
+            total_rx_bytes: 0,
+            ..Default::default()
+        });
+        let heavy = Arc::new(MockMetrics {
+            network_type: NetworkType::Cellular,
+            total_tx_bytes: 600 * 1024 * 1024,
+            total_rx_bytes: 0,
+            ..Default::default()
+        });
+
+        let light_cost = CostCollector::new(light).calculate_local_cost();
+        let heavy_cost = CostCollector::new(heavy).calculate_local_cost();
+
+        assert!(heavy_cost.network_cost > light_cost.network_cost);
+    }
+
     #[test]
     fn test_peer_costs() {
         let metrics = Arc::new(MockMetrics::default());
@@ -281,8 +677,20 @@ mod tests {
         let peer1 = NodeId::new();
         let peer2 = NodeId::new();
 
-        collector.update_peer_cost(peer1, NodeCost { total_cost: 0.8, ..Default::default() });
-        collector.update_peer_cost(peer2, NodeCost { total_cost: 0.3, ..Default::default() });
+        collector.update_peer_cost(
+            peer1,
+            NodeCost {
+                total_cost: 0.8,
+                ..Default::default()
+            },
+        );
+        collector.update_peer_cost(
+            peer2,
+            NodeCost {
+                total_cost: 0.3,
+                ..Default::default()
+            },
+        );
 
         // Should find peer2 (lower cost)
         assert_eq!(collector.find_lowest_cost_peer(), Some(peer2));
@@ -293,6 +701,107 @@ mod tests {
         assert_eq!(sorted[0].0, peer2);
     }
 
+    #[test]
+    fn test_sustained_load_raises_network_cost() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+
+        let idle_cost = collector.calculate_local_cost();
+
+        // Several tasks running far longer than the reference duration
+        // should push the smoothed estimate well above the idle baseline.
+        for _ in 0..20 {
+            collector.record_task_duration(REFERENCE_TASK_DURATION_MS * 5);
+        }
+        let loaded_cost = collector.calculate_local_cost();
+
+        assert!(loaded_cost.network_cost > idle_cost.network_cost);
+    }
+
+    #[test]
+    fn test_load_distribution_tracks_samples() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+
+        collector.record_task_duration(0);
+        collector.record_task_duration(REFERENCE_TASK_DURATION_MS);
+        collector.record_task_duration(REFERENCE_TASK_DURATION_MS * 10);
+
+        let distribution = collector.load_distribution();
+        assert!((distribution.min - 0.0).abs() < f32::EPSILON);
+        assert!((distribution.p95 - 1.0).abs() < f32::EPSILON);
+        assert!(distribution.smoothed > 0.0);
+    }
+
+    #[test]
+    fn test_load_sample_window_is_bounded() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+
+        for _ in 0..(LOAD_SAMPLE_WINDOW * 2) {
+            collector.record_task_duration(REFERENCE_TASK_DURATION_MS);
+        }
+
+        assert_eq!(
+            collector.load.read().unwrap().samples.len(),
+            LOAD_SAMPLE_WINDOW
+        );
+    }
+
+    #[test]
+    fn test_reserve_credits_goes_negative_fails() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+        let peer = NodeId::new();
+
+        assert!(collector.try_reserve_credits(peer, MAX_CREDIT_BALANCE));
+        assert!(!collector.try_reserve_credits(peer, 0.1));
+    }
+
+    #[test]
+    fn test_release_credits_restores_balance() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+        let peer = NodeId::new();
+
+        assert!(collector.try_reserve_credits(peer, MAX_CREDIT_BALANCE));
+        collector.release_credits(peer, 4.0);
+        assert!(collector.try_reserve_credits(peer, 4.0));
+        assert!(!collector.try_reserve_credits(peer, 0.1));
+    }
+
+    #[test]
+    fn test_replenish_favors_cheaper_peers() {
+        let metrics = Arc::new(MockMetrics::default());
+        let collector = CostCollector::new(metrics);
+
+        let cheap = NodeId::new();
+        let expensive = NodeId::new();
+        collector.update_peer_cost(
+            cheap,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+        collector.update_peer_cost(
+            expensive,
+            NodeCost {
+                total_cost: 0.9,
+                ..Default::default()
+            },
+        );
+
+        assert!(collector.try_reserve_credits(cheap, MAX_CREDIT_BALANCE));
+        assert!(collector.try_reserve_credits(expensive, MAX_CREDIT_BALANCE));
+
+        collector.replenish_all();
+
+        // Both start from empty, but the cheaper peer should refill more.
+        assert!(collector.try_reserve_credits(cheap, 1.5));
+        assert!(!collector.try_reserve_credits(expensive, 0.5));
+    }
+
     #[test]
     fn test_custom_weights() {
         let metrics = Arc::new(MockMetrics::default());
@@ -0,0 +1,158 @@
+//! Persistent Cost/Weight Store
+//!
+//! `CostCollector`'s custom `CostWeights` and its `peer_costs` cache live
+//! only in memory, so every restart of `AtmosphereNode` throws away
+//! learned routing state and the node rejoins the mesh blind - routine on
+//! mobile, where the OS kills backgrounded apps aggressively. `CostStore`
+//! persists a snapshot of both, flushed in the background whenever either
+//! changes, so `AtmosphereNode::start` can reload it and resume
+//! cost-aware routing immediately instead of cold-starting - mirroring
+//! `IntentStore` journalling in-flight intents for the same reason.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::cost::{CostWeights, NodeCost};
+use crate::node::NodeId;
+
+/// Max delay before a queued snapshot is written to disk, so a burst of
+/// weight/peer-cost updates coalesces into one flush instead of hitting
+/// disk on every change.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Everything `CostStore` persists: the local node's custom weights and
+/// its cached view of peer costs. Each `NodeCost` already carries its own
+/// `timestamp_ms`, so a stale peer entry can be evicted on load without
+/// needing a separate timestamp alongside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostSnapshot {
+    pub weights: CostWeights,
+    pub peer_costs: HashMap<NodeId, NodeCost>,
+}
+
+/// Persists `CostCollector`'s learned routing state so a restarted
+/// `AtmosphereNode` can resume cost-aware routing immediately instead of
+/// rejoining the mesh blind. Implemented by `FileCostStore`; swap in
+/// another implementation to persist elsewhere (e.g. a platform-native
+/// datastore) or to stub persistence out in tests.
+#[async_trait::async_trait]
+pub trait CostStore: Send + Sync {
+    /// Queue `snapshot` to be flushed to disk, coalescing with whatever
+    /// is already pending from the last flush interval.
+    async fn save(&self, snapshot: CostSnapshot);
+
+    /// Load the last-persisted snapshot, discarding any peer entry older
+    /// than `max_age`. Returns `None` if nothing has ever been persisted.
+    async fn load(&self, max_age: Duration) -> Option<CostSnapshot>;
+}
+
+enum StoreOp {
+    Save(CostSnapshot),
+}
+
+/// Default `CostStore`, persisting a bincode-encoded snapshot to a local
+/// file.
+pub struct FileCostStore {
+    path: PathBuf,
+    ops: mpsc::UnboundedSender<StoreOp>,
+}
+
+impl FileCostStore {
+    /// Prepare to persist snapshots at `path` and spawn the background
+    /// flush task, writing at most once per `flush_interval`.
+    pub fn open(path: impl AsRef<Path>, flush_interval: Duration) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let (ops, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::flush_loop(path.clone(), receiver, flush_interval));
+        Self { path, ops }
+    }
+
+    /// `open` with `DEFAULT_FLUSH_INTERVAL`.
+    pub fn open_default(path: impl AsRef<Path>) -> Self {
+        Self::open(path, DEFAULT_FLUSH_INTERVAL)
+    }
+
+    /// Wait for a snapshot, drain anything queued behind it so only the
+    /// newest survives, then debounce for `flush_interval` in case more
+    /// arrive before writing - coalescing a burst of saves into a single
+    /// flush rather than hitting disk on every change.
+    async fn flush_loop(
+        path: PathBuf,
+        mut receiver: mpsc::UnboundedReceiver<StoreOp>,
+        flush_interval: Duration,
+    ) {
+        loop {
+            let Some(StoreOp::Save(mut latest)) = receiver.recv().await else {
+                return; // sender dropped, nothing left to flush
+            };
+            while let Ok(StoreOp::Save(next)) = receiver.try_recv() {
+                latest = next;
+            }
+
+            tokio::time::sleep(flush_interval).await;
+            while let Ok(StoreOp::Save(next)) = receiver.try_recv() {
+                latest = next;
+            }
+
+            let path = path.clone();
+            let written =
+                tokio::task::spawn_blocking(move || Self::write_snapshot(&path, &latest)).await;
+            match written {
+                Ok(Err(err)) => {
+                    tracing::warn!(error = %err, "Failed to flush cost store snapshot")
+                }
+                Err(join_err) => {
+                    tracing::warn!(error = %join_err, "Cost store flush task panicked")
+                }
+                Ok(Ok(())) => {}
+            }
+        }
+    }
+
+    fn write_snapshot(path: &Path, snapshot: &CostSnapshot) -> std::io::Result<()> {
+        let encoded = bincode::serialize(snapshot)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        std::fs::write(path, encoded)
+    }
+
+    fn read_snapshot(path: &Path) -> Option<CostSnapshot> {
+        let bytes = std::fs::read(path).ok()?;
+        bincode::deserialize(&bytes).ok()
+    }
+}
+
+#[async_trait::async_trait]
+impl CostStore for FileCostStore {
+    async fn save(&self, snapshot: CostSnapshot) {
+        let _ = self.ops.send(StoreOp::Save(snapshot));
+    }
+
+    async fn load(&self, max_age: Duration) -> Option<CostSnapshot> {
+        let path = self.path.clone();
+        let snapshot = tokio::task::spawn_blocking(move || Self::read_snapshot(&path))
+            .await
+            .ok()
+            .flatten()?;
+
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let max_age_ms = max_age.as_millis() as u64;
+
+        let peer_costs = snapshot
+            .peer_costs
+            .into_iter()
+            .filter(|(_, cost)| now_ms.saturating_sub(cost.timestamp_ms) <= max_age_ms)
+            .collect();
+
+        Some(CostSnapshot {
+            weights: snapshot.weights,
+            peer_costs,
+        })
+    }
+}
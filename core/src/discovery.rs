@@ -0,0 +1,226 @@
+//! mDNS/DNS-SD LAN discovery
+//!
+//! A peer normally only becomes known through an explicit `MeshClient::connect`
+//! call or mesh-relayed gossip, so two devices on the same network have no
+//! way to find each other until one already knows the other's address. This
+//! advertises the node over mDNS/DNS-SD under `_atmosphere._udp`, with a TXT
+//! record carrying its `node_id`, display name, and a public-key
+//! fingerprint, while simultaneously browsing for other `_atmosphere`
+//! instances and calling `MeshClient::connect`/`disconnect` as peers are
+//! resolved and expire. Controlled by `NodeConfig::enable_mdns` so it can be
+//! turned off for privacy-sensitive or headless/server deployments.
+//!
+//! mDNS gives no guarantee a peer announces before it drops off the
+//! network, so a peer that stops being re-resolved is disconnected after
+//! `PEER_TTL` rather than lingering forever.
+
+use std::collections::HashMap;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use crate::error::{AtmosphereError, Result};
+use crate::mesh::MeshClient;
+use crate::node::NodeId;
+
+const SERVICE_TYPE: &str = "_atmosphere._udp.local.";
+const TXT_NODE_ID: &str = "node_id";
+const TXT_NAME: &str = "name";
+const TXT_FINGERPRINT: &str = "fp";
+
+/// How long a discovered peer may go unseen before it's disconnected as stale.
+const PEER_TTL: Duration = Duration::from_secs(30);
+
+/// How often the background task checks for peers past `PEER_TTL`, and the
+/// longest it ever blocks waiting for the next mDNS event.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Owns the mDNS daemon and the background task that keeps `MeshClient`'s
+/// connections in sync with whatever is still advertising on the LAN.
+pub struct MdnsDiscovery {
+    daemon: Option<ServiceDaemon>,
+    running: Arc<AtomicBool>,
+    task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl MdnsDiscovery {
+    pub fn new() -> Self {
+        Self {
+            daemon: None,
+            running: Arc::new(AtomicBool::new(false)),
+            task: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Advertise this node over mDNS and start browsing for peers,
+    /// automatically `connect`ing newly resolved ones on `mesh` and
+    /// `disconnect`ing them once they stop being re-resolved.
+    pub async fn start(
+        &mut self,
+        mesh: Arc<MeshClient>,
+        node_id: NodeId,
+        name: String,
+        port: u16,
+        public_key_fingerprint: String,
+    ) -> Result<()> {
+        if self.is_running() {
+            return Ok(());
+        }
+
+        let daemon = ServiceDaemon::new()
+            .map_err(|e| AtmosphereError::Network(format!("mDNS daemon failed: {}", e)))?;
+
+        let host_ip = local_ipv4().unwrap_or_else(|| "0.0.0.0".to_string());
+        let instance_name = node_id.to_string();
+
+        let mut properties = HashMap::new();
+        properties.insert(TXT_NODE_ID.to_string(), instance_name.clone());
+        properties.insert(TXT_NAME.to_string(), name);
+        properties.insert(TXT_FINGERPRINT.to_string(), public_key_fingerprint);
+
+        let service_info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance_name,
+            &format!("{}.local.", instance_name),
+            host_ip.as_str(),
+            port,
+            properties,
+        )
+        .map_err(|e| AtmosphereError::Network(format!("invalid mDNS service info: {}", e)))?;
+
+        daemon
+            .register(service_info)
+            .map_err(|e| AtmosphereError::Network(format!("mDNS registration failed: {}", e)))?;
+
+        let receiver = daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| AtmosphereError::Network(format!("mDNS browse failed: {}", e)))?;
+
+        self.running.store(true, Ordering::Relaxed);
+        let running = Arc::clone(&self.running);
+
+        self.task = Some(tokio::spawn(async move {
+            let mut last_seen: HashMap<NodeId, Instant> = HashMap::new();
+
+            while running.load(Ordering::Relaxed) {
+                let recv = receiver.clone();
+                let event =
+                    tokio::task::spawn_blocking(move || recv.recv_timeout(SWEEP_INTERVAL)).await;
+
+                match event {
+                    Ok(Ok(ServiceEvent::ServiceResolved(info))) => {
+                        if let Some((peer_id, address)) = parse_service_info(&info) {
+                            if peer_id != node_id {
+                                last_seen.insert(peer_id, Instant::now());
+                                if let Err(e) = mesh.connect(&address).await {
+                                    tracing::debug!(
+                                        peer = %peer_id,
+                                        error = %e,
+                                        "Failed to connect to mDNS-discovered peer"
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    Ok(Ok(ServiceEvent::ServiceRemoved(_, fullname))) => {
+                        if let Some(peer_id) = node_id_from_fullname(&fullname) {
+                            last_seen.remove(&peer_id);
+                            let _ = mesh.disconnect(&peer_id).await;
+                        }
+                    }
+                    _ => {}
+                }
+
+                let stale: Vec<NodeId> = last_seen
+                    .iter()
+                    .filter(|(_, seen)| seen.elapsed() >= PEER_TTL)
+                    .map(|(id, _)| *id)
+                    .collect();
+                for peer_id in stale {
+                    last_seen.remove(&peer_id);
+                    let _ = mesh.disconnect(&peer_id).await;
+                }
+            }
+        }));
+
+        self.daemon = Some(daemon);
+        Ok(())
+    }
+
+    /// Stop advertising and browsing.
+    pub async fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+        if let Some(daemon) = self.daemon.take() {
+            let _ = daemon.shutdown();
+        }
+    }
+}
+
+impl Default for MdnsDiscovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extract a resolved peer's `node_id` and a `ws://` address to `connect` to
+/// from its advertised TXT record and resolved host/port.
+fn parse_service_info(info: &ServiceInfo) -> Option<(NodeId, String)> {
+    let props = info.get_properties();
+    let node_id_str = props.get(TXT_NODE_ID)?;
+    let node_id = NodeId::from_uuid(uuid::Uuid::parse_str(node_id_str).ok()?);
+    let ip = info.get_addresses().iter().next()?;
+    let address = format!("ws://{}:{}", ip, info.get_port());
+    Some((node_id, address))
+}
+
+/// mDNS reports removals by the service's full instance name
+/// (`<node_id>._atmosphere._udp.local.`), so pull the `node_id` back out of it.
+fn node_id_from_fullname(fullname: &str) -> Option<NodeId> {
+    let node_id_str = fullname.split('.').next()?;
+    Some(NodeId::from_uuid(uuid::Uuid::parse_str(node_id_str).ok()?))
+}
+
+/// Best-effort local IPv4 address to advertise, found by opening a UDP
+/// socket toward a public address without sending anything - this never
+/// touches the network, it just asks the OS which interface would be used.
+fn local_ipv4() -> Option<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_id_from_fullname_roundtrip() {
+        let node_id = NodeId::new();
+        let fullname = format!("{}._atmosphere._udp.local.", node_id);
+        assert_eq!(node_id_from_fullname(&fullname), Some(node_id));
+    }
+
+    #[test]
+    fn test_node_id_from_fullname_rejects_garbage() {
+        assert_eq!(
+            node_id_from_fullname("not-a-uuid._atmosphere._udp.local."),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_discovery_not_running() {
+        let discovery = MdnsDiscovery::new();
+        assert!(!discovery.is_running());
+    }
+}
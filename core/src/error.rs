@@ -29,6 +29,9 @@ pub enum AtmosphereError {
     #[error("Timeout: {0}")]
     Timeout(String),
 
+    #[error("No route to node: {0}")]
+    NoRoute(String),
+
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
 
@@ -3,16 +3,19 @@
 //! Routes intents to capable nodes based on capability matching and cost.
 //! Intents are high-level requests like "take a photo" or "run computation".
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::capability::CapabilityRegistry;
+use crate::consensus::{AssignmentLog, Claim, ClaimTransport};
 use crate::cost::CostCollector;
 use crate::error::{AtmosphereError, Result};
 use crate::node::NodeId;
+use crate::store::IntentStore;
 
 /// An intent to be routed to a capable node
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +46,18 @@ pub struct Intent {
 
     /// Timeout in milliseconds
     pub timeout_ms: u64,
+
+    /// Whether duplicate concurrent execution is safe. Gates hedging:
+    /// even with `hedge_count` set, a non-idempotent action (e.g.
+    /// "capture and charge") never fans out to more than one target.
+    #[serde(default)]
+    pub idempotent: bool,
+
+    /// Number of cheapest capable peers to hedge dispatch across instead
+    /// of just one, for a latency-sensitive intent willing to pay for
+    /// redundant execution. Only takes effect when `idempotent` is true.
+    #[serde(default)]
+    pub hedge_count: Option<usize>,
 }
 
 impl Intent {
@@ -63,12 +78,15 @@ impl Intent {
             prefer_local: true,
             created_at_ms: now_ms,
             timeout_ms: 30000,
+            idempotent: false,
+            hedge_count: None,
         }
     }
 
     /// Add a parameter
     pub fn with_param(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
-        self.params.insert(key.into(), serde_json::to_value(value).unwrap());
+        self.params
+            .insert(key.into(), serde_json::to_value(value).unwrap());
         self
     }
 
@@ -95,6 +113,17 @@ impl Intent {
         self.timeout_ms = timeout_ms;
         self
     }
+
+    /// Hedge dispatch across the `n` cheapest capable peers instead of
+    /// just one, so a slow or dead remote doesn't cost the whole
+    /// `timeout_ms` before a retry even starts. Implies `idempotent`,
+    /// since by definition more than one target may actually execute the
+    /// action before the race is won.
+    pub fn with_hedging(mut self, n: usize) -> Self {
+        self.hedge_count = Some(n.max(1));
+        self.idempotent = true;
+        self
+    }
 }
 
 /// Status of an intent
@@ -106,8 +135,10 @@ pub enum IntentStatus {
     /// Routed to a node, awaiting execution
     Routed { target: NodeId },
 
-    /// Currently executing
-    Executing { target: NodeId },
+    /// Currently executing. Normally a single target; for a hedged
+    /// intent, every peer the action was dispatched to in parallel - the
+    /// first of them to `complete` wins the race.
+    Executing { targets: Vec<NodeId> },
 
     /// Successfully completed
     Completed { target: NodeId },
@@ -124,18 +155,106 @@ pub enum IntentStatus {
 
 /// Result of routing an intent
 #[derive(Debug, Clone)]
-pub struct RoutingDecision {
-    /// Chosen target node
-    pub target: NodeId,
+pub enum RoutingDecision {
+    /// Dispatched to exactly one target - the outcome for every
+    /// non-hedged intent.
+    Single {
+        /// Chosen target node
+        target: NodeId,
+
+        /// Whether target is local
+        is_local: bool,
+
+        /// Cost of execution on target
+        cost: f32,
+
+        /// Capability ID on target
+        capability_id: Uuid,
+    },
+
+    /// Dispatched in parallel to every target in `targets`, for an
+    /// idempotent intent routed via `Intent::with_hedging`. The first to
+    /// `complete` wins; the rest are cancelled via `cancel_attempt`.
+    Hedged {
+        /// Every target the action was dispatched to, cheapest first.
+        targets: Vec<NodeId>,
+
+        /// Capability ID used on the cheapest (first) target.
+        capability_id: Uuid,
+
+        /// Combined cost of dispatching to every target, so callers see
+        /// the true budget impact of fanning out rather than just the
+        /// cheapest leg.
+        cost: f32,
+    },
+}
+
+impl RoutingDecision {
+    /// The primary target: the sole target for `Single`, or the cheapest
+    /// (first) of `Hedged`'s targets.
+    pub fn target(&self) -> NodeId {
+        match self {
+            RoutingDecision::Single { target, .. } => *target,
+            RoutingDecision::Hedged { targets, .. } => targets[0],
+        }
+    }
+
+    /// Whether the primary target is this node. Hedging only ever fans
+    /// out to remote peers, so this is always `false` for `Hedged`.
+    pub fn is_local(&self) -> bool {
+        matches!(self, RoutingDecision::Single { is_local: true, .. })
+    }
+
+    /// Cost of this decision - the single target's cost, or the combined
+    /// cost of every hedged target.
+    pub fn cost(&self) -> f32 {
+        match self {
+            RoutingDecision::Single { cost, .. } | RoutingDecision::Hedged { cost, .. } => *cost,
+        }
+    }
 
-    /// Whether target is local
-    pub is_local: bool,
+    /// Capability ID used on the primary target.
+    pub fn capability_id(&self) -> Uuid {
+        match self {
+            RoutingDecision::Single { capability_id, .. }
+            | RoutingDecision::Hedged { capability_id, .. } => *capability_id,
+        }
+    }
+}
+
+/// Outcome of one dispatch `Attempt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// Dispatched, no response yet.
+    Pending,
+
+    /// The target executed the intent successfully.
+    Succeeded,
+
+    /// The target failed, or became unreachable, before completing it.
+    Failed { reason: String },
 
-    /// Cost of execution on target
-    pub cost: f32,
+    /// A hedged sibling attempt won the race first; this one was called
+    /// off via `cancel_attempt` before it finished.
+    Cancelled,
+}
 
-    /// Capability ID on target
+/// One dispatch of an intent to a target - borrowing the job/run split
+/// from CI systems, an `Intent` is the job and each `Attempt` is a run
+/// against a different host. `IntentRouter::report_failure` appends a new
+/// one each time it re-routes around a failed attempt.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub target: NodeId,
     pub capability_id: Uuid,
+    pub started_at_ms: u64,
+    pub outcome: AttemptOutcome,
+
+    /// Credits reserved against `target`'s admission-control balance for
+    /// this attempt via `CostCollector::try_reserve_credits`. `0.0` for
+    /// local attempts, which don't draw on a peer's budget. Refunded via
+    /// `release_credits` if the attempt ends without completing.
+    pub credit_weight: f32,
 }
 
 /// Tracked intent with status
@@ -143,6 +262,59 @@ struct TrackedIntent {
     intent: Intent,
     status: IntentStatus,
     result: Option<serde_json::Value>,
+    attempts: Vec<Attempt>,
+
+    /// Whether the watchdog has already logged a soft-deadline warning for
+    /// this intent, so it only fires once per intent rather than every
+    /// sweep between the soft and hard deadline.
+    soft_warned: bool,
+}
+
+/// Dispatch attempts before an intent is given up on and moved to terminal
+/// `Failed`, unless overridden via [`IntentRouter::with_max_attempts`].
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// How often the watchdog spawned by [`IntentRouter::start`] sweeps
+/// `active_intents` for anything past its deadline.
+const WATCHDOG_SWEEP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Fraction of `Intent::timeout_ms` at which the watchdog logs a soft
+/// warning, before hard-terminating at the full timeout - the
+/// slow-timeout/terminate-after-N pattern borrowed from test runners.
+const SOFT_DEADLINE_FRACTION: f64 = 0.8;
+
+/// Base delay before a re-routed retry, doubled per prior attempt and
+/// capped so a flaky mesh doesn't stall intents indefinitely.
+const RETRY_BASE_BACKOFF_MS: u64 = 50;
+const RETRY_MAX_BACKOFF_MS: u64 = 2_000;
+
+/// How long [`IntentRouter::claim_and_commit`] waits for a quorum ack in
+/// `RoutingMode::Coordinated` before giving up on the proposal. A lost
+/// proposal surfaces as an ordinary routing `Err`, so it rides the same
+/// `report_failure` retry/backoff path as any other failed attempt.
+const CLAIM_QUORUM_TIMEOUT: Duration = Duration::from_millis(1_000);
+
+/// How `IntentRouter` decides who ultimately owns an intent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoutingMode {
+    /// Trust this node's own greedy cost-based pick. Fast, but two nodes
+    /// routing the same kind of work concurrently can both land on the
+    /// same remote peer and double-dispatch it.
+    #[default]
+    Local,
+
+    /// Run every pick through the replicated [`AssignmentLog`]: a
+    /// candidate is only committed once a quorum of mesh peers
+    /// acknowledges the claim, so at most one node ever proceeds to
+    /// `Executing` for a given intent.
+    Coordinated,
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 /// Routes intents to capable nodes
@@ -158,6 +330,24 @@ pub struct IntentRouter {
 
     /// Local node ID (set on start)
     local_node_id: RwLock<Option<NodeId>>,
+
+    /// Dispatch attempts allowed before `report_failure` gives up.
+    max_attempts: usize,
+
+    /// Whether picks are committed locally or arbitrated through the
+    /// replicated `AssignmentLog`.
+    routing_mode: RoutingMode,
+
+    /// Committed claims, consulted and appended to in `RoutingMode::Coordinated`.
+    assignment_log: Arc<AssignmentLog>,
+
+    /// Broadcasts claim proposals and announcements. Required in
+    /// `RoutingMode::Coordinated`, unused in `RoutingMode::Local`.
+    claim_transport: Option<Arc<dyn ClaimTransport>>,
+
+    /// Journals intents and status transitions for crash recovery. `None`
+    /// means in-memory only - a restart loses `active_intents` outright.
+    intent_store: Option<Arc<dyn IntentStore>>,
 }
 
 impl IntentRouter {
@@ -168,9 +358,67 @@ impl IntentRouter {
             cost_collector,
             active_intents: RwLock::new(HashMap::new()),
             local_node_id: RwLock::new(None),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            routing_mode: RoutingMode::Local,
+            assignment_log: Arc::new(AssignmentLog::new()),
+            claim_transport: None,
+            intent_store: None,
+        }
+    }
+
+    /// Override the number of dispatch attempts `report_failure` will make
+    /// before moving an intent to terminal `Failed`.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Opt into `RoutingMode::Coordinated`: every pick is proposed through
+    /// `transport` and only committed once a quorum of mesh peers
+    /// acknowledges it, so concurrently routing nodes never double-dispatch
+    /// the same intent.
+    pub fn with_coordinated_routing(mut self, transport: Arc<dyn ClaimTransport>) -> Self {
+        self.routing_mode = RoutingMode::Coordinated;
+        self.claim_transport = Some(transport);
+        self
+    }
+
+    /// The routing mode this router was configured with.
+    pub fn routing_mode(&self) -> RoutingMode {
+        self.routing_mode
+    }
+
+    /// Journal every intent and status transition to `store`, and
+    /// rehydrate from it the next time `start()` runs - so a process
+    /// restart resumes in-flight work instead of losing it.
+    pub fn with_intent_store(mut self, store: Arc<dyn IntentStore>) -> Self {
+        self.intent_store = Some(store);
+        self
+    }
+
+    /// Journal a status transition, if an `IntentStore` is configured. A
+    /// no-op for the common in-memory-only router.
+    async fn persist_status(
+        &self,
+        intent_id: Uuid,
+        status: &IntentStatus,
+        result: Option<&serde_json::Value>,
+    ) {
+        if let Some(store) = &self.intent_store {
+            store.update_status(intent_id, status, result).await;
         }
     }
 
+    /// Record a claim announced by another node, so a peer that loses a
+    /// proposal round learns the winner without polling. No-op in
+    /// `RoutingMode::Local`, where there's no log to reconcile against.
+    pub async fn observe_claim(&self, claim: Claim) {
+        if self.routing_mode != RoutingMode::Coordinated {
+            return;
+        }
+        self.assignment_log.observe(claim).await;
+    }
+
     /// Set the local node ID
     pub async fn set_local_node_id(&self, node_id: NodeId) {
         *self.local_node_id.write().await = Some(node_id);
@@ -179,125 +427,626 @@ impl IntentRouter {
     /// Route an intent to a capable node
     pub async fn route(&self, intent: Intent) -> Result<RoutingDecision> {
         let intent_id = intent.id;
-        let capability_type = &intent.capability_type;
 
         tracing::debug!(
             intent_id = %intent_id,
-            capability = %capability_type,
+            capability = %intent.capability_type,
             "Routing intent"
         );
 
-        // Track the intent
+        self.track_pending(&intent).await;
+        self.dispatch(intent_id, &intent, &HashSet::new()).await
+    }
+
+    /// Track `intent` as `Pending` without attempting to dispatch it - the
+    /// bookkeeping half of `route`, split out so `IntentScheduler` can
+    /// enqueue work immediately and defer picking a target to its tick
+    /// loop.
+    pub(crate) async fn track_pending(&self, intent: &Intent) {
         self.active_intents.write().await.insert(
-            intent_id,
+            intent.id,
             TrackedIntent {
                 intent: intent.clone(),
                 status: IntentStatus::Pending,
                 result: None,
+                attempts: Vec::new(),
+                soft_warned: false,
             },
         );
 
-        // First, check for local capability
+        if let Some(store) = &self.intent_store {
+            store.append(intent, &IntentStatus::Pending).await;
+        }
+    }
+
+    /// Attempt to place an already-tracked `intent` as a fresh dispatch -
+    /// the routing half of `route`, exposed for `IntentScheduler` to retry
+    /// a queued intent once it looks placeable.
+    pub(crate) async fn try_dispatch(&self, intent: &Intent) -> Result<RoutingDecision> {
+        self.dispatch(intent.id, intent, &HashSet::new()).await
+    }
+
+    /// Count of intents of `capability_type` currently `Routed` or
+    /// `Executing`, so `IntentScheduler` can enforce a per-type
+    /// concurrency cap without tracking dispatch outcomes itself.
+    pub async fn in_flight_count(&self, capability_type: &str) -> usize {
+        self.active_intents
+            .read()
+            .await
+            .values()
+            .filter(|t| {
+                t.intent.capability_type == capability_type
+                    && matches!(
+                        t.status,
+                        IntentStatus::Routed { .. } | IntentStatus::Executing { .. }
+                    )
+            })
+            .count()
+    }
+
+    /// Re-enter routing for an intent whose most recent attempt failed,
+    /// excluding every target already tried. Applies exponential backoff
+    /// before the next dispatch, and moves the intent to terminal `Failed`
+    /// once no untried capable peer remains or `max_attempts` is hit.
+    pub async fn report_failure(&self, intent_id: Uuid, reason: String) -> Result<RoutingDecision> {
+        let (intent, attempts_so_far, excluded) = {
+            let mut intents = self.active_intents.write().await;
+            let tracked = intents.get_mut(&intent_id).ok_or_else(|| {
+                AtmosphereError::NoCapablePeer(format!("unknown intent {}", intent_id))
+            })?;
+
+            if let Some(last) = tracked.attempts.last_mut() {
+                last.outcome = AttemptOutcome::Failed {
+                    reason: reason.clone(),
+                };
+                if last.credit_weight > 0.0 {
+                    self.cost_collector
+                        .release_credits(last.target, last.credit_weight);
+                }
+            }
+
+            if tracked.attempts.len() >= self.max_attempts {
+                tracked.status = IntentStatus::Failed { reason };
+                return Err(AtmosphereError::NoCapablePeer(
+                    tracked.intent.capability_type.clone(),
+                ));
+            }
+
+            let excluded: HashSet<NodeId> = tracked.attempts.iter().map(|a| a.target).collect();
+            (tracked.intent.clone(), tracked.attempts.len(), excluded)
+        };
+
+        let backoff_ms = RETRY_BASE_BACKOFF_MS
+            .saturating_mul(1u64 << attempts_so_far.min(16))
+            .min(RETRY_MAX_BACKOFF_MS);
+        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+
+        self.dispatch(intent_id, &intent, &excluded).await
+    }
+
+    /// Pick a target for `intent`, skipping anything in `excluded`, and
+    /// record the attempt. Shared by the first dispatch in `route` (empty
+    /// exclusion set) and retries from `report_failure`.
+    async fn dispatch(
+        &self,
+        intent_id: Uuid,
+        intent: &Intent,
+        excluded: &HashSet<NodeId>,
+    ) -> Result<RoutingDecision> {
+        if intent.idempotent && intent.hedge_count.is_some() && excluded.is_empty() {
+            if let Some(decision) = self.dispatch_hedged(intent_id, intent).await? {
+                return Ok(decision);
+            }
+            // No candidates cheap enough to hedge across - fall through to
+            // the single-target path below, same as an un-hedged intent.
+        }
+
+        let capability_type = &intent.capability_type;
         let local_caps = self.capabilities.find_local_by_type(capability_type);
         let local_node_id = self.local_node_id.read().await.unwrap_or_else(NodeId::new);
+        let local_tried = excluded.contains(&local_node_id);
 
-        if !local_caps.is_empty() && intent.prefer_local {
+        // First, check for local capability
+        if !local_tried && !local_caps.is_empty() && intent.prefer_local {
             let cap = &local_caps[0];
             let local_cost = self.cost_collector.calculate_local_cost();
 
             if local_cost.total_cost <= intent.max_cost {
-                self.update_status(intent_id, IntentStatus::Routed { target: local_node_id }).await;
-
-                return Ok(RoutingDecision {
-                    target: local_node_id,
-                    is_local: true,
-                    cost: local_cost.total_cost,
-                    capability_id: cap.id,
-                });
+                return self
+                    .claim_and_commit(
+                        intent_id,
+                        local_node_id,
+                        local_node_id,
+                        cap.id,
+                        local_cost.total_cost,
+                        true,
+                        0.0,
+                    )
+                    .await;
             }
         }
 
         // Look for remote capabilities
-        let remote_caps = self.capabilities.find_peers_with_capability(capability_type);
+        let remote_caps = self
+            .capabilities
+            .find_peers_with_capability(capability_type);
+
+        // Routing a remote intent means pushing the payload over our own
+        // uplink, so when that uplink is metered we surcharge every remote
+        // candidate to keep routing biased toward local execution (or an
+        // unmetered peer, if one happens to be cheaper even after the
+        // surcharge).
+        let metered_surcharge = if self.cost_collector.is_local_metered() {
+            0.2
+        } else {
+            0.0
+        };
+
+        // Rank every untried, affordable remote peer cheapest-first, then
+        // walk down the list reserving admission-control credits - a peer
+        // out of credits (hammered to saturation) is skipped in favor of
+        // the next-cheapest instead of stalling the intent.
+        let mut candidates: Vec<(NodeId, Uuid, f32, f32)> = remote_caps
+            .into_iter()
+            .filter(|(node_id, _)| !excluded.contains(node_id))
+            .filter_map(|(node_id, cap)| {
+                let cost = self
+                    .cost_collector
+                    .get_peer_cost(&node_id)
+                    .map(|c| c.total_cost * cap.cost_weight)
+                    .unwrap_or(0.5)
+                    + metered_surcharge;
+                (cost <= intent.max_cost).then_some((node_id, cap.id, cost, cap.cost_weight))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        let best_peer = candidates.into_iter().find(|(node_id, _, _, weight)| {
+            self.cost_collector.try_reserve_credits(*node_id, *weight)
+        });
 
-        if remote_caps.is_empty() && local_caps.is_empty() {
-            self.update_status(
-                intent_id,
-                IntentStatus::Failed {
-                    reason: format!("No capable node found for: {}", capability_type),
-                },
-            )
+        if let Some((node_id, cap_id, cost, weight)) = best_peer {
+            return self
+                .claim_and_commit(
+                    intent_id,
+                    local_node_id,
+                    node_id,
+                    cap_id,
+                    cost,
+                    false,
+                    weight,
+                )
+                .await;
+        }
+
+        // Fall back to local if no good remote option and we haven't
+        // already tried it
+        if !local_tried && !local_caps.is_empty() {
+            let cap = &local_caps[0];
+            let local_cost = self.cost_collector.calculate_local_cost();
+
+            return self
+                .claim_and_commit(
+                    intent_id,
+                    local_node_id,
+                    local_node_id,
+                    cap.id,
+                    local_cost.total_cost,
+                    true,
+                    0.0,
+                )
+                .await;
+        }
+
+        // Nothing affordable is free - as a last resort, a high-priority
+        // intent can bump a strictly-lower-priority intent that's merely
+        // `Routed` (not yet `Executing`) off its target and take it over.
+        // Preemption is already a last-resort override of ordinary cost
+        // ranking, so it bypasses credit admission control too rather than
+        // reserving against the bumped target's budget.
+        if let Some((node_id, cap_id, cost)) = self.try_preempt(intent_id, intent, excluded).await {
+            return self
+                .claim_and_commit(intent_id, local_node_id, node_id, cap_id, cost, false, 0.0)
+                .await;
+        }
+
+        let reason = if local_caps.is_empty() && excluded.is_empty() {
+            format!("No capable node found for: {}", capability_type)
+        } else {
+            "No untried peer within cost budget".to_string()
+        };
+        self.update_status(intent_id, IntentStatus::Failed { reason })
             .await;
 
-            return Err(AtmosphereError::NoCapablePeer(capability_type.clone()));
+        Err(AtmosphereError::NoCapablePeer(capability_type.clone()))
+    }
+
+    /// Dispatch `intent` to the `hedge_count` cheapest remote peers within
+    /// `max_cost` at once, falling back to fewer if that many don't exist.
+    /// Returns `Ok(None)` when no candidate is affordable at all, so the
+    /// caller can fall back to ordinary single-target dispatch instead of
+    /// failing outright.
+    ///
+    /// Bypasses `RoutingMode::Coordinated` - hedged targets are claimed
+    /// locally rather than through the consensus log, since the
+    /// first-response-wins contract already prevents double execution.
+    async fn dispatch_hedged(
+        &self,
+        intent_id: Uuid,
+        intent: &Intent,
+    ) -> Result<Option<RoutingDecision>> {
+        let n = intent.hedge_count.unwrap_or(1).max(1);
+        let metered_surcharge = if self.cost_collector.is_local_metered() {
+            0.2
+        } else {
+            0.0
+        };
+
+        let mut candidates: Vec<(NodeId, Uuid, f32, f32)> = self
+            .capabilities
+            .find_peers_with_capability(&intent.capability_type)
+            .into_iter()
+            .filter_map(|(node_id, cap)| {
+                let cost = self
+                    .cost_collector
+                    .get_peer_cost(&node_id)
+                    .map(|c| c.total_cost * cap.cost_weight)
+                    .unwrap_or(0.5)
+                    + metered_surcharge;
+                (cost <= intent.max_cost).then_some((node_id, cap.id, cost, cap.cost_weight))
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Ok(None);
         }
 
-        // Find the best remote peer by cost
-        let mut best_peer: Option<(NodeId, Uuid, f32)> = None;
+        candidates.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Walk cheapest-first, reserving credits, until `n` targets are
+        // hedged across or candidates run out - a peer out of credits is
+        // skipped rather than hedged to, same admission control as
+        // single-target dispatch.
+        let candidates: Vec<(NodeId, Uuid, f32, f32)> = candidates
+            .into_iter()
+            .filter(|(node_id, _, _, weight)| {
+                self.cost_collector.try_reserve_credits(*node_id, *weight)
+            })
+            .take(n)
+            .collect();
+
+        if candidates.is_empty() {
+            return Ok(None);
+        }
 
-        for (node_id, cap) in remote_caps {
-            let cost = self
-                .cost_collector
-                .get_peer_cost(&node_id)
-                .map(|c| c.total_cost * cap.cost_weight)
-                .unwrap_or(0.5);
+        let targets: Vec<NodeId> = candidates
+            .iter()
+            .map(|(node_id, _, _, _)| *node_id)
+            .collect();
+        let capability_id = candidates[0].1;
+        let total_cost: f32 = candidates.iter().map(|(_, _, cost, _)| cost).sum();
 
-            if cost <= intent.max_cost {
-                match &best_peer {
-                    None => best_peer = Some((node_id, cap.id, cost)),
-                    Some((_, _, best_cost)) if cost < *best_cost => {
-                        best_peer = Some((node_id, cap.id, cost));
-                    }
-                    _ => {}
+        if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
+            for (target, cap_id, _, weight) in &candidates {
+                tracked.attempts.push(Attempt {
+                    target: *target,
+                    capability_id: *cap_id,
+                    started_at_ms: now_ms(),
+                    outcome: AttemptOutcome::Pending,
+                    credit_weight: *weight,
+                });
+            }
+            tracked.status = IntentStatus::Executing {
+                targets: targets.clone(),
+            };
+        }
+        self.persist_status(
+            intent_id,
+            &IntentStatus::Executing {
+                targets: targets.clone(),
+            },
+            None,
+        )
+        .await;
+
+        Ok(Some(RoutingDecision::Hedged {
+            targets,
+            capability_id,
+            cost: total_cost,
+        }))
+    }
+
+    /// Look for a `Routed` (not yet `Executing`) intent with strictly lower
+    /// `priority` than `intent` whose target advertises `intent`'s
+    /// capability, bump it back to `Pending` to be re-routed later, and
+    /// hand its target to `intent` instead.
+    async fn try_preempt(
+        &self,
+        intent_id: Uuid,
+        intent: &Intent,
+        excluded: &HashSet<NodeId>,
+    ) -> Option<(NodeId, Uuid, f32)> {
+        let remote_caps = self
+            .capabilities
+            .find_peers_with_capability(&intent.capability_type);
+
+        let mut intents = self.active_intents.write().await;
+
+        let victim = intents
+            .iter()
+            .filter(|(id, _)| **id != intent_id)
+            .filter_map(|(id, tracked)| match tracked.status {
+                IntentStatus::Routed { target }
+                    if !excluded.contains(&target)
+                        && tracked.intent.priority < intent.priority
+                        && remote_caps.iter().any(|(peer, _)| *peer == target) =>
+                {
+                    Some((*id, target, tracked.intent.priority))
+                }
+                _ => None,
+            })
+            .min_by_key(|(_, _, priority)| *priority)
+            .map(|(id, target, _)| (id, target));
+
+        let (victim_id, target) = victim?;
+        let cap_id = remote_caps
+            .iter()
+            .find(|(peer, _)| *peer == target)
+            .map(|(_, cap)| cap.id)?;
+
+        if let Some(bumped) = intents.get_mut(&victim_id) {
+            bumped.status = IntentStatus::Pending;
+            if let Some(last) = bumped.attempts.last_mut() {
+                last.outcome = AttemptOutcome::Failed {
+                    reason: format!("preempted by higher-priority intent {}", intent_id),
+                };
+                if last.credit_weight > 0.0 {
+                    self.cost_collector
+                        .release_credits(last.target, last.credit_weight);
                 }
             }
+            tracing::info!(
+                intent_id = %intent_id,
+                bumped_intent_id = %victim_id,
+                target = %target,
+                "Preempting lower-priority intent's target"
+            );
         }
+        self.persist_status(victim_id, &IntentStatus::Pending, None)
+            .await;
 
-        // Fall back to local if no good remote option
-        if best_peer.is_none() && !local_caps.is_empty() {
-            let cap = &local_caps[0];
-            let local_cost = self.cost_collector.calculate_local_cost();
+        let cost = self
+            .cost_collector
+            .get_peer_cost(&target)
+            .map(|c| c.total_cost)
+            .unwrap_or(0.5);
 
-            self.update_status(intent_id, IntentStatus::Routed { target: local_node_id }).await;
+        Some((target, cap_id, cost))
+    }
 
-            return Ok(RoutingDecision {
-                target: local_node_id,
-                is_local: true,
-                cost: local_cost.total_cost,
-                capability_id: cap.id,
-            });
+    /// Spawn the background watchdog that periodically scans
+    /// `active_intents` for anything past its deadline, after first
+    /// rehydrating from the `IntentStore`, if one is configured. Abort the
+    /// returned handle to stop it; the task holds its own `Arc` to the
+    /// router, so dropping the caller's handles does not stop it.
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let router = self;
+        tokio::spawn(async move {
+            router.load_active().await;
+            loop {
+                tokio::time::sleep(WATCHDOG_SWEEP_INTERVAL).await;
+                router.sweep_deadlines().await;
+            }
+        })
+    }
+
+    /// Rehydrate intents journalled as still `Pending`/`Routed`/`Executing`
+    /// before a restart: one whose deadline already passed is marked
+    /// `TimedOut` outright, everything else is re-tracked as `Pending` and
+    /// re-submitted to `dispatch` for a fresh pick, the same as a
+    /// `report_failure` retry would. A no-op if no `IntentStore` is
+    /// configured.
+    async fn load_active(&self) {
+        let Some(store) = self.intent_store.clone() else {
+            return;
+        };
+        let now = now_ms();
+
+        for (intent, _journalled_status) in store.load_active().await {
+            let intent_id = intent.id;
+            let age = now.saturating_sub(intent.created_at_ms);
+
+            self.track_pending(&intent).await;
+
+            if age >= intent.timeout_ms {
+                self.update_status(intent_id, IntentStatus::TimedOut).await;
+                continue;
+            }
+
+            if let Err(err) = self.dispatch(intent_id, &intent, &HashSet::new()).await {
+                tracing::warn!(
+                    intent_id = %intent_id,
+                    error = %err,
+                    "Failed to re-route intent rehydrated from the journal"
+                );
+            }
         }
+    }
 
-        match best_peer {
-            Some((node_id, cap_id, cost)) => {
-                self.update_status(intent_id, IntentStatus::Routed { target: node_id }).await;
+    /// Mark anything still in-flight past `Intent::timeout_ms` as
+    /// `TimedOut`, logging a soft warning first at `SOFT_DEADLINE_FRACTION`
+    /// of the way there.
+    async fn sweep_deadlines(&self) {
+        let now = now_ms();
+        let mut intents = self.active_intents.write().await;
+
+        for (intent_id, tracked) in intents.iter_mut() {
+            if !matches!(
+                tracked.status,
+                IntentStatus::Pending
+                    | IntentStatus::Routed { .. }
+                    | IntentStatus::Executing { .. }
+            ) {
+                continue;
+            }
 
-                Ok(RoutingDecision {
-                    target: node_id,
-                    is_local: false,
-                    cost,
-                    capability_id: cap_id,
-                })
+            let age = now.saturating_sub(tracked.intent.created_at_ms);
+            let soft_deadline = (tracked.intent.timeout_ms as f64 * SOFT_DEADLINE_FRACTION) as u64;
+
+            if age >= tracked.intent.timeout_ms {
+                tracing::warn!(
+                    intent_id = %intent_id,
+                    age_ms = age,
+                    timeout_ms = tracked.intent.timeout_ms,
+                    "Intent exceeded its deadline, marking TimedOut"
+                );
+                tracked.status = IntentStatus::TimedOut;
+                for attempt in tracked
+                    .attempts
+                    .iter()
+                    .filter(|a| a.outcome == AttemptOutcome::Pending && a.credit_weight > 0.0)
+                {
+                    self.cost_collector
+                        .release_credits(attempt.target, attempt.credit_weight);
+                }
+                self.persist_status(*intent_id, &IntentStatus::TimedOut, None)
+                    .await;
+            } else if age >= soft_deadline && !tracked.soft_warned {
+                tracked.soft_warned = true;
+                tracing::warn!(
+                    intent_id = %intent_id,
+                    age_ms = age,
+                    timeout_ms = tracked.intent.timeout_ms,
+                    "Intent approaching its deadline"
+                );
             }
-            None => {
-                self.update_status(
+        }
+    }
+
+    /// Gate a candidate pick behind `RoutingMode`. In `Local` mode this is
+    /// a pass-through to `commit_attempt`. In `Coordinated` mode it first
+    /// checks for an already-committed claim for `intent_id` - adopting it
+    /// rather than racing a new one - and otherwise proposes `target` as a
+    /// claim, committing and announcing it only once a quorum acks. A lost
+    /// proposal round surfaces as a plain `Err`, so it rides the same
+    /// `report_failure` retry/backoff path as any other failed attempt
+    /// rather than needing its own recovery logic here.
+    async fn claim_and_commit(
+        &self,
+        intent_id: Uuid,
+        local_node_id: NodeId,
+        target: NodeId,
+        capability_id: Uuid,
+        cost: f32,
+        is_local: bool,
+        credit_weight: f32,
+    ) -> Result<RoutingDecision> {
+        if self.routing_mode != RoutingMode::Coordinated {
+            return self
+                .commit_attempt(
+                    intent_id,
+                    target,
+                    capability_id,
+                    cost,
+                    is_local,
+                    credit_weight,
+                )
+                .await;
+        }
+
+        if let Some(claim) = self.assignment_log.claimant(intent_id).await {
+            return self
+                .commit_attempt(
                     intent_id,
-                    IntentStatus::Failed {
-                        reason: "No peer within cost budget".to_string(),
-                    },
+                    claim.target,
+                    claim.capability_id,
+                    cost,
+                    claim.target == local_node_id,
+                    credit_weight,
                 )
                 .await;
+        }
 
-                Err(AtmosphereError::NoCapablePeer(capability_type.clone()))
-            }
+        let transport = self.claim_transport.as_ref().ok_or_else(|| {
+            AtmosphereError::InvalidConfig(
+                "RoutingMode::Coordinated requires a ClaimTransport".to_string(),
+            )
+        })?;
+
+        let claim = Claim {
+            intent_id,
+            target,
+            capability_id,
+        };
+
+        if !transport.propose_claim(&claim, CLAIM_QUORUM_TIMEOUT).await {
+            return Err(AtmosphereError::Timeout(format!(
+                "claim for intent {} did not reach quorum",
+                intent_id
+            )));
         }
+
+        self.assignment_log.commit(claim.clone()).await;
+        transport.announce_claim(&claim).await;
+
+        self.commit_attempt(
+            intent_id,
+            target,
+            capability_id,
+            cost,
+            is_local,
+            credit_weight,
+        )
+        .await
+    }
+
+    /// Record a new `Attempt` against `target` and mark the intent routed.
+    async fn commit_attempt(
+        &self,
+        intent_id: Uuid,
+        target: NodeId,
+        capability_id: Uuid,
+        cost: f32,
+        is_local: bool,
+        credit_weight: f32,
+    ) -> Result<RoutingDecision> {
+        if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
+            tracked.attempts.push(Attempt {
+                target,
+                capability_id,
+                started_at_ms: now_ms(),
+                outcome: AttemptOutcome::Pending,
+                credit_weight,
+            });
+            tracked.status = IntentStatus::Routed { target };
+        }
+        self.persist_status(intent_id, &IntentStatus::Routed { target }, None)
+            .await;
+
+        Ok(RoutingDecision::Single {
+            target,
+            is_local,
+            cost,
+            capability_id,
+        })
+    }
+
+    /// Get the dispatch history for an intent, oldest attempt first.
+    pub async fn get_attempts(&self, intent_id: Uuid) -> Vec<Attempt> {
+        self.active_intents
+            .read()
+            .await
+            .get(&intent_id)
+            .map(|t| t.attempts.clone())
+            .unwrap_or_default()
     }
 
     /// Update intent status
     async fn update_status(&self, intent_id: Uuid, status: IntentStatus) {
         if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
-            tracked.status = status;
+            tracked.status = status.clone();
         }
+        self.persist_status(intent_id, &status, None).await;
     }
 
     /// Get intent status
@@ -309,21 +1058,116 @@ impl IntentRouter {
             .map(|t| t.status.clone())
     }
 
-    /// Mark intent as completed with result
-    pub async fn complete(&self, intent_id: Uuid, result: serde_json::Value) {
-        if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
-            let target = match &tracked.status {
-                IntentStatus::Routed { target } | IntentStatus::Executing { target } => *target,
-                _ => NodeId::new(),
+    /// Mark intent as completed with `result`, as reported by `target`.
+    /// For a hedged intent with several outstanding `Executing` targets,
+    /// the first target to call this wins the race: its result is
+    /// recorded and every other outstanding target is stood down via
+    /// `cancel_attempt`. A target reporting after the intent already
+    /// reached a terminal status is a late straggler and is ignored.
+    pub async fn complete(&self, intent_id: Uuid, target: NodeId, result: serde_json::Value) {
+        let (stragglers, duration_ms) = {
+            let mut intents = self.active_intents.write().await;
+            let Some(tracked) = intents.get_mut(&intent_id) else {
+                return;
             };
+
+            if matches!(
+                tracked.status,
+                IntentStatus::Completed { .. }
+                    | IntentStatus::Failed { .. }
+                    | IntentStatus::Cancelled
+                    | IntentStatus::TimedOut
+            ) {
+                return;
+            }
+
+            let stragglers: Vec<NodeId> = match &tracked.status {
+                IntentStatus::Executing { targets } => {
+                    targets.iter().copied().filter(|t| *t != target).collect()
+                }
+                _ => Vec::new(),
+            };
+
+            let duration_ms = tracked
+                .attempts
+                .iter_mut()
+                .find(|a| a.target == target && a.outcome == AttemptOutcome::Pending)
+                .map(|attempt| {
+                    let duration_ms = now_ms().saturating_sub(attempt.started_at_ms);
+                    attempt.outcome = AttemptOutcome::Succeeded;
+                    duration_ms
+                });
+
             tracked.status = IntentStatus::Completed { target };
-            tracked.result = Some(result);
+            tracked.result = Some(result.clone());
+            (stragglers, duration_ms)
+        };
+
+        if let Some(duration_ms) = duration_ms {
+            self.cost_collector.record_task_duration(duration_ms);
+        }
+
+        self.persist_status(
+            intent_id,
+            &IntentStatus::Completed { target },
+            Some(&result),
+        )
+        .await;
+
+        for straggler in stragglers {
+            self.cancel_attempt(intent_id, straggler).await;
+        }
+    }
+
+    /// Mark the in-flight attempt against `target` for `intent_id` as
+    /// cancelled - how a hedged dispatch's stragglers are stood down once
+    /// another target's `complete` call wins the race, and also usable
+    /// directly by a caller that wants to call off one specific attempt.
+    pub async fn cancel_attempt(&self, intent_id: Uuid, target: NodeId) {
+        if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
+            if let Some(attempt) = tracked
+                .attempts
+                .iter_mut()
+                .find(|a| a.target == target && a.outcome == AttemptOutcome::Pending)
+            {
+                attempt.outcome = AttemptOutcome::Cancelled;
+                if attempt.credit_weight > 0.0 {
+                    self.cost_collector
+                        .release_credits(attempt.target, attempt.credit_weight);
+                }
+            }
         }
     }
 
-    /// Mark intent as failed
+    /// Mark intent as failed, without retrying. Unlike `report_failure`,
+    /// this is a one-way terminal transition - use it for callers that
+    /// manage their own retry policy instead of `IntentRouter`'s.
     pub async fn fail(&self, intent_id: Uuid, reason: String) {
-        self.update_status(intent_id, IntentStatus::Failed { reason }).await;
+        let mut duration_ms = None;
+        if let Some(tracked) = self.active_intents.write().await.get_mut(&intent_id) {
+            if let Some(last) = tracked.attempts.last_mut() {
+                if last.outcome == AttemptOutcome::Pending {
+                    duration_ms = Some(now_ms().saturating_sub(last.started_at_ms));
+                    last.outcome = AttemptOutcome::Failed {
+                        reason: reason.clone(),
+                    };
+                    if last.credit_weight > 0.0 {
+                        self.cost_collector
+                            .release_credits(last.target, last.credit_weight);
+                    }
+                }
+            }
+            tracked.status = IntentStatus::Failed {
+                reason: reason.clone(),
+            };
+        }
+
+        if let Some(duration_ms) = duration_ms {
+            self.cost_collector.record_task_duration(duration_ms);
+        }
+
+        self.persist_status(intent_id, &IntentStatus::Failed { reason }, None)
+            .await;
     }
 
     /// Cancel an intent
@@ -340,23 +1184,53 @@ impl IntentRouter {
             .and_then(|t| t.result.clone())
     }
 
-    /// Remove completed/failed intents older than the given age
+    /// Remove completed/failed intents older than the given age, and drop
+    /// any committed claims for intents that have reached a terminal
+    /// status so the `AssignmentLog` doesn't grow without bound.
     pub async fn cleanup(&self, max_age_ms: u64) {
         let now_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_millis() as u64)
             .unwrap_or(0);
 
-        self.active_intents.write().await.retain(|_, tracked| {
+        let mut pruned = Vec::new();
+        self.active_intents.write().await.retain(|id, tracked| {
             let age = now_ms.saturating_sub(tracked.intent.created_at_ms);
-            match tracked.status {
+            let keep = match tracked.status {
                 IntentStatus::Completed { .. }
                 | IntentStatus::Failed { .. }
                 | IntentStatus::Cancelled
                 | IntentStatus::TimedOut => age < max_age_ms,
                 _ => true,
+            };
+            if !keep {
+                pruned.push(*id);
             }
+            keep
         });
+
+        if let Some(store) = &self.intent_store {
+            for id in pruned {
+                store.prune(id).await;
+            }
+        }
+
+        let still_active: HashSet<Uuid> = self
+            .active_intents
+            .read()
+            .await
+            .iter()
+            .filter(|(_, t)| {
+                matches!(
+                    t.status,
+                    IntentStatus::Pending
+                        | IntentStatus::Routed { .. }
+                        | IntentStatus::Executing { .. }
+                )
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        self.assignment_log.truncate(&still_active).await;
     }
 
     /// Get count of active intents
@@ -365,7 +1239,14 @@ impl IntentRouter {
             .read()
             .await
             .values()
-            .filter(|t| matches!(t.status, IntentStatus::Pending | IntentStatus::Routed { .. } | IntentStatus::Executing { .. }))
+            .filter(|t| {
+                matches!(
+                    t.status,
+                    IntentStatus::Pending
+                        | IntentStatus::Routed { .. }
+                        | IntentStatus::Executing { .. }
+                )
+            })
             .count()
     }
 }
@@ -380,6 +1261,7 @@ impl std::fmt::Debug for IntentRouter {
 mod tests {
     use super::*;
     use crate::capability::Capability;
+    use crate::cost::NodeCost;
     use crate::metrics::MockMetrics;
 
     fn create_test_router() -> IntentRouter {
@@ -420,6 +1302,7 @@ mod tests {
             cpu: 0.2,
             memory_mb: 2048,
             total_memory_mb: 4096,
+            ..Default::default()
         });
         let cost_collector = Arc::new(CostCollector::new(metrics));
         let router = IntentRouter::new(Arc::clone(&capabilities), cost_collector);
@@ -431,7 +1314,7 @@ mod tests {
         let intent = Intent::new("camera", "capture");
         let decision = router.route(intent).await.unwrap();
 
-        assert!(decision.is_local);
+        assert!(decision.is_local());
     }
 
     #[tokio::test]
@@ -448,6 +1331,41 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_metered_local_node_prefers_local_over_marginal_remote() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics {
+            network_type: crate::metrics::NetworkType::Cellular,
+            ..Default::default()
+        });
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+
+        capabilities.register(Capability::new("camera", "Front Camera"));
+        let local_node_id = NodeId::new();
+        router.set_local_node_id(local_node_id).await;
+
+        let remote_id = NodeId::new();
+        capabilities.update_remote(remote_id, vec![Capability::new("camera", "Remote Camera")]);
+        cost_collector.update_peer_cost(
+            remote_id,
+            NodeCost {
+                total_cost: 0.5,
+                ..Default::default()
+            },
+        );
+
+        // The remote's 0.5 cost alone fits a 0.6 budget, but the 0.2
+        // metered surcharge raises it to 0.7, so routing falls back to
+        // local execution rather than ship the payload over cellular.
+        let intent = Intent::new("camera", "capture")
+            .prefer_remote()
+            .with_max_cost(0.6);
+        let decision = router.route(intent).await.unwrap();
+
+        assert!(decision.is_local());
+    }
+
     #[tokio::test]
     async fn test_intent_status_tracking() {
         let capabilities = Arc::new(CapabilityRegistry::new());
@@ -461,12 +1379,18 @@ mod tests {
         let intent = Intent::new("test", "action");
         let intent_id = intent.id;
 
-        router.route(intent).await.unwrap();
+        let decision = router.route(intent).await.unwrap();
 
         let status = router.get_status(intent_id).await.unwrap();
         assert!(matches!(status, IntentStatus::Routed { .. }));
 
-        router.complete(intent_id, serde_json::json!({"success": true})).await;
+        router
+            .complete(
+                intent_id,
+                decision.target(),
+                serde_json::json!({"success": true}),
+            )
+            .await;
 
         let status = router.get_status(intent_id).await.unwrap();
         assert!(matches!(status, IntentStatus::Completed { .. }));
@@ -489,6 +1413,8 @@ mod tests {
                 intent,
                 status: IntentStatus::Pending,
                 result: None,
+                attempts: Vec::new(),
+                soft_warned: false,
             },
         );
 
@@ -510,18 +1436,616 @@ mod tests {
 
         {
             let mut intents = router.active_intents.write().await;
-            intents.insert(intent1.id, TrackedIntent {
-                intent: intent1,
+            intents.insert(
+                intent1.id,
+                TrackedIntent {
+                    intent: intent1,
+                    status: IntentStatus::Pending,
+                    result: None,
+                    attempts: Vec::new(),
+                    soft_warned: false,
+                },
+            );
+            intents.insert(
+                intent2.id,
+                TrackedIntent {
+                    intent: intent2,
+                    status: IntentStatus::Cancelled,
+                    result: None,
+                    attempts: Vec::new(),
+                    soft_warned: false,
+                },
+            );
+        }
+
+        assert_eq!(router.active_count().await, 1); // Only pending counts
+    }
+
+    #[tokio::test]
+    async fn test_report_failure_retries_on_a_different_peer() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let first_peer = NodeId::new();
+        let second_peer = NodeId::new();
+        capabilities.update_remote(first_peer, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(second_peer, vec![Capability::new("compute", "GPU")]);
+        cost_collector.update_peer_cost(
+            first_peer,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+        cost_collector.update_peer_cost(
+            second_peer,
+            NodeCost {
+                total_cost: 0.2,
+                ..Default::default()
+            },
+        );
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0);
+        let intent_id = intent.id;
+        let first = router.route(intent).await.unwrap();
+        assert_eq!(first.target(), first_peer);
+
+        let retried = router
+            .report_failure(intent_id, "peer unreachable".to_string())
+            .await
+            .unwrap();
+        assert_eq!(retried.target(), second_peer);
+
+        let attempts = router.get_attempts(intent_id).await;
+        assert_eq!(attempts.len(), 2);
+        assert!(matches!(attempts[0].outcome, AttemptOutcome::Failed { .. }));
+        assert_eq!(attempts[1].outcome, AttemptOutcome::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_routing_skips_peer_out_of_credits() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let cheap_peer = NodeId::new();
+        let pricier_peer = NodeId::new();
+        capabilities.update_remote(cheap_peer, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(pricier_peer, vec![Capability::new("compute", "GPU")]);
+        cost_collector.update_peer_cost(
+            cheap_peer,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+        cost_collector.update_peer_cost(
+            pricier_peer,
+            NodeCost {
+                total_cost: 0.2,
+                ..Default::default()
+            },
+        );
+
+        // Drain the cheap peer's credit balance outright, as if it had
+        // already been hammered with other work this round.
+        assert!(cost_collector.try_reserve_credits(cheap_peer, 10.0));
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0);
+        let decision = router.route(intent).await.unwrap();
+
+        assert_eq!(decision.target(), pricier_peer);
+    }
+
+    #[tokio::test]
+    async fn test_report_failure_terminates_after_max_attempts() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector))
+            .with_max_attempts(2);
+        router.set_local_node_id(NodeId::new()).await;
+
+        let peer_a = NodeId::new();
+        let peer_b = NodeId::new();
+        capabilities.update_remote(peer_a, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(peer_b, vec![Capability::new("compute", "GPU")]);
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0);
+        let intent_id = intent.id;
+        router.route(intent).await.unwrap();
+
+        router
+            .report_failure(intent_id, "first failure".to_string())
+            .await
+            .unwrap();
+
+        let result = router
+            .report_failure(intent_id, "second failure".to_string())
+            .await;
+        assert!(matches!(result, Err(AtmosphereError::NoCapablePeer(_))));
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert!(matches!(status, IntentStatus::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_report_failure_terminates_when_no_untried_peer_remains() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector))
+            .with_max_attempts(10);
+        router.set_local_node_id(NodeId::new()).await;
+
+        let only_peer = NodeId::new();
+        capabilities.update_remote(only_peer, vec![Capability::new("compute", "GPU")]);
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0);
+        let intent_id = intent.id;
+        router.route(intent).await.unwrap();
+
+        let result = router
+            .report_failure(intent_id, "unreachable".to_string())
+            .await;
+        assert!(matches!(result, Err(AtmosphereError::NoCapablePeer(_))));
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert!(matches!(status, IntentStatus::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_complete_marks_last_attempt_succeeded() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        capabilities.register(Capability::new("test", "Test Cap"));
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(capabilities, cost_collector);
+        router.set_local_node_id(NodeId::new()).await;
+
+        let intent = Intent::new("test", "action");
+        let intent_id = intent.id;
+        let decision = router.route(intent).await.unwrap();
+
+        router
+            .complete(
+                intent_id,
+                decision.target(),
+                serde_json::json!({"ok": true}),
+            )
+            .await;
+
+        let attempts = router.get_attempts(intent_id).await;
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].outcome, AttemptOutcome::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_times_out_stale_intent() {
+        let router = Arc::new(create_test_router());
+        router.set_local_node_id(NodeId::new()).await;
+
+        let intent = Intent::new("test", "action").with_timeout_ms(20);
+        let intent_id = intent.id;
+        router.active_intents.write().await.insert(
+            intent_id,
+            TrackedIntent {
+                intent,
                 status: IntentStatus::Pending,
                 result: None,
-            });
-            intents.insert(intent2.id, TrackedIntent {
-                intent: intent2,
-                status: IntentStatus::Cancelled,
+                attempts: Vec::new(),
+                soft_warned: false,
+            },
+        );
+
+        let handle = Arc::clone(&router).start();
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        handle.abort();
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert_eq!(status, IntentStatus::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_deadlines_leaves_fresh_intent_alone() {
+        let router = create_test_router();
+        router.set_local_node_id(NodeId::new()).await;
+
+        let intent = Intent::new("test", "action").with_timeout_ms(60_000);
+        let intent_id = intent.id;
+        router.active_intents.write().await.insert(
+            intent_id,
+            TrackedIntent {
+                intent,
+                status: IntentStatus::Pending,
                 result: None,
-            });
+                attempts: Vec::new(),
+                soft_warned: false,
+            },
+        );
+
+        router.sweep_deadlines().await;
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert_eq!(status, IntentStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_intent_preempts_lower_priority_routed() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let only_peer = NodeId::new();
+        capabilities.update_remote(only_peer, vec![Capability::new("compute", "GPU")]);
+        cost_collector.update_peer_cost(
+            only_peer,
+            NodeCost {
+                total_cost: 0.9,
+                ..Default::default()
+            },
+        );
+
+        let low_priority = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_priority(1)
+            .with_max_cost(1.0);
+        let low_priority_id = low_priority.id;
+        let low_decision = router.route(low_priority).await.unwrap();
+        assert_eq!(low_decision.target(), only_peer);
+
+        // Too cheap a budget for the same peer at its current cost, so the
+        // only way in is to bump the low-priority intent off it.
+        let high_priority = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_priority(9)
+            .with_max_cost(0.1);
+        let high_priority_id = high_priority.id;
+        let high_decision = router.route(high_priority).await.unwrap();
+        assert_eq!(high_decision.target(), only_peer);
+
+        let bumped_status = router.get_status(low_priority_id).await.unwrap();
+        assert_eq!(bumped_status, IntentStatus::Pending);
+
+        let winner_status = router.get_status(high_priority_id).await.unwrap();
+        assert!(matches!(winner_status, IntentStatus::Routed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_preemption_never_bumps_equal_or_higher_priority() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let only_peer = NodeId::new();
+        capabilities.update_remote(only_peer, vec![Capability::new("compute", "GPU")]);
+        cost_collector.update_peer_cost(
+            only_peer,
+            NodeCost {
+                total_cost: 0.9,
+                ..Default::default()
+            },
+        );
+
+        let existing = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_priority(5)
+            .with_max_cost(1.0);
+        let existing_id = existing.id;
+        router.route(existing).await.unwrap();
+
+        let contender = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_priority(5)
+            .with_max_cost(0.1);
+        let result = router.route(contender).await;
+
+        assert!(matches!(result, Err(AtmosphereError::NoCapablePeer(_))));
+        let existing_status = router.get_status(existing_id).await.unwrap();
+        assert!(matches!(existing_status, IntentStatus::Routed { .. }));
+    }
+
+    /// Always-acks or always-refuses `ClaimTransport`, so tests can drive
+    /// `RoutingMode::Coordinated` without a real mesh.
+    struct MockClaimTransport {
+        grants_quorum: bool,
+        announced: std::sync::Mutex<Vec<Claim>>,
+    }
+
+    impl MockClaimTransport {
+        fn new(grants_quorum: bool) -> Self {
+            Self {
+                grants_quorum,
+                announced: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ClaimTransport for MockClaimTransport {
+        async fn propose_claim(&self, _claim: &Claim, _timeout: Duration) -> bool {
+            self.grants_quorum
         }
 
-        assert_eq!(router.active_count().await, 1); // Only pending counts
+        async fn announce_claim(&self, claim: &Claim) {
+            self.announced.lock().unwrap().push(claim.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_routing_commits_claim_on_quorum() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        capabilities.register(Capability::new("test", "Test Cap"));
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let transport = Arc::new(MockClaimTransport::new(true));
+        let router = IntentRouter::new(capabilities, cost_collector)
+            .with_coordinated_routing(Arc::clone(&transport) as Arc<dyn ClaimTransport>);
+        let local_node_id = NodeId::new();
+        router.set_local_node_id(local_node_id).await;
+
+        assert_eq!(router.routing_mode(), RoutingMode::Coordinated);
+
+        let intent = Intent::new("test", "action");
+        let decision = router.route(intent).await.unwrap();
+
+        assert!(decision.is_local());
+        assert_eq!(transport.announced.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_routing_fails_when_quorum_is_lost() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        capabilities.register(Capability::new("test", "Test Cap"));
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let transport = Arc::new(MockClaimTransport::new(false));
+        let router = IntentRouter::new(capabilities, cost_collector)
+            .with_coordinated_routing(transport as Arc<dyn ClaimTransport>);
+        router.set_local_node_id(NodeId::new()).await;
+
+        let intent = Intent::new("test", "action");
+        let result = router.route(intent).await;
+
+        assert!(matches!(result, Err(AtmosphereError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_routing_adopts_already_committed_claim() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let local_node_id = NodeId::new();
+        capabilities.register(Capability::new("compute", "GPU"));
+        let remote_peer = NodeId::new();
+        capabilities.update_remote(remote_peer, vec![Capability::new("compute", "GPU")]);
+
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        // Quorum would refuse a fresh proposal, but the winning claim was
+        // already committed (e.g. observed from another node), so routing
+        // should adopt it instead of proposing and losing.
+        let transport = Arc::new(MockClaimTransport::new(false));
+        let router = IntentRouter::new(capabilities, cost_collector)
+            .with_coordinated_routing(transport as Arc<dyn ClaimTransport>);
+        router.set_local_node_id(local_node_id).await;
+
+        let intent = Intent::new("compute", "process").prefer_remote();
+        let capability_id = Uuid::new_v4();
+        router
+            .observe_claim(Claim {
+                intent_id: intent.id,
+                target: remote_peer,
+                capability_id,
+            })
+            .await;
+
+        let decision = router.route(intent).await.unwrap();
+
+        assert_eq!(decision.target(), remote_peer);
+        assert!(!decision.is_local());
+    }
+
+    #[tokio::test]
+    async fn test_hedged_intent_dispatches_to_the_n_cheapest_peers() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let cheap = NodeId::new();
+        let mid = NodeId::new();
+        let pricey = NodeId::new();
+        for peer in [cheap, mid, pricey] {
+            capabilities.update_remote(peer, vec![Capability::new("compute", "GPU")]);
+        }
+        cost_collector.update_peer_cost(
+            cheap,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+        cost_collector.update_peer_cost(
+            mid,
+            NodeCost {
+                total_cost: 0.3,
+                ..Default::default()
+            },
+        );
+        cost_collector.update_peer_cost(
+            pricey,
+            NodeCost {
+                total_cost: 0.9,
+                ..Default::default()
+            },
+        );
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0)
+            .with_hedging(2);
+        let intent_id = intent.id;
+        let decision = router.route(intent).await.unwrap();
+
+        match decision {
+            RoutingDecision::Hedged { targets, cost, .. } => {
+                assert_eq!(targets, vec![cheap, mid]);
+                assert!((cost - 0.4).abs() < 0.01);
+            }
+            RoutingDecision::Single { .. } => panic!("expected a hedged decision"),
+        }
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert!(matches!(status, IntentStatus::Executing { .. }));
+
+        let attempts = router.get_attempts(intent_id).await;
+        assert_eq!(attempts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_hedged_intent_falls_back_to_fewer_targets_when_short() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let only_peer = NodeId::new();
+        capabilities.update_remote(only_peer, vec![Capability::new("compute", "GPU")]);
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0)
+            .with_hedging(5);
+        let decision = router.route(intent).await.unwrap();
+
+        match decision {
+            RoutingDecision::Hedged { targets, .. } => assert_eq!(targets, vec![only_peer]),
+            RoutingDecision::Single { .. } => panic!("expected a hedged decision"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_intent_never_hedges_even_with_hedge_count_set() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let peer_a = NodeId::new();
+        let peer_b = NodeId::new();
+        capabilities.update_remote(peer_a, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(peer_b, vec![Capability::new("compute", "GPU")]);
+
+        let mut intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0)
+            .with_hedging(2);
+        // Gating flag explicitly overridden back off after `with_hedging`
+        // set it, the way a deserialized-then-mutated intent could end up.
+        intent.idempotent = false;
+
+        let decision = router.route(intent).await.unwrap();
+
+        assert!(matches!(decision, RoutingDecision::Single { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_complete_by_winning_target_cancels_hedged_stragglers() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let fast = NodeId::new();
+        let slow = NodeId::new();
+        capabilities.update_remote(fast, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(slow, vec![Capability::new("compute", "GPU")]);
+        cost_collector.update_peer_cost(
+            fast,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+        cost_collector.update_peer_cost(
+            slow,
+            NodeCost {
+                total_cost: 0.2,
+                ..Default::default()
+            },
+        );
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0)
+            .with_hedging(2);
+        let intent_id = intent.id;
+        router.route(intent).await.unwrap();
+
+        router
+            .complete(intent_id, fast, serde_json::json!({"ok": true}))
+            .await;
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert_eq!(status, IntentStatus::Completed { target: fast });
+
+        let attempts = router.get_attempts(intent_id).await;
+        let slow_attempt = attempts.iter().find(|a| a.target == slow).unwrap();
+        assert_eq!(slow_attempt.outcome, AttemptOutcome::Cancelled);
+        let fast_attempt = attempts.iter().find(|a| a.target == fast).unwrap();
+        assert_eq!(fast_attempt.outcome, AttemptOutcome::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn test_late_straggler_complete_does_not_override_the_winner() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = IntentRouter::new(Arc::clone(&capabilities), Arc::clone(&cost_collector));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let fast = NodeId::new();
+        let slow = NodeId::new();
+        capabilities.update_remote(fast, vec![Capability::new("compute", "GPU")]);
+        capabilities.update_remote(slow, vec![Capability::new("compute", "GPU")]);
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0)
+            .with_hedging(2);
+        let intent_id = intent.id;
+        router.route(intent).await.unwrap();
+
+        router
+            .complete(intent_id, fast, serde_json::json!({"winner": "fast"}))
+            .await;
+        router
+            .complete(intent_id, slow, serde_json::json!({"winner": "slow"}))
+            .await;
+
+        let status = router.get_status(intent_id).await.unwrap();
+        assert_eq!(status, IntentStatus::Completed { target: fast });
+        let result = router.get_result(intent_id).await.unwrap();
+        assert_eq!(result["winner"], "fast");
     }
 }
@@ -4,21 +4,47 @@
 //! Provides node management, gossip protocol, capability registry,
 //! cost collection, and intent routing.
 
+pub mod ble;
 pub mod capability;
+pub mod clock;
+pub mod consensus;
 pub mod cost;
+pub mod cost_store;
+pub mod discovery;
 pub mod error;
 pub mod intent;
 pub mod mesh;
 pub mod metrics;
 pub mod node;
+pub mod ring;
+pub mod routing;
+pub mod saga;
+pub mod sampler;
+pub mod scheduler;
+pub mod sim;
+pub mod store;
+pub mod swap;
 
+pub use ble::BleTransport;
 pub use capability::{Capability, CapabilityRegistry};
+pub use clock::{Clock, SimClock, SystemClock};
+pub use consensus::{AssignmentLog, Claim, ClaimTransport};
 pub use cost::{CostCollector, NodeCost};
+pub use cost_store::{CostSnapshot, CostStore, FileCostStore};
+pub use discovery::MdnsDiscovery;
 pub use error::{AtmosphereError, Result};
-pub use intent::{Intent, IntentRouter, IntentStatus};
-pub use mesh::{GossipMessage, MeshClient, PeerInfo};
+pub use intent::{Attempt, AttemptOutcome, Intent, IntentRouter, IntentStatus, RoutingMode};
+pub use mesh::{GossipEnvelope, GossipMessage, MeshClient, PeerInfo};
 pub use metrics::PlatformMetrics;
 pub use node::{AtmosphereNode, NodeConfig, NodeId};
+pub use ring::HashRing;
+pub use routing::RoutingTable;
+pub use saga::{GroupStatus, GroupStep, IntentGroup, SagaCoordinator};
+pub use sampler::{PeerDescriptor, PeerSampler};
+pub use scheduler::IntentScheduler;
+pub use sim::{MeshSimulation, SimNode};
+pub use store::{IntentStore, SqliteIntentStore};
+pub use swap::{SwapConfig, SwapManager};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
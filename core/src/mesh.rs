@@ -3,16 +3,27 @@
 //! Gossip protocol implementation for peer discovery and communication.
 //! Uses WebSocket connections for transport.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock};
 use serde::{Deserialize, Serialize};
-use ed25519_dalek::SigningKey;
-
+use uuid::Uuid;
+use ed25519_dalek::{Signature as Ed25519Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream};
+
+use crate::ble::BleTransport;
 use crate::capability::Capability;
+use crate::consensus::{Claim, ClaimTransport};
 use crate::cost::NodeCost;
 use crate::error::{AtmosphereError, Result};
 use crate::node::{NodeConfig, NodeId};
+use crate::routing::{Observation, RoutingTable, BUCKET_SIZE};
+use crate::sampler::{PeerDescriptor, PeerSampler, DEFAULT_VIEW_SIZE};
 
 /// Information about a connected peer
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,9 +92,10 @@ pub enum GossipMessage {
         error: Option<String>,
     },
 
-    /// Peer list for discovery
+    /// Peer sampling push/pull: a random subset of the sender's sampled
+    /// view, offered to the receiver's own `PeerSampler`.
     PeerList {
-        peers: Vec<String>, // addresses
+        peers: Vec<PeerDescriptor>,
     },
 
     /// Ping for keepalive
@@ -101,6 +113,126 @@ pub enum GossipMessage {
         node_id: NodeId,
         reason: String,
     },
+
+    /// `ClaimTransport::propose_claim`'s broadcast: "I intend to claim
+    /// `target` for `intent_id`." The receiver acks unconditionally with
+    /// `ClaimAck` - this only establishes that a quorum of the mesh is
+    /// live and reachable, not that the claim is conflict-free; conflicting
+    /// claims are arbitrated locally once committed, via
+    /// `AssignmentLog::observe`'s first-seen-wins rule.
+    ClaimPropose {
+        intent_id: Uuid,
+        target: NodeId,
+        capability_id: Uuid,
+    },
+
+    /// Acknowledges a `ClaimPropose` with the same `intent_id`, counted by
+    /// the proposing node toward the quorum `propose_claim` is waiting on.
+    ClaimAck {
+        intent_id: Uuid,
+    },
+
+    /// `ClaimTransport::announce_claim`'s broadcast: `intent_id` has
+    /// committed to `target`, so peers that lost (or never saw) the
+    /// proposal round learn the outcome without polling. `MeshClient` has
+    /// no `AssignmentLog` of its own to fold this into - a caller wanting
+    /// that (e.g. a second `IntentRouter` on the same node calling
+    /// `observe_claim`) needs to watch for this variant itself; for now
+    /// `GossipInbox::receive` only validates and forwards it.
+    ClaimAnnounce {
+        intent_id: Uuid,
+        target: NodeId,
+        capability_id: Uuid,
+    },
+}
+
+/// First message exchanged once the WebSocket connects: each side's claimed
+/// identity plus a fresh challenge nonce the other side must sign to prove
+/// it holds the matching private key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HandshakeHello {
+    node_id: NodeId,
+    public_key: String,
+    nonce: String,
+}
+
+/// Second message: a signature over the signer's own `node_id` plus the
+/// nonce the peer issued it. Binding `node_id` into the signed bytes means a
+/// signature can't be replayed under a different claimed identity - it only
+/// verifies against the `node_id` it was actually produced for. That alone
+/// only proves the signer controls the private key behind the `public_key`
+/// it claimed, not that the `node_id` it chose to sign actually belongs to
+/// that key - `run_handshake` separately checks `node_id ==
+/// NodeId::from_public_key(public_key)` so a fresh keypair can't claim an
+/// existing peer's identity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HandshakeProof {
+    signature: String,
+}
+
+/// How long a connect (including the handshake that follows it) is allowed
+/// to take before it's treated as a failure.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wraps an outbound/inbound `GossipMessage` with the sender's claimed
+/// `node_id`, a monotonic sequence number, and a signature over all three -
+/// closes the gap where any peer could forge a `Capabilities`/`Cost`
+/// message claiming another node's identity. `send_to`/`broadcast` seal
+/// every outbound message into one of these, and `receive_gossip` is the
+/// matching inbound check once a transport actually delivers one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GossipEnvelope {
+    pub node_id: NodeId,
+    pub sequence: u64,
+    pub timestamp_ms: u64,
+    pub message: GossipMessage,
+    pub signature: String,
+}
+
+impl GossipEnvelope {
+    /// Sign `message` as `node_id`/`sequence`, producing an envelope ready
+    /// to send.
+    fn seal(node_id: NodeId, sequence: u64, message: GossipMessage, signing_key: &SigningKey) -> Result<Self> {
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let signature = hex_encode(
+            signing_key
+                .sign(&Self::signed_bytes(node_id, sequence, timestamp_ms, &message)?)
+                .to_bytes()
+                .as_slice(),
+        );
+        Ok(Self { node_id, sequence, timestamp_ms, message, signature })
+    }
+
+    /// Verify this envelope's signature against `public_key`.
+    fn verify(&self, public_key: &VerifyingKey) -> Result<()> {
+        let signature_bytes: [u8; 64] = hex_decode(&self.signature)
+            .ok_or_else(|| AtmosphereError::Signature("malformed envelope signature".to_string()))?
+            .try_into()
+            .map_err(|_| AtmosphereError::Signature("envelope signature has the wrong length".to_string()))?;
+        let signature = Ed25519Signature::from_bytes(&signature_bytes);
+        let signed = Self::signed_bytes(self.node_id, self.sequence, self.timestamp_ms, &self.message)?;
+        public_key.verify(&signed, &signature).map_err(|_| {
+            AtmosphereError::Signature(format!(
+                "envelope from {} failed signature verification",
+                self.node_id
+            ))
+        })
+    }
+
+    /// Canonical bytes covered by the signature: the sender's claimed
+    /// identity and sequence number (so a relabeled or replayed envelope
+    /// fails verification) and the send timestamp, followed by the inner
+    /// message's JSON encoding.
+    fn signed_bytes(node_id: NodeId, sequence: u64, timestamp_ms: u64, message: &GossipMessage) -> Result<Vec<u8>> {
+        let mut bytes = node_id.0.as_bytes().to_vec();
+        bytes.extend_from_slice(&sequence.to_le_bytes());
+        bytes.extend_from_slice(&timestamp_ms.to_le_bytes());
+        bytes.extend_from_slice(&serde_json::to_vec(message)?);
+        Ok(bytes)
+    }
 }
 
 /// Connection state for a peer
@@ -116,6 +248,253 @@ pub enum ConnectionState {
 struct PeerConnection {
     info: PeerInfo,
     state: ConnectionState,
+    /// Highest `GossipEnvelope.sequence` accepted from this peer so far,
+    /// so a replayed or out-of-order envelope can be told apart from a
+    /// fresh one. `None` until the first envelope arrives.
+    last_sequence: Option<u64>,
+    /// Feeds `run_peer_writer`'s loop, which owns the actual socket write
+    /// half - `send_to`/`broadcast` enqueue here rather than writing
+    /// directly, since the socket itself lives on a spawned task.
+    outbound: mpsc::UnboundedSender<Message>,
+    /// `run_peer_reader`'s task, reading this peer's socket for as long as
+    /// the connection stays open. Aborted on `disconnect` so it doesn't
+    /// linger past the point this node considers the peer gone.
+    reader: tokio::task::JoinHandle<()>,
+}
+
+/// An in-flight `request()` call awaiting its `IntentResponse`.
+struct PendingRequest {
+    /// The peer the request was actually sent to over the wire - the final
+    /// destination if directly connected, otherwise the forwarding hop
+    /// chosen from the routing table - so a `disconnect` can fail just the
+    /// requests whose delivery path broke.
+    target: NodeId,
+    sender: oneshot::Sender<Result<String>>,
+}
+
+/// An in-flight `ClaimTransport::propose_claim` call, waiting on acks from a
+/// majority of connected peers (this node's own implicit vote already
+/// counted toward `needed`).
+struct PendingClaim {
+    /// Peers that have acked this proposal so far, so a duplicate or
+    /// replayed `ClaimAck` can't be double-counted.
+    acked: HashSet<NodeId>,
+    /// Remaining distinct acks required to reach quorum.
+    needed: usize,
+    /// Woken once `needed` is reached; `propose_claim` races this against
+    /// its own timeout.
+    notify: Option<oneshot::Sender<()>>,
+}
+
+/// The subset of `MeshClient`'s state needed to verify and process an
+/// inbound `GossipEnvelope` and to tear a peer down once its connection is
+/// gone. Cloned out of `MeshClient`'s `Arc` fields (cheap - every field here
+/// already is one) so `run_peer_reader`'s background task can own a
+/// `'static` copy without requiring `MeshClient` itself to be wrapped in an
+/// `Arc` just to be connected to a peer.
+#[derive(Clone)]
+struct GossipInbox {
+    node_id: NodeId,
+    signing_key: Arc<SigningKey>,
+    peers: Arc<RwLock<HashMap<NodeId, PeerConnection>>>,
+    sampler: Arc<PeerSampler>,
+    pending_requests: Arc<RwLock<HashMap<String, PendingRequest>>>,
+    pending_claims: Arc<RwLock<HashMap<Uuid, PendingClaim>>>,
+    next_sequence: Arc<RwLock<u64>>,
+    routing_table: Arc<RoutingTable>,
+}
+
+impl GossipInbox {
+    /// Verify an inbound `GossipEnvelope` received over `peer_id`'s
+    /// connection: its claimed `node_id` must match that connection's
+    /// authenticated identity, its signature must verify against the
+    /// peer's stored `public_key`, and its `sequence` must be newer than
+    /// the last one accepted from that peer. Returns the inner message
+    /// once all three hold.
+    async fn receive(&self, peer_id: &NodeId, envelope: GossipEnvelope) -> Result<GossipMessage> {
+        if envelope.node_id != *peer_id {
+            return Err(AtmosphereError::Signature(format!(
+                "envelope claims node_id {} but arrived over {}'s connection",
+                envelope.node_id, peer_id
+            )));
+        }
+
+        let mut peers = self.peers.write().await;
+        let peer = peers
+            .get_mut(peer_id)
+            .ok_or_else(|| AtmosphereError::Network(format!("Peer not connected: {}", peer_id)))?;
+
+        let public_key_bytes: [u8; 32] = hex_decode(&peer.info.public_key)
+            .ok_or_else(|| AtmosphereError::Signature("peer has no usable public key on file".to_string()))?
+            .try_into()
+            .map_err(|_| AtmosphereError::Signature("peer public key has the wrong length".to_string()))?;
+        let public_key = VerifyingKey::from_bytes(&public_key_bytes)
+            .map_err(|e| AtmosphereError::Signature(format!("invalid stored public key: {}", e)))?;
+
+        envelope.verify(&public_key)?;
+
+        if let Some(last) = peer.last_sequence {
+            if envelope.sequence <= last {
+                return Err(AtmosphereError::Signature(format!(
+                    "stale or replayed sequence number {} from peer {}",
+                    envelope.sequence, peer_id
+                )));
+            }
+        }
+        peer.last_sequence = Some(envelope.sequence);
+        peer.info.last_seen_ms = envelope.timestamp_ms;
+        drop(peers);
+
+        match &envelope.message {
+            GossipMessage::PeerList { peers } => {
+                self.sampler.merge(peers).await;
+                for peer in peers {
+                    self.observe_for_routing(peer.clone()).await;
+                }
+            }
+            GossipMessage::IntentResponse {
+                intent_id,
+                success,
+                result,
+                error,
+            } => {
+                self.complete_pending_request(intent_id, *success, result.clone(), error.clone())
+                    .await;
+            }
+            GossipMessage::ClaimPropose { intent_id, .. } => {
+                self.reply_to(peer_id, GossipMessage::ClaimAck { intent_id: *intent_id })
+                    .await;
+            }
+            GossipMessage::ClaimAck { intent_id } => {
+                self.record_claim_ack(*intent_id, *peer_id).await;
+            }
+            _ => {}
+        }
+
+        Ok(envelope.message)
+    }
+
+    /// Feed a candidate peer (freshly connected, or merely heard about via
+    /// gossip) into the routing table. If its bucket is already full, the
+    /// least-recently-seen entry is only evicted once it's confirmed to no
+    /// longer be a connected peer - a live connection is never displaced
+    /// just because a bucket filled up.
+    async fn observe_for_routing(&self, peer: PeerDescriptor) {
+        if let Some(Observation::BucketFull { stale }) =
+            self.routing_table.observe(peer.clone()).await
+        {
+            if !self.peers.read().await.contains_key(&stale.node_id) {
+                self.routing_table.replace_stale(&stale.node_id, peer).await;
+            }
+        }
+    }
+
+    /// Complete the pending `request()` matching `intent_id`, if any, with
+    /// the inbound `IntentResponse`'s outcome.
+    async fn complete_pending_request(
+        &self,
+        intent_id: &str,
+        success: bool,
+        result: Option<String>,
+        error: Option<String>,
+    ) {
+        if let Some(pending) = self.pending_requests.write().await.remove(intent_id) {
+            let outcome = if success {
+                Ok(result.unwrap_or_default())
+            } else {
+                Err(AtmosphereError::Internal(
+                    error.unwrap_or_else(|| "remote request failed".to_string()),
+                ))
+            };
+            let _ = pending.sender.send(outcome);
+        }
+    }
+
+    /// Remove `node_id` from the peer table, abort its reader task, drop it
+    /// from the routing table, and fail anything still waiting on a
+    /// response from it - the cleanup both an explicit `disconnect` and a
+    /// socket closing/erroring out from under `run_peer_reader` need.
+    async fn disconnect(&self, node_id: &NodeId) {
+        if let Some(peer) = self.peers.write().await.remove(node_id) {
+            peer.reader.abort();
+            tracing::info!(peer_id = %node_id, "Disconnected from peer");
+        }
+        self.routing_table.remove(node_id).await;
+        self.fail_pending_requests_to(node_id).await;
+    }
+
+    /// Fail every pending request waiting on `node_id` with
+    /// `AtmosphereError::ConnectionFailed`, since its peer is gone and no
+    /// response will ever arrive.
+    async fn fail_pending_requests_to(&self, node_id: &NodeId) {
+        let mut pending = self.pending_requests.write().await;
+        let stale: Vec<String> = pending
+            .iter()
+            .filter(|(_, p)| p.target == *node_id)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for intent_id in stale {
+            if let Some(p) = pending.remove(&intent_id) {
+                let _ = p.sender.send(Err(AtmosphereError::ConnectionFailed(format!(
+                    "peer {} disconnected before responding",
+                    node_id
+                ))));
+            }
+        }
+    }
+
+    /// Assign the next sequence number and sign `message` as this node,
+    /// producing a `GossipEnvelope` ready to send. Mirrors
+    /// `MeshClient::seal_outbound`, which delegates here so both the
+    /// foreground `send_to`/`broadcast` path and this background inbox
+    /// share one sequence counter.
+    async fn seal_outbound(&self, message: GossipMessage) -> Result<GossipEnvelope> {
+        let mut next_sequence = self.next_sequence.write().await;
+        let sequence = *next_sequence;
+        *next_sequence += 1;
+        drop(next_sequence);
+
+        GossipEnvelope::seal(self.node_id, sequence, message, &self.signing_key)
+    }
+
+    /// Seal and send `message` directly onto `peer_id`'s outbound channel,
+    /// for replies (like `ClaimAck`) generated from within `receive` itself
+    /// rather than by a caller going through `MeshClient::send_to`. Silently
+    /// dropped if the peer is gone or its channel is closed - the same as a
+    /// lost reply on a real network, nothing upstream is waiting on this
+    /// specific send succeeding.
+    async fn reply_to(&self, peer_id: &NodeId, message: GossipMessage) {
+        let envelope = match self.seal_outbound(message).await {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                tracing::warn!(peer_id = %peer_id, error = %e, "failed to seal reply");
+                return;
+            }
+        };
+        let json = match serde_json::to_string(&envelope) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!(peer_id = %peer_id, error = %e, "failed to encode reply");
+                return;
+            }
+        };
+        if let Some(peer) = self.peers.read().await.get(peer_id) {
+            let _ = peer.outbound.send(Message::Text(json));
+        }
+    }
+
+    /// Count `from`'s ack toward the `ClaimPropose` proposed under
+    /// `intent_id`, waking `propose_claim`'s waiter once quorum is reached.
+    async fn record_claim_ack(&self, intent_id: Uuid, from: NodeId) {
+        let mut pending_claims = self.pending_claims.write().await;
+        if let Some(pending) = pending_claims.get_mut(&intent_id) {
+            if pending.acked.insert(from) && pending.acked.len() >= pending.needed {
+                if let Some(notify) = pending.notify.take() {
+                    let _ = notify.send(());
+                }
+            }
+        }
+    }
 }
 
 /// Mesh network client
@@ -125,9 +504,10 @@ pub struct MeshClient {
     /// Our node ID
     node_id: NodeId,
 
-    /// Our signing key
-    #[allow(dead_code)]
-    signing_key: SigningKey,
+    /// Our signing key. `Arc`-wrapped so `GossipInbox` (and, through it,
+    /// `run_peer_reader`'s background task) can share it to sign replies
+    /// like `ClaimAck` without needing `MeshClient` itself behind an `Arc`.
+    signing_key: Arc<SigningKey>,
 
     /// Configuration
     config: NodeConfig,
@@ -137,6 +517,36 @@ pub struct MeshClient {
 
     /// Running state
     running: Arc<RwLock<bool>>,
+
+    /// Optional BLE/GATT transport state, kept alongside the IP transport.
+    /// Peers ingested into its `BleCentral` are merged into `get_peers`;
+    /// `BleTransport` itself makes no Bluetooth platform calls.
+    ble: Option<BleTransport>,
+
+    /// Next `GossipEnvelope.sequence` this node will sign an outbound
+    /// message with, so a receiver can tell a replayed envelope from a
+    /// fresh one.
+    next_sequence: Arc<RwLock<u64>>,
+
+    /// Bounded, attack-resistant sample of the wider mesh population,
+    /// refreshed by periodic `PeerList` gossip rather than by explicit
+    /// `connect` calls - lets routing reach peers this node holds no
+    /// direct WebSocket connection to.
+    sampler: Arc<PeerSampler>,
+
+    /// In-flight `request()` calls, keyed by the `intent_id` they're
+    /// waiting on, completed by `receive_gossip` when the matching
+    /// `IntentResponse` arrives.
+    pending_requests: Arc<RwLock<HashMap<String, PendingRequest>>>,
+
+    /// In-flight `propose_claim` calls, keyed by `intent_id`, completed by
+    /// `GossipInbox::record_claim_ack` as `ClaimAck`s arrive.
+    pending_claims: Arc<RwLock<HashMap<Uuid, PendingClaim>>>,
+
+    /// Kademlia-style k-bucket routing table over every peer this node has
+    /// connected to or heard about via gossip, used to pick a forwarding
+    /// hop for a peer we hold no direct connection to.
+    routing_table: Arc<RoutingTable>,
 }
 
 impl MeshClient {
@@ -144,41 +554,108 @@ impl MeshClient {
     pub fn new(node_id: NodeId, signing_key: SigningKey, config: NodeConfig) -> Self {
         Self {
             node_id,
-            signing_key,
+            signing_key: Arc::new(signing_key),
             config,
             peers: Arc::new(RwLock::new(HashMap::new())),
             running: Arc::new(RwLock::new(false)),
+            ble: None,
+            next_sequence: Arc::new(RwLock::new(0)),
+            sampler: Arc::new(PeerSampler::new(DEFAULT_VIEW_SIZE)),
+            pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            pending_claims: Arc::new(RwLock::new(HashMap::new())),
+            routing_table: Arc::new(RoutingTable::new(node_id)),
+        }
+    }
+
+    /// Enable the BLE/GATT transport alongside the IP transport, marking
+    /// advertising and scanning as started. No Bluetooth platform calls are
+    /// made; BLE peers arrive only through `BleCentral::ingest_peer`.
+    pub fn enable_ble(&mut self) {
+        let transport = BleTransport::new(self.node_id);
+        transport.start();
+        self.ble = Some(transport);
+    }
+
+    /// Disable the BLE transport, if enabled.
+    pub fn disable_ble(&mut self) {
+        if let Some(transport) = self.ble.take() {
+            transport.stop();
+        }
+    }
+
+    /// Peers discovered over BLE only (not yet merged into `peers`).
+    pub fn ble_peers(&self) -> Vec<PeerInfo> {
+        self.ble.as_ref().map(|t| t.peers()).unwrap_or_default()
+    }
+
+    /// The `GossipInbox` view of this client's own `Arc` fields, fresh for
+    /// each call - cheap, since every field it holds is itself an `Arc`.
+    fn inbox(&self) -> GossipInbox {
+        GossipInbox {
+            node_id: self.node_id,
+            signing_key: Arc::clone(&self.signing_key),
+            peers: Arc::clone(&self.peers),
+            sampler: Arc::clone(&self.sampler),
+            pending_requests: Arc::clone(&self.pending_requests),
+            pending_claims: Arc::clone(&self.pending_claims),
+            next_sequence: Arc::clone(&self.next_sequence),
+            routing_table: Arc::clone(&self.routing_table),
         }
     }
 
-    /// Connect to a peer at the given address
+    /// Connect to a peer at the given address.
+    ///
+    /// Opens a real WebSocket connection and runs a mutual challenge-nonce
+    /// handshake over it before the peer is ever inserted into `peers`: each
+    /// side proves ownership of its claimed public key by signing the
+    /// other's nonce, so a connected peer's `node_id`/`public_key` are
+    /// cryptographically authenticated rather than self-reported. Once the
+    /// handshake succeeds, the socket is split and kept alive for the life
+    /// of the connection: a writer task drains `send_to`/`broadcast`'s
+    /// `outbound` channel onto it, and a reader task (`run_peer_reader`)
+    /// feeds every inbound frame through `receive_gossip` - the connection
+    /// isn't just used for the handshake and then dropped.
     pub async fn connect(&self, address: &str) -> Result<NodeId> {
         tracing::info!(address = %address, "Connecting to peer");
 
-        // For now, we create a placeholder connection
-        // Real implementation would use tokio-tungstenite
-        let peer_id = NodeId::new(); // Would be received from handshake
+        let mut ws_stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, connect_async(address)).await {
+            Ok(Ok((ws_stream, _response))) => ws_stream,
+            Ok(Err(e)) => return Err(AtmosphereError::ConnectionFailed(e.to_string())),
+            Err(_) => return Err(AtmosphereError::Timeout(format!("connecting to {}", address))),
+        };
 
-        let now_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as u64)
-            .unwrap_or(0);
+        let peer_info = match tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            run_handshake(&mut ws_stream, self.node_id, &self.signing_key, address),
+        )
+        .await
+        {
+            Ok(result) => result?,
+            Err(_) => return Err(AtmosphereError::Timeout(format!("handshake with {}", address))),
+        };
+
+        let peer_id = peer_info.node_id;
+        let descriptor = PeerDescriptor {
+            node_id: peer_id,
+            address: peer_info.address.clone(),
+        };
+
+        let (write_half, read_half) = ws_stream.split();
+        let (outbound, outbound_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_peer_writer(write_half, outbound_rx));
+        let reader = tokio::spawn(run_peer_reader(self.inbox(), peer_id, read_half));
 
         let peer = PeerConnection {
-            info: PeerInfo {
-                node_id: peer_id,
-                public_key: String::new(),
-                name: format!("peer-{}", &peer_id.0.to_string()[..8]),
-                address: address.to_string(),
-                connected_at_ms: now_ms,
-                last_seen_ms: now_ms,
-                capabilities: Vec::new(),
-                cost: None,
-            },
+            info: peer_info,
             state: ConnectionState::Connected,
+            last_sequence: None,
+            outbound,
+            reader,
         };
 
         self.peers.write().await.insert(peer_id, peer);
+        self.sampler.offer(&[descriptor.clone()]).await;
+        self.inbox().observe_for_routing(descriptor).await;
         tracing::info!(peer_id = %peer_id, "Connected to peer");
 
         Ok(peer_id)
@@ -186,30 +663,39 @@ impl MeshClient {
 
     /// Disconnect from a specific peer
     pub async fn disconnect(&self, node_id: &NodeId) -> Result<()> {
-        if let Some(mut peer) = self.peers.write().await.remove(node_id) {
-            peer.state = ConnectionState::Disconnected;
-            tracing::info!(peer_id = %node_id, "Disconnected from peer");
-        }
+        self.inbox().disconnect(node_id).await;
         Ok(())
     }
 
     /// Disconnect from all peers
     pub async fn disconnect_all(&self) {
-        let mut peers = self.peers.write().await;
-        for (id, _) in peers.drain() {
-            tracing::info!(peer_id = %id, "Disconnected from peer");
+        let ids: Vec<NodeId> = self.peers.read().await.keys().copied().collect();
+        for id in ids {
+            self.inbox().disconnect(&id).await;
         }
     }
 
-    /// Get list of connected peers
+    /// Get list of connected peers, unifying IP-connected peers with any
+    /// discovered over BLE so callers (capability registry, cost collector)
+    /// see one consistent peer set regardless of transport.
     pub async fn get_peers(&self) -> Vec<PeerInfo> {
-        self.peers
+        let mut peers: Vec<PeerInfo> = self
+            .peers
             .read()
             .await
             .values()
             .filter(|p| p.state == ConnectionState::Connected)
             .map(|p| p.info.clone())
-            .collect()
+            .collect();
+
+        let known: std::collections::HashSet<NodeId> = peers.iter().map(|p| p.node_id).collect();
+        for ble_peer in self.ble_peers() {
+            if !known.contains(&ble_peer.node_id) {
+                peers.push(ble_peer);
+            }
+        }
+
+        peers
     }
 
     /// Get a specific peer's info
@@ -227,33 +713,50 @@ impl MeshClient {
             .count()
     }
 
-    /// Send a gossip message to a specific peer
+    /// Send a gossip message to a specific peer, signed as this node so the
+    /// receiver can authenticate it against our `public_key`. Enqueues onto
+    /// the peer's `outbound` channel, which `run_peer_writer` drains onto
+    /// the actual socket.
     pub async fn send_to(&self, node_id: &NodeId, message: GossipMessage) -> Result<()> {
-        let peers = self.peers.read().await;
-        
-        if !peers.contains_key(node_id) {
+        if !self.peers.read().await.contains_key(node_id) {
             return Err(AtmosphereError::Network(format!(
                 "Peer not connected: {}",
                 node_id
             )));
         }
 
-        // Real implementation would serialize and send via WebSocket
-        let _json = serde_json::to_string(&message)?;
-        tracing::debug!(peer_id = %node_id, message_type = ?std::mem::discriminant(&message), "Sent message");
+        let envelope = self.seal_outbound(message).await?;
+        let json = serde_json::to_string(&envelope)?;
+
+        let peers = self.peers.read().await;
+        let peer = peers
+            .get(node_id)
+            .ok_or_else(|| AtmosphereError::Network(format!("Peer not connected: {}", node_id)))?;
+        peer.outbound.send(Message::Text(json)).map_err(|_| {
+            AtmosphereError::ConnectionFailed(format!("connection to {} is closed", node_id))
+        })?;
+
+        tracing::debug!(peer_id = %node_id, message_type = ?std::mem::discriminant(&envelope.message), "Sent message");
 
         Ok(())
     }
 
-    /// Broadcast a message to all connected peers
+    /// Broadcast a message to all connected peers, signed once as this node
+    /// so every receiver can authenticate the same envelope. Each peer with
+    /// a closed `outbound` channel is silently skipped rather than failing
+    /// the whole broadcast - `run_peer_reader` will clean its entry out of
+    /// `peers` once it notices the same thing.
     pub async fn broadcast(&self, message: GossipMessage) -> Result<usize> {
+        let envelope = self.seal_outbound(message).await?;
+        let json = serde_json::to_string(&envelope)?;
+
         let peers = self.peers.read().await;
         let mut sent = 0;
 
-        let _json = serde_json::to_string(&message)?;
-
         for (node_id, peer) in peers.iter() {
-            if peer.state == ConnectionState::Connected {
+            if peer.state == ConnectionState::Connected
+                && peer.outbound.send(Message::Text(json.clone())).is_ok()
+            {
                 tracing::debug!(peer_id = %node_id, "Broadcasting message");
                 sent += 1;
             }
@@ -262,6 +765,142 @@ impl MeshClient {
         Ok(sent)
     }
 
+    /// Verify an inbound `GossipEnvelope` received over `peer_id`'s
+    /// connection: its claimed `node_id` must match that connection's
+    /// authenticated identity, its signature must verify against the
+    /// peer's stored `public_key`, and its `sequence` must be newer than
+    /// the last one accepted from that peer. Returns the inner message
+    /// once all three hold. `run_peer_reader` calls this for every frame
+    /// read off a peer's socket; tests call it directly to exercise the
+    /// verification logic without a real connection.
+    pub async fn receive_gossip(&self, peer_id: &NodeId, envelope: GossipEnvelope) -> Result<GossipMessage> {
+        self.inbox().receive(peer_id, envelope).await
+    }
+
+    /// Assign the next sequence number and sign `message` as this node,
+    /// producing a `GossipEnvelope` ready to send. Delegates to
+    /// `GossipInbox::seal_outbound` so this and the background reader task
+    /// share one sequence counter.
+    async fn seal_outbound(&self, message: GossipMessage) -> Result<GossipEnvelope> {
+        self.inbox().seal_outbound(message).await
+    }
+
+    /// The current epidemic peer-sample view: a bounded, near-uniform
+    /// random subset of the mesh that routing can fall back on when a peer
+    /// isn't among `get_peers()`'s live connections.
+    pub async fn sampled_peers(&self) -> Vec<PeerDescriptor> {
+        self.sampler.view().await
+    }
+
+    /// Push the half of one epidemic gossip round this node drives: send a
+    /// random subset of our sampled view to `peer_id`. The pull half - that
+    /// peer's own push, arriving back through `receive_gossip` - is merged
+    /// into the sampler automatically there.
+    pub async fn gossip_sample_push(&self, peer_id: &NodeId) -> Result<()> {
+        let subset = self.sampler.push_subset().await;
+        self.send_to(peer_id, GossipMessage::PeerList { peers: subset }).await
+    }
+
+    /// Merge a peer's `PeerList` push (or our own connections) into the
+    /// local sampled view.
+    pub async fn merge_sampled_peers(&self, peers: &[PeerDescriptor]) {
+        self.sampler.merge(peers).await;
+    }
+
+    /// Regenerate the sampler's per-slot seeds, as the Brahms algorithm
+    /// requires periodically to keep an adversary who has inferred the
+    /// current seeds from gaming future rounds indefinitely.
+    pub async fn reseed_sampler(&self) {
+        self.sampler.reseed().await;
+    }
+
+    /// The `count` known peers closest to `target_id` by XOR distance,
+    /// closest first - used to pick a forwarding hop for a peer this node
+    /// holds no direct connection to.
+    pub async fn closest_peers(&self, target_id: &NodeId, count: usize) -> Vec<PeerDescriptor> {
+        self.routing_table.closest_peers(target_id, count).await
+    }
+
+    /// Resolve the peer a message bound for `node_id` should actually be
+    /// sent to: `node_id` itself if it's a direct connection, otherwise the
+    /// closest connected peer to it in the routing table, as a forwarding
+    /// hop. `Err(AtmosphereError::NoRoute)` if neither is available.
+    async fn forwarding_hop(&self, node_id: &NodeId) -> Result<NodeId> {
+        if self.peers.read().await.contains_key(node_id) {
+            return Ok(*node_id);
+        }
+
+        let connected = self.peers.read().await;
+        self.routing_table
+            .closest_peers(node_id, BUCKET_SIZE)
+            .await
+            .into_iter()
+            .find(|candidate| connected.contains_key(&candidate.node_id))
+            .map(|candidate| candidate.node_id)
+            .ok_or_else(|| {
+                AtmosphereError::NoRoute(format!(
+                    "no connected peer can forward toward {}",
+                    node_id
+                ))
+            })
+    }
+
+    /// Send an `IntentRequest` to `node_id` and await its matching
+    /// `IntentResponse`, turning the gossip channel into a request/response
+    /// substrate. If `node_id` isn't a directly connected peer, the request
+    /// is forwarded through the routing table's closest connected peer to
+    /// it instead, enabling multi-hop delivery without every node needing a
+    /// connection to every other. Resolves with `Err(AtmosphereError::Timeout)`
+    /// if no response arrives within `timeout`, `Err(AtmosphereError::NoRoute)`
+    /// if no peer can forward toward `node_id` at all, or
+    /// `Err(AtmosphereError::Internal)` if the peer reports failure.
+    pub async fn request(
+        &self,
+        node_id: &NodeId,
+        capability_type: impl Into<String>,
+        payload: impl Into<String>,
+        timeout: Duration,
+    ) -> Result<String> {
+        let next_hop = self.forwarding_hop(node_id).await?;
+
+        let intent_id = Uuid::new_v4().to_string();
+        let (sender, receiver) = oneshot::channel();
+
+        self.pending_requests.write().await.insert(
+            intent_id.clone(),
+            PendingRequest {
+                target: next_hop,
+                sender,
+            },
+        );
+
+        let message = GossipMessage::IntentRequest {
+            intent_id: intent_id.clone(),
+            capability_type: capability_type.into(),
+            payload: payload.into(),
+        };
+
+        if let Err(e) = self.send_to(&next_hop, message).await {
+            self.pending_requests.write().await.remove(&intent_id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AtmosphereError::Internal(format!(
+                "pending request {} dropped without a response",
+                intent_id
+            ))),
+            Err(_) => {
+                self.pending_requests.write().await.remove(&intent_id);
+                Err(AtmosphereError::Timeout(format!(
+                    "waiting for response to intent {}",
+                    intent_id
+                )))
+            }
+        }
+    }
+
     /// Update a peer's capabilities
     pub async fn update_peer_capabilities(&self, node_id: &NodeId, capabilities: Vec<Capability>) {
         if let Some(peer) = self.peers.write().await.get_mut(node_id) {
@@ -290,6 +929,69 @@ impl MeshClient {
     }
 }
 
+#[async_trait::async_trait]
+impl ClaimTransport for MeshClient {
+    /// Broadcast a `ClaimPropose` and wait for a majority of connected
+    /// peers to `ClaimAck` it, counting this node's own implicit vote
+    /// toward the majority the same way a Raft leader counts itself. With
+    /// zero connected peers, a lone node is trivially its own majority.
+    async fn propose_claim(&self, claim: &Claim, timeout: Duration) -> bool {
+        let peer_count = self.peer_count().await;
+        let total = peer_count + 1;
+        let majority = total / 2 + 1;
+        let needed = majority - 1;
+
+        if needed == 0 {
+            let _ = self
+                .broadcast(GossipMessage::ClaimPropose {
+                    intent_id: claim.intent_id,
+                    target: claim.target,
+                    capability_id: claim.capability_id,
+                })
+                .await;
+            return true;
+        }
+
+        let (notify_tx, notify_rx) = oneshot::channel();
+        self.pending_claims.write().await.insert(
+            claim.intent_id,
+            PendingClaim {
+                acked: HashSet::new(),
+                needed,
+                notify: Some(notify_tx),
+            },
+        );
+
+        if self
+            .broadcast(GossipMessage::ClaimPropose {
+                intent_id: claim.intent_id,
+                target: claim.target,
+                capability_id: claim.capability_id,
+            })
+            .await
+            .is_err()
+        {
+            self.pending_claims.write().await.remove(&claim.intent_id);
+            return false;
+        }
+
+        let reached = tokio::time::timeout(timeout, notify_rx).await.is_ok();
+        self.pending_claims.write().await.remove(&claim.intent_id);
+        reached
+    }
+
+    /// Broadcast that `claim` has committed.
+    async fn announce_claim(&self, claim: &Claim) {
+        let _ = self
+            .broadcast(GossipMessage::ClaimAnnounce {
+                intent_id: claim.intent_id,
+                target: claim.target,
+                capability_id: claim.capability_id,
+            })
+            .await;
+    }
+}
+
 impl std::fmt::Debug for MeshClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MeshClient")
@@ -299,23 +1001,239 @@ impl std::fmt::Debug for MeshClient {
     }
 }
 
+/// Run the mutual handshake over an already-connected WebSocket, whichever
+/// side established it: both dialing (`MeshClient::connect`) and accepting
+/// run the exact same exchange, since it's symmetric. Each side sends a
+/// `HandshakeHello` (claimed identity + a fresh nonce), then a
+/// `HandshakeProof` signing the peer's nonce together with its own claimed
+/// `node_id`. The peer's identity is only trusted once that signature
+/// verifies against the public key it claimed.
+async fn run_handshake<S>(
+    ws: &mut WebSocketStream<S>,
+    my_node_id: NodeId,
+    signing_key: &SigningKey,
+    address: &str,
+) -> Result<PeerInfo>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut my_nonce = [0u8; 32];
+    OsRng.fill_bytes(&mut my_nonce);
+
+    let hello = HandshakeHello {
+        node_id: my_node_id,
+        public_key: hex_encode(signing_key.verifying_key().as_bytes()),
+        nonce: hex_encode(&my_nonce),
+    };
+    send_json(ws, &hello).await?;
+    let peer_hello: HandshakeHello = recv_json(ws).await?;
+
+    let peer_nonce = hex_decode(&peer_hello.nonce)
+        .ok_or_else(|| AtmosphereError::Signature("malformed peer nonce".to_string()))?;
+
+    let mut my_signed_bytes = my_node_id.0.as_bytes().to_vec();
+    my_signed_bytes.extend_from_slice(&peer_nonce);
+    let proof = HandshakeProof {
+        signature: hex_encode(signing_key.sign(&my_signed_bytes).to_bytes().as_slice()),
+    };
+    send_json(ws, &proof).await?;
+    let peer_proof: HandshakeProof = recv_json(ws).await?;
+
+    let peer_public_key_bytes: [u8; 32] = hex_decode(&peer_hello.public_key)
+        .ok_or_else(|| AtmosphereError::Signature("malformed peer public key".to_string()))?
+        .try_into()
+        .map_err(|_| AtmosphereError::Signature("peer public key has the wrong length".to_string()))?;
+    let peer_verifying_key = VerifyingKey::from_bytes(&peer_public_key_bytes)
+        .map_err(|e| AtmosphereError::Signature(format!("invalid peer public key: {}", e)))?;
+
+    // A valid signature alone only proves the peer holds the private key
+    // matching `peer_hello.public_key` - it says nothing about whether
+    // `peer_hello.node_id` is actually that key's identity rather than an
+    // arbitrary value the peer chose to claim this session. Require the two
+    // to agree, closing off an attacker minting a fresh keypair to claim an
+    // existing peer's `node_id`.
+    let expected_node_id = NodeId::from_public_key(&peer_verifying_key);
+    if peer_hello.node_id != expected_node_id {
+        return Err(AtmosphereError::Signature(format!(
+            "peer claimed node_id {} but its public key derives to {}",
+            peer_hello.node_id, expected_node_id
+        )));
+    }
+
+    let peer_signature_bytes: [u8; 64] = hex_decode(&peer_proof.signature)
+        .ok_or_else(|| AtmosphereError::Signature("malformed peer signature".to_string()))?
+        .try_into()
+        .map_err(|_| AtmosphereError::Signature("peer signature has the wrong length".to_string()))?;
+    let peer_signature = Ed25519Signature::from_bytes(&peer_signature_bytes);
+
+    let mut expected_bytes = peer_hello.node_id.0.as_bytes().to_vec();
+    expected_bytes.extend_from_slice(&my_nonce);
+    peer_verifying_key.verify(&expected_bytes, &peer_signature).map_err(|_| {
+        AtmosphereError::Signature(format!(
+            "peer {} failed to prove ownership of its claimed public key",
+            peer_hello.node_id
+        ))
+    })?;
+
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    Ok(PeerInfo {
+        node_id: peer_hello.node_id,
+        public_key: peer_hello.public_key,
+        name: format!("peer-{}", &peer_hello.node_id.0.to_string()[..8]),
+        address: address.to_string(),
+        connected_at_ms: now_ms,
+        last_seen_ms: now_ms,
+        capabilities: Vec::new(),
+        cost: None,
+    })
+}
+
+async fn send_json<S, T>(ws: &mut WebSocketStream<S>, value: &T) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let json = serde_json::to_string(value)?;
+    ws.send(Message::Text(json))
+        .await
+        .map_err(|e| AtmosphereError::ConnectionFailed(e.to_string()))
+}
+
+async fn recv_json<S, T>(ws: &mut WebSocketStream<S>) -> Result<T>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    T: for<'de> Deserialize<'de>,
+{
+    match ws.next().await {
+        Some(Ok(Message::Text(text))) => Ok(serde_json::from_str(&text)?),
+        Some(Ok(_)) => Err(AtmosphereError::ConnectionFailed(
+            "unexpected frame during handshake".to_string(),
+        )),
+        Some(Err(e)) => Err(AtmosphereError::ConnectionFailed(e.to_string())),
+        None => Err(AtmosphereError::ConnectionFailed(
+            "peer closed the connection during handshake".to_string(),
+        )),
+    }
+}
+
+/// Drain a peer's `outbound` channel onto its actual socket for as long as
+/// the channel stays open, so `send_to`/`broadcast` can hand off a message
+/// without blocking on the write half directly. Ends (and drops the sink,
+/// closing the socket) once the channel closes or a write fails.
+async fn run_peer_writer<S>(mut sink: SplitSink<WebSocketStream<S>, Message>, mut outbound: mpsc::UnboundedReceiver<Message>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    while let Some(message) = outbound.recv().await {
+        if sink.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Read `GossipEnvelope`s off a peer's socket for as long as the connection
+/// stays open, handing each to `inbox` for the same verification
+/// `receive_gossip` does - this is what actually drives gossip, RPC
+/// (`request`), and consensus claims over the network once `connect`'s
+/// handshake completes, rather than `MeshClient` only ever talking to
+/// itself via tests calling `receive_gossip` directly. Cleans up the peer's
+/// table entry via `GossipInbox::disconnect` once the socket closes or
+/// errors, the same as an explicit `disconnect` would.
+async fn run_peer_reader<S>(inbox: GossipInbox, peer_id: NodeId, mut stream: SplitStream<WebSocketStream<S>>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    while let Some(frame) = stream.next().await {
+        let text = match frame {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::warn!(peer_id = %peer_id, error = %e, "peer connection read error");
+                break;
+            }
+        };
+
+        match serde_json::from_str::<GossipEnvelope>(&text) {
+            Ok(envelope) => {
+                if let Err(e) = inbox.receive(&peer_id, envelope).await {
+                    tracing::warn!(peer_id = %peer_id, error = %e, "rejected inbound gossip envelope");
+                }
+            }
+            Err(e) => tracing::warn!(peer_id = %peer_id, error = %e, "malformed gossip envelope"),
+        }
+    }
+
+    inbox.disconnect(&peer_id).await;
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::rngs::OsRng;
 
     fn create_test_client() -> MeshClient {
-        let node_id = NodeId::new();
         let signing_key = SigningKey::generate(&mut OsRng);
+        let node_id = NodeId::from_public_key(&signing_key.verifying_key());
         let config = NodeConfig::default();
         MeshClient::new(node_id, signing_key, config)
     }
 
+    /// Accept one inbound WebSocket connection and run the peer side of the
+    /// handshake against it - the handshake is symmetric, so this reuses
+    /// `run_handshake` directly rather than faking a simpler protocol -
+    /// giving tests a real socket to connect `MeshClient::connect` against.
+    async fn spawn_mock_peer() -> String {
+        spawn_mock_peer_full().await.0
+    }
+
+    /// Like `spawn_mock_peer`, but also returns the mock peer's `node_id`
+    /// and `SigningKey` so a test can author `GossipEnvelope`s "from" it.
+    async fn spawn_mock_peer_full() -> (String, NodeId, SigningKey) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let peer_id = NodeId::from_public_key(&signing_key.verifying_key());
+
+        let handshake_key = signing_key.clone();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            if run_handshake(&mut ws, peer_id, &handshake_key, "mock").await.is_ok() {
+                // A real peer keeps its socket open past the handshake;
+                // mirror that here so `send_to`/`broadcast` in a test have
+                // a live connection to write onto instead of one the mock
+                // already dropped.
+                while ws.next().await.is_some() {}
+            }
+        });
+
+        (format!("ws://{}", addr), peer_id, signing_key)
+    }
+
     #[tokio::test]
     async fn test_connect_disconnect() {
         let client = create_test_client();
+        let address = spawn_mock_peer().await;
 
-        let peer_id = client.connect("ws://localhost:8765").await.unwrap();
+        let peer_id = client.connect(&address).await.unwrap();
         assert_eq!(client.peer_count().await, 1);
 
         client.disconnect(&peer_id).await.unwrap();
@@ -326,8 +1244,8 @@ mod tests {
     async fn test_disconnect_all() {
         let client = create_test_client();
 
-        client.connect("ws://localhost:8765").await.unwrap();
-        client.connect("ws://localhost:8766").await.unwrap();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
         assert_eq!(client.peer_count().await, 2);
 
         client.disconnect_all().await;
@@ -337,20 +1255,21 @@ mod tests {
     #[tokio::test]
     async fn test_get_peers() {
         let client = create_test_client();
+        let address = spawn_mock_peer().await;
+
+        client.connect(&address).await.unwrap();
 
-        client.connect("ws://localhost:8765").await.unwrap();
-        
         let peers = client.get_peers().await;
         assert_eq!(peers.len(), 1);
-        assert_eq!(peers[0].address, "ws://localhost:8765");
+        assert_eq!(peers[0].address, address);
     }
 
     #[tokio::test]
     async fn test_send_message() {
         let client = create_test_client();
 
-        let peer_id = client.connect("ws://localhost:8765").await.unwrap();
-        
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
         let message = GossipMessage::Ping {
             timestamp_ms: 12345,
         };
@@ -373,8 +1292,8 @@ mod tests {
     async fn test_broadcast() {
         let client = create_test_client();
 
-        client.connect("ws://localhost:8765").await.unwrap();
-        client.connect("ws://localhost:8766").await.unwrap();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
 
         let message = GossipMessage::Capabilities {
             node_id: NodeId::new(),
@@ -405,8 +1324,8 @@ mod tests {
     async fn test_update_peer_capabilities() {
         let client = create_test_client();
 
-        let peer_id = client.connect("ws://localhost:8765").await.unwrap();
-        
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
         let caps = vec![
             crate::capability::Capability::new("camera", "Test Camera"),
         ];
@@ -416,4 +1335,358 @@ mod tests {
         let peer = client.get_peer(&peer_id).await.unwrap();
         assert_eq!(peer.capabilities.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_get_peers_merges_ble_peers() {
+        let mut client = create_test_client();
+        client.enable_ble();
+
+        client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let ble_node_id = NodeId::new();
+        client.ble.as_ref().unwrap().central.ingest_peer(crate::ble::BlePeer {
+            info: PeerInfo {
+                node_id: ble_node_id,
+                public_key: String::new(),
+                name: "ble-peer".to_string(),
+                address: "ble://aa:bb:cc:dd:ee:ff".to_string(),
+                connected_at_ms: 0,
+                last_seen_ms: 0,
+                capabilities: Vec::new(),
+                cost: None,
+            },
+            device_address: "aa:bb:cc:dd:ee:ff".to_string(),
+            rssi: None,
+        });
+
+        let peers = client.get_peers().await;
+        assert_eq!(peers.len(), 2);
+        assert!(peers.iter().any(|p| p.node_id == ble_node_id));
+    }
+
+    #[tokio::test]
+    async fn test_receive_gossip_accepts_valid_envelope() {
+        let client = create_test_client();
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let message = GossipMessage::Ping { timestamp_ms: 42 };
+        let envelope = GossipEnvelope::seal(peer_id, 0, message, &peer_key).unwrap();
+
+        let received = client.receive_gossip(&peer_id, envelope).await.unwrap();
+        assert!(matches!(received, GossipMessage::Ping { timestamp_ms: 42 }));
+    }
+
+    #[tokio::test]
+    async fn test_receive_gossip_rejects_tampered_signature() {
+        let client = create_test_client();
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let mut envelope =
+            GossipEnvelope::seal(peer_id, 0, GossipMessage::Ping { timestamp_ms: 1 }, &peer_key).unwrap();
+        envelope.message = GossipMessage::Ping { timestamp_ms: 999 };
+
+        let result = client.receive_gossip(&peer_id, envelope).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_receive_gossip_rejects_impersonation() {
+        let client = create_test_client();
+        let (address, peer_id, _peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let impostor_id = NodeId::new();
+        let impostor_key = SigningKey::generate(&mut OsRng);
+        let envelope =
+            GossipEnvelope::seal(impostor_id, 0, GossipMessage::Ping { timestamp_ms: 1 }, &impostor_key).unwrap();
+
+        let result = client.receive_gossip(&peer_id, envelope).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_receive_gossip_rejects_replayed_sequence() {
+        let client = create_test_client();
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let envelope =
+            GossipEnvelope::seal(peer_id, 0, GossipMessage::Ping { timestamp_ms: 1 }, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope.clone()).await.unwrap();
+
+        let result = client.receive_gossip(&peer_id, envelope).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connect_offers_peer_to_sampler() {
+        let client = create_test_client();
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let sampled = client.sampled_peers().await;
+        assert!(sampled.iter().any(|d| d.node_id == peer_id));
+    }
+
+    #[tokio::test]
+    async fn test_receive_gossip_merges_peer_list_into_sampler() {
+        let client = create_test_client();
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let gossiped_id = NodeId::new();
+        let message = GossipMessage::PeerList {
+            peers: vec![crate::sampler::PeerDescriptor {
+                node_id: gossiped_id,
+                address: "ws://127.0.0.1:9".to_string(),
+            }],
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 0, message, &peer_key).unwrap();
+
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        let sampled = client.sampled_peers().await;
+        assert!(sampled.iter().any(|d| d.node_id == gossiped_id));
+    }
+
+    #[tokio::test]
+    async fn test_request_completes_on_matching_response() {
+        let client = Arc::new(create_test_client());
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let requester = Arc::clone(&client);
+        let handle = tokio::spawn(async move {
+            requester
+                .request(&peer_id, "camera", "capture", Duration::from_secs(5))
+                .await
+        });
+
+        // Give the request a moment to register itself before we reply.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let pending_intent_id = {
+            let pending = client.pending_requests.read().await;
+            pending.keys().next().cloned().unwrap()
+        };
+        let response = GossipMessage::IntentResponse {
+            intent_id: pending_intent_id,
+            success: true,
+            result: Some("photo.jpg".to_string()),
+            error: None,
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 0, response, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        let result = handle.await.unwrap().unwrap();
+        assert_eq!(result, "photo.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_request_fails_on_error_response() {
+        let client = Arc::new(create_test_client());
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let requester = Arc::clone(&client);
+        let handle = tokio::spawn(async move {
+            requester
+                .request(&peer_id, "camera", "capture", Duration::from_secs(5))
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let pending_intent_id = {
+            let pending = client.pending_requests.read().await;
+            pending.keys().next().cloned().unwrap()
+        };
+        let response = GossipMessage::IntentResponse {
+            intent_id: pending_intent_id,
+            success: false,
+            result: None,
+            error: Some("capability busy".to_string()),
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 0, response, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        let result = handle.await.unwrap();
+        assert!(matches!(result, Err(AtmosphereError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn test_request_times_out_without_response() {
+        let client = create_test_client();
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let result = client
+            .request(&peer_id, "camera", "capture", Duration::from_millis(50))
+            .await;
+
+        assert!(matches!(result, Err(AtmosphereError::Timeout(_))));
+        assert!(client.pending_requests.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_closest_peers_includes_connected_peer() {
+        let client = create_test_client();
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let closest = client.closest_peers(&peer_id, 5).await;
+        assert!(closest.iter().any(|p| p.node_id == peer_id));
+    }
+
+    #[tokio::test]
+    async fn test_request_forwards_through_routing_table_when_not_connected() {
+        let client = Arc::new(create_test_client());
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        // Hear about a peer we hold no direct connection to via gossip, so
+        // it lands in the routing table but not in `peers`.
+        let remote_id = NodeId::new();
+        let gossip = GossipMessage::PeerList {
+            peers: vec![PeerDescriptor {
+                node_id: remote_id,
+                address: "ws://127.0.0.1:9".to_string(),
+            }],
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 0, gossip, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        let requester = Arc::clone(&client);
+        let handle = tokio::spawn(async move {
+            requester
+                .request(&remote_id, "camera", "capture", Duration::from_secs(5))
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let pending_intent_id = {
+            let pending = client.pending_requests.read().await;
+            pending.keys().next().cloned().unwrap()
+        };
+        let response = GossipMessage::IntentResponse {
+            intent_id: pending_intent_id,
+            success: true,
+            result: Some("ok".to_string()),
+            error: None,
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 1, response, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        let result = handle.await.unwrap().unwrap();
+        assert_eq!(result, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_request_errors_with_no_route_when_unreachable() {
+        let client = create_test_client();
+        let unreachable = NodeId::new();
+
+        let result = client
+            .request(&unreachable, "camera", "capture", Duration::from_secs(1))
+            .await;
+
+        assert!(matches!(result, Err(AtmosphereError::NoRoute(_))));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_fails_pending_requests() {
+        let client = Arc::new(create_test_client());
+        let peer_id = client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let requester = Arc::clone(&client);
+        let handle = tokio::spawn(async move {
+            requester
+                .request(&peer_id, "camera", "capture", Duration::from_secs(5))
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        client.disconnect(&peer_id).await.unwrap();
+
+        let result = handle.await.unwrap();
+        assert!(matches!(result, Err(AtmosphereError::ConnectionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_propose_claim_with_no_peers_is_its_own_majority() {
+        let client = create_test_client();
+        let claim = Claim {
+            intent_id: Uuid::new_v4(),
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        assert!(client.propose_claim(&claim, Duration::from_secs(1)).await);
+    }
+
+    #[tokio::test]
+    async fn test_propose_claim_reaches_quorum_via_ack() {
+        let client = Arc::new(create_test_client());
+        let (address, peer_id, peer_key) = spawn_mock_peer_full().await;
+        client.connect(&address).await.unwrap();
+
+        let claim = Claim {
+            intent_id: Uuid::new_v4(),
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        let proposer = Arc::clone(&client);
+        let claim_for_task = claim.clone();
+        let handle = tokio::spawn(async move {
+            proposer
+                .propose_claim(&claim_for_task, Duration::from_secs(5))
+                .await
+        });
+
+        // Give the proposal a moment to register itself before we ack it.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let ack = GossipMessage::ClaimAck {
+            intent_id: claim.intent_id,
+        };
+        let envelope = GossipEnvelope::seal(peer_id, 0, ack, &peer_key).unwrap();
+        client.receive_gossip(&peer_id, envelope).await.unwrap();
+
+        assert!(handle.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_propose_claim_times_out_without_quorum() {
+        let client = create_test_client();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let claim = Claim {
+            intent_id: Uuid::new_v4(),
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        let reached = client
+            .propose_claim(&claim, Duration::from_millis(100))
+            .await;
+        assert!(!reached);
+    }
+
+    #[tokio::test]
+    async fn test_announce_claim_broadcasts_to_peers() {
+        let client = create_test_client();
+        client.connect(&spawn_mock_peer().await).await.unwrap();
+
+        let claim = Claim {
+            intent_id: Uuid::new_v4(),
+            target: NodeId::new(),
+            capability_id: Uuid::new_v4(),
+        };
+
+        // No assertion beyond "doesn't hang or error" - `broadcast`'s own
+        // tests already cover delivery, and the mock peer doesn't ack
+        // announcements back.
+        client.announce_claim(&claim).await;
+    }
 }
@@ -5,6 +5,7 @@
 //! provide concrete implementations.
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 /// Platform metrics provider trait
 ///
@@ -46,10 +47,56 @@ pub trait PlatformMetrics: Send + Sync {
     fn estimated_bandwidth_mbps(&self) -> u32 {
         0
     }
+
+    /// Battery temperature in tenths of a degree Celsius (e.g. 320 = 32.0C).
+    /// Mirrors Android `BatteryManager.EXTRA_TEMPERATURE`. `None` if no
+    /// battery sensor is available.
+    fn battery_temperature_tenths_celsius(&self) -> Option<i32> {
+        None
+    }
+
+    /// Battery voltage in millivolts, if known.
+    fn battery_voltage_mv(&self) -> Option<u32> {
+        None
+    }
+
+    /// Current charge status (charging/discharging/full/not-charging).
+    fn battery_charge_status(&self) -> ChargeStatus {
+        ChargeStatus::Unknown
+    }
+
+    /// Battery health as reported by the platform's fuel gauge.
+    fn battery_health(&self) -> BatteryHealth {
+        BatteryHealth::Unknown
+    }
+
+    /// Source currently supplying charge current, if any.
+    fn charging_source(&self) -> ChargingSource {
+        ChargingSource::None
+    }
+
+    /// Cumulative bytes transmitted since boot across all interfaces,
+    /// mirroring the kind of counters Android's `NetworkStatsFactory`
+    /// exposes per UID/interface.
+    fn total_tx_bytes(&self) -> u64 {
+        0
+    }
+
+    /// Cumulative bytes received since boot across all interfaces.
+    fn total_rx_bytes(&self) -> u64 {
+        0
+    }
+
+    /// Per-transport breakdown of cumulative tx/rx bytes, keyed by
+    /// `NetworkType`. Optional - an empty map means only the aggregate
+    /// `total_tx_bytes`/`total_rx_bytes` figures are available.
+    fn per_interface_bytes(&self) -> HashMap<NetworkType, InterfaceByteCounts> {
+        HashMap::new()
+    }
 }
 
 /// Network connection type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum NetworkType {
     Wifi,
     Cellular,
@@ -58,6 +105,56 @@ pub enum NetworkType {
     Unknown,
 }
 
+impl NetworkType {
+    /// Whether this connection type is typically billed by data volume, so
+    /// routing should avoid pushing heavy traffic over it when an unmetered
+    /// alternative exists.
+    pub fn is_metered(&self) -> bool {
+        matches!(self, NetworkType::Cellular)
+    }
+}
+
+/// Cumulative byte counters for a single network interface/transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InterfaceByteCounts {
+    pub tx_bytes: u64,
+    pub rx_bytes: u64,
+}
+
+/// Battery charge status, mirroring Android `BatteryManager.EXTRA_STATUS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChargeStatus {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    #[default]
+    Unknown,
+}
+
+/// Battery health, mirroring Android `BatteryManager.EXTRA_HEALTH`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatteryHealth {
+    Good,
+    Overheat,
+    Dead,
+    OverVoltage,
+    Cold,
+    #[default]
+    Unknown,
+}
+
+/// Source currently supplying charge current, mirroring Android's
+/// `EXTRA_PLUGGED` AC/USB/wireless flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChargingSource {
+    Ac,
+    Usb,
+    Wireless,
+    #[default]
+    None,
+}
+
 /// Mock metrics implementation for testing
 #[derive(Debug, Clone)]
 pub struct MockMetrics {
@@ -66,6 +163,15 @@ pub struct MockMetrics {
     pub cpu: f32,
     pub memory_mb: u64,
     pub total_memory_mb: u64,
+    pub battery_temperature_tenths_celsius: Option<i32>,
+    pub battery_voltage_mv: Option<u32>,
+    pub battery_charge_status: ChargeStatus,
+    pub battery_health: BatteryHealth,
+    pub charging_source: ChargingSource,
+    pub total_tx_bytes: u64,
+    pub total_rx_bytes: u64,
+    pub per_interface_bytes: HashMap<NetworkType, InterfaceByteCounts>,
+    pub network_type: NetworkType,
 }
 
 impl Default for MockMetrics {
@@ -76,6 +182,15 @@ impl Default for MockMetrics {
             cpu: 0.3,
             memory_mb: 2048,
             total_memory_mb: 4096,
+            battery_temperature_tenths_celsius: Some(300),
+            battery_voltage_mv: Some(3900),
+            battery_charge_status: ChargeStatus::Discharging,
+            battery_health: BatteryHealth::Good,
+            charging_source: ChargingSource::None,
+            total_tx_bytes: 0,
+            total_rx_bytes: 0,
+            per_interface_bytes: HashMap::new(),
+            network_type: NetworkType::Wifi,
         }
     }
 }
@@ -101,6 +216,42 @@ impl PlatformMetrics for MockMetrics {
     fn total_memory_mb(&self) -> u64 {
         self.total_memory_mb
     }
+
+    fn battery_temperature_tenths_celsius(&self) -> Option<i32> {
+        self.battery_temperature_tenths_celsius
+    }
+
+    fn battery_voltage_mv(&self) -> Option<u32> {
+        self.battery_voltage_mv
+    }
+
+    fn battery_charge_status(&self) -> ChargeStatus {
+        self.battery_charge_status
+    }
+
+    fn battery_health(&self) -> BatteryHealth {
+        self.battery_health
+    }
+
+    fn charging_source(&self) -> ChargingSource {
+        self.charging_source
+    }
+
+    fn total_tx_bytes(&self) -> u64 {
+        self.total_tx_bytes
+    }
+
+    fn total_rx_bytes(&self) -> u64 {
+        self.total_rx_bytes
+    }
+
+    fn per_interface_bytes(&self) -> HashMap<NetworkType, InterfaceByteCounts> {
+        self.per_interface_bytes.clone()
+    }
+
+    fn network_type(&self) -> NetworkType {
+        self.network_type
+    }
 }
 
 #[cfg(test)]
@@ -124,6 +275,7 @@ mod tests {
             cpu: 0.8,
             memory_mb: 1024,
             total_memory_mb: 8192,
+            ..Default::default()
         };
         assert_eq!(metrics.battery_percent(), None);
         assert!(!metrics.is_on_battery());
@@ -132,6 +284,48 @@ mod tests {
         assert_eq!(metrics.total_memory_mb(), 8192);
     }
 
+    #[test]
+    fn test_battery_telemetry_defaults() {
+        let metrics = MockMetrics::default();
+        assert_eq!(metrics.battery_temperature_tenths_celsius(), Some(300));
+        assert_eq!(metrics.battery_health(), BatteryHealth::Good);
+        assert_eq!(metrics.charging_source(), ChargingSource::None);
+    }
+
+    #[test]
+    fn test_network_type_is_metered() {
+        assert!(NetworkType::Cellular.is_metered());
+        assert!(!NetworkType::Wifi.is_metered());
+        assert!(!NetworkType::Ethernet.is_metered());
+        assert!(!NetworkType::Unknown.is_metered());
+    }
+
+    #[test]
+    fn test_mock_metrics_byte_counters() {
+        let mut per_interface = HashMap::new();
+        per_interface.insert(
+            NetworkType::Cellular,
+            InterfaceByteCounts {
+                tx_bytes: 1024,
+                rx_bytes: 2048,
+            },
+        );
+
+        let metrics = MockMetrics {
+            total_tx_bytes: 5000,
+            total_rx_bytes: 9000,
+            per_interface_bytes: per_interface,
+            ..Default::default()
+        };
+
+        assert_eq!(metrics.total_tx_bytes(), 5000);
+        assert_eq!(metrics.total_rx_bytes(), 9000);
+        assert_eq!(
+            metrics.per_interface_bytes().get(&NetworkType::Cellular).unwrap().tx_bytes,
+            1024
+        );
+    }
+
     #[test]
     fn test_network_type_default() {
         let net_type = NetworkType::default();
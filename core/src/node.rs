@@ -3,30 +3,56 @@
 //! The main entry point for the Atmosphere mesh network.
 //! AtmosphereNode manages the node's identity, connections, and services.
 
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use uuid::Uuid;
-use ed25519_dalek::{SigningKey, VerifyingKey};
-use serde::{Deserialize, Serialize};
-use rand::rngs::OsRng;
 
 use crate::capability::CapabilityRegistry;
+use crate::clock::{Clock, SystemClock};
 use crate::cost::CostCollector;
+use crate::cost_store::FileCostStore;
+use crate::discovery::MdnsDiscovery;
 use crate::error::{AtmosphereError, Result};
 use crate::intent::IntentRouter;
 use crate::mesh::MeshClient;
 use crate::metrics::PlatformMetrics;
+use crate::swap::SwapConfig;
+
+/// Namespace `from_public_key` hashes a node's raw public key bytes under,
+/// in the same spirit as the example namespaces in RFC 4122 - arbitrary but
+/// fixed, so the derivation can't be confused with a v5 UUID minted for some
+/// other purpose.
+const NODE_ID_KEY_NAMESPACE: Uuid = Uuid::from_u128(0x6d65_7368_6964_656e_7469_7479_7631_2e30);
 
 /// Unique identifier for a node in the mesh network
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NodeId(pub Uuid);
 
 impl NodeId {
-    /// Generate a new random node ID
+    /// Generate a new random node ID, unrelated to any key. Only fit for
+    /// identities that never need to survive a `mesh::run_handshake` check -
+    /// simulated nodes, gossiped test fixtures - since a handshake peer
+    /// verifies a claimed `node_id` against `from_public_key`, not this.
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
 
+    /// Derive a node's real mesh identity deterministically from its
+    /// Ed25519 public key, so `node_id` is bound to the key that key
+    /// actually signs with rather than an independent, self-reported value.
+    /// `mesh::run_handshake` verifies a peer's claimed `node_id` against
+    /// this derivation (over the public key it just proved ownership of)
+    /// before trusting the peer's identity - otherwise a fresh keypair
+    /// could claim any existing `node_id` and pass signature verification.
+    pub fn from_public_key(public_key: &VerifyingKey) -> Self {
+        Self(Uuid::new_v5(&NODE_ID_KEY_NAMESPACE, public_key.as_bytes()))
+    }
+
     /// Create from an existing UUID
     pub fn from_uuid(uuid: Uuid) -> Self {
         Self(uuid)
@@ -70,6 +96,31 @@ pub struct NodeConfig {
 
     /// Enable cost-based routing
     pub cost_aware_routing: bool,
+
+    /// Configuration for the model weight swap subsystem, used on
+    /// memory-constrained devices. `None` means weights are always fully
+    /// resident.
+    pub swap: Option<SwapConfig>,
+
+    /// Advertise and discover peers on the local network over mDNS/DNS-SD.
+    /// Opt-out rather than opt-in, since zero-config LAN peering is what
+    /// users expect from a local mesh app; disable it for privacy-sensitive
+    /// or headless/server deployments that shouldn't announce themselves.
+    pub enable_mdns: bool,
+
+    /// Where to persist `cost_collector`'s learned weights and peer-cost
+    /// cache across restarts. `None` (the default) keeps cost state
+    /// in-memory only, so a killed/restarted node cold-starts cost-aware
+    /// routing instead of resuming it.
+    pub cost_store_path: Option<PathBuf>,
+
+    /// How often a changed cost snapshot is flushed to `cost_store_path`.
+    pub cost_store_flush_interval_secs: u64,
+
+    /// On load, a persisted peer-cost entry older than this is discarded
+    /// rather than used for routing, since a long-dead peer's last-known
+    /// cost is more likely stale than useful.
+    pub cost_store_max_age_secs: u64,
 }
 
 impl Default for NodeConfig {
@@ -81,6 +132,11 @@ impl Default for NodeConfig {
             max_peers: 50,
             gossip_interval_secs: 30,
             cost_aware_routing: true,
+            swap: None,
+            enable_mdns: true,
+            cost_store_path: None,
+            cost_store_flush_interval_secs: 5,
+            cost_store_max_age_secs: 300,
         }
     }
 }
@@ -120,16 +176,48 @@ pub struct AtmosphereNode {
     intent_router: Arc<IntentRouter>,
 
     /// Mesh client (optional, created on start)
-    mesh_client: Arc<RwLock<Option<MeshClient>>>,
+    mesh_client: Arc<RwLock<Option<Arc<MeshClient>>>>,
+
+    /// mDNS LAN discovery (optional, created on start when
+    /// `config.enable_mdns` is set)
+    mdns: Arc<RwLock<Option<MdnsDiscovery>>>,
+
+    /// Intent deadline watchdog, running while the node is `Running`
+    watchdog: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+
+    /// Per-gossip-round credit replenishment for `cost_collector`, running
+    /// while the node is `Running`.
+    credit_replenisher: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 impl AtmosphereNode {
-    /// Create a new Atmosphere node with the given configuration
+    /// Create a new Atmosphere node with the given configuration, timing
+    /// its cost calculations against real wall-clock time.
     pub fn new(config: NodeConfig, metrics: Arc<dyn PlatformMetrics>) -> Self {
-        let id = NodeId::new();
+        Self::new_with_clock(config, metrics, Arc::new(SystemClock))
+    }
+
+    /// `new`, but timing `cost_collector`'s calculations against `clock`
+    /// instead of always using `SystemClock` - e.g. a `SimClock` so
+    /// `MeshSimulation` can drive this node through deterministic,
+    /// wall-clock-independent gossip rounds.
+    pub fn new_with_clock(
+        config: NodeConfig,
+        metrics: Arc<dyn PlatformMetrics>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         let signing_key = SigningKey::generate(&mut OsRng);
+        let id = NodeId::from_public_key(&signing_key.verifying_key());
         let capabilities = Arc::new(CapabilityRegistry::new());
-        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let mut cost_collector = CostCollector::new(metrics).with_clock(clock);
+        if let Some(path) = &config.cost_store_path {
+            let store = Arc::new(FileCostStore::open(
+                path,
+                Duration::from_secs(config.cost_store_flush_interval_secs.max(1)),
+            ));
+            cost_collector = cost_collector.with_store(store);
+        }
+        let cost_collector = Arc::new(cost_collector);
         let intent_router = Arc::new(IntentRouter::new(
             Arc::clone(&capabilities),
             Arc::clone(&cost_collector),
@@ -144,6 +232,9 @@ impl AtmosphereNode {
             cost_collector,
             intent_router,
             mesh_client: Arc::new(RwLock::new(None)),
+            mdns: Arc::new(RwLock::new(None)),
+            watchdog: Arc::new(RwLock::new(None)),
+            credit_replenisher: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -157,6 +248,18 @@ impl AtmosphereNode {
         self.signing_key.verifying_key()
     }
 
+    /// A short hex fingerprint of the node's public key, suitable for
+    /// display or advertisement (e.g. in an mDNS TXT record) without
+    /// exposing the full key.
+    pub fn public_key_fingerprint(&self) -> String {
+        self.public_key()
+            .as_bytes()
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
     /// Get the node's configuration
     pub fn config(&self) -> &NodeConfig {
         &self.config
@@ -185,7 +288,7 @@ impl AtmosphereNode {
     /// Start the node and begin participating in the mesh
     pub async fn start(&self) -> Result<()> {
         let mut state = self.state.write().await;
-        
+
         if *state != NodeState::Stopped {
             return Err(AtmosphereError::InvalidConfig(
                 "Node is already running or starting".to_string(),
@@ -195,28 +298,69 @@ impl AtmosphereNode {
         *state = NodeState::Starting;
         tracing::info!(node_id = %self.id, "Starting Atmosphere node");
 
+        // Warm the peer-cost cache and restore custom weights from a
+        // previous run, if persistence is configured, so cost-aware
+        // routing resumes immediately instead of cold-starting.
+        self.cost_collector
+            .load_from_store(Duration::from_secs(
+                self.config.cost_store_max_age_secs.max(1),
+            ))
+            .await;
+
         // Create mesh client
-        let mesh_client = MeshClient::new(
+        let mesh_client = Arc::new(MeshClient::new(
             self.id,
             self.signing_key.clone(),
             self.config.clone(),
-        );
+        ));
 
         // Store mesh client
-        *self.mesh_client.write().await = Some(mesh_client);
+        *self.mesh_client.write().await = Some(Arc::clone(&mesh_client));
 
         // Connect to bootstrap peers
-        if let Some(ref client) = *self.mesh_client.read().await {
-            for peer_addr in &self.config.bootstrap_peers {
-                if let Err(e) = client.connect(peer_addr).await {
-                    tracing::warn!(peer = %peer_addr, error = %e, "Failed to connect to bootstrap peer");
-                }
+        for peer_addr in &self.config.bootstrap_peers {
+            if let Err(e) = mesh_client.connect(peer_addr).await {
+                tracing::warn!(peer = %peer_addr, error = %e, "Failed to connect to bootstrap peer");
             }
         }
 
+        // Start LAN auto-discovery, unless disabled for privacy/server deployments
+        if self.config.enable_mdns {
+            let mut discovery = MdnsDiscovery::new();
+            if let Err(e) = discovery
+                .start(
+                    Arc::clone(&mesh_client),
+                    self.id,
+                    self.config.name.clone(),
+                    self.config.listen_port,
+                    self.public_key_fingerprint(),
+                )
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to start mDNS discovery");
+            } else {
+                *self.mdns.write().await = Some(discovery);
+            }
+        }
+
+        *self.watchdog.write().await = Some(Arc::clone(&self.intent_router).start());
+
+        // Refill every peer's admission-control credit balance once per
+        // gossip round, so a cheapest peer that's been hammered to
+        // saturation regains headroom at the same cadence peers learn
+        // about each other's cost.
+        let gossip_interval = Duration::from_secs(self.config.gossip_interval_secs.max(1));
+        let cost_collector = Arc::clone(&self.cost_collector);
+        *self.credit_replenisher.write().await = Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(gossip_interval).await;
+                cost_collector.replenish_all();
+            }
+        }));
+
         *state = NodeState::Running;
         tracing::info!(node_id = %self.id, "Atmosphere node started");
-        
+
         Ok(())
     }
 
@@ -231,6 +375,21 @@ impl AtmosphereNode {
         *state = NodeState::Stopping;
         tracing::info!(node_id = %self.id, "Stopping Atmosphere node");
 
+        // Stop the intent deadline watchdog
+        if let Some(handle) = self.watchdog.write().await.take() {
+            handle.abort();
+        }
+
+        // Stop credit replenishment
+        if let Some(handle) = self.credit_replenisher.write().await.take() {
+            handle.abort();
+        }
+
+        // Stop mDNS discovery
+        if let Some(mut discovery) = self.mdns.write().await.take() {
+            discovery.stop().await;
+        }
+
         // Disconnect mesh client
         if let Some(ref client) = *self.mesh_client.read().await {
             client.disconnect_all().await;
@@ -238,7 +397,7 @@ impl AtmosphereNode {
 
         *self.mesh_client.write().await = None;
         *state = NodeState::Stopped;
-        
+
         tracing::info!(node_id = %self.id, "Atmosphere node stopped");
         Ok(())
     }
@@ -262,6 +421,21 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_node_id_from_public_key_is_deterministic_and_key_bound() {
+        let key = SigningKey::generate(&mut OsRng);
+        let other = SigningKey::generate(&mut OsRng);
+
+        assert_eq!(
+            NodeId::from_public_key(&key.verifying_key()),
+            NodeId::from_public_key(&key.verifying_key())
+        );
+        assert_ne!(
+            NodeId::from_public_key(&key.verifying_key()),
+            NodeId::from_public_key(&other.verifying_key())
+        );
+    }
+
     #[test]
     fn test_node_id_display() {
         let id = NodeId::from_uuid(Uuid::nil());
@@ -274,6 +448,18 @@ mod tests {
         assert_eq!(config.listen_port, 8765);
         assert_eq!(config.max_peers, 50);
         assert!(config.cost_aware_routing);
+        assert!(config.enable_mdns);
+    }
+
+    #[test]
+    fn test_public_key_fingerprint_is_stable_short_hex() {
+        let config = NodeConfig::default();
+        let metrics = Arc::new(MockMetrics::default());
+        let node = AtmosphereNode::new(config, metrics);
+
+        let fingerprint = node.public_key_fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert_eq!(fingerprint, node.public_key_fingerprint());
     }
 
     #[tokio::test]
@@ -281,7 +467,7 @@ mod tests {
         let config = NodeConfig::default();
         let metrics = Arc::new(MockMetrics::default());
         let node = AtmosphereNode::new(config, metrics);
-        
+
         assert_eq!(node.state().await, NodeState::Stopped);
         assert!(!node.id().0.is_nil());
     }
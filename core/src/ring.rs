@@ -0,0 +1,225 @@
+//! Consistent-Hash Ring
+//!
+//! `CapabilityRegistry::find_peers_with_capability` returns matching peers
+//! in whatever order the backing `HashMap` happens to iterate them in, so
+//! two calls for the same capability can hand back a different "first"
+//! peer and there's no notion of ordered fallbacks for retry or
+//! replication. This places every known peer at `VIRTUAL_NODES_PER_PEER`
+//! points around a 64-bit ring, each point hashed from `(node_id,
+//! vnode_index)`. Routing a key walks clockwise from the key's own hash
+//! and returns the first eligible peers it meets, in order - stable across
+//! calls, and since only the virtual points near a joining/leaving peer
+//! move, membership changes reshuffle a small fraction of keys rather than
+//! rebalancing everything.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+use crate::node::NodeId;
+
+/// Virtual points placed per physical peer. Higher spreads load more
+/// evenly across peers at the cost of a larger ring to walk.
+pub const VIRTUAL_NODES_PER_PEER: usize = 64;
+
+/// Maps peers onto a 64-bit ring via virtual nodes and routes keys to them
+/// by walking clockwise from the key's hash.
+#[derive(Debug, Default)]
+pub struct HashRing {
+    /// Ring position -> the peer whose virtual node landed there. A
+    /// `BTreeMap` gives an O(log n) "first point at or after X" lookup,
+    /// which is exactly the clockwise walk this needs.
+    points: RwLock<BTreeMap<u64, NodeId>>,
+}
+
+impl HashRing {
+    /// Create an empty ring.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn vnode_hash(node_id: &NodeId, vnode_index: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        node_id.hash(&mut hasher);
+        vnode_index.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn key_hash(key: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Place `node_id` at its `VIRTUAL_NODES_PER_PEER` ring positions.
+    /// Idempotent - re-adding an already-placed peer just overwrites the
+    /// same points with the same value.
+    pub fn add_node(&self, node_id: NodeId) {
+        let mut points = self.points.write().unwrap();
+        for i in 0..VIRTUAL_NODES_PER_PEER {
+            points.insert(Self::vnode_hash(&node_id, i), node_id);
+        }
+    }
+
+    /// Remove every virtual point belonging to `node_id`.
+    pub fn remove_node(&self, node_id: &NodeId) {
+        let mut points = self.points.write().unwrap();
+        points.retain(|_, id| id != node_id);
+    }
+
+    /// Number of distinct peers currently placed on the ring.
+    pub fn node_count(&self) -> usize {
+        self.points
+            .read()
+            .unwrap()
+            .values()
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Walk clockwise from `key`'s ring position, returning up to `limit`
+    /// distinct peers for which `is_eligible` holds, closest first. Wraps
+    /// around the ring exactly once, so this always terminates even if
+    /// `limit` can't be satisfied.
+    pub fn walk(
+        &self,
+        key: &str,
+        limit: usize,
+        is_eligible: impl Fn(&NodeId) -> bool,
+    ) -> Vec<NodeId> {
+        let points = self.points.read().unwrap();
+        if limit == 0 || points.is_empty() {
+            return Vec::new();
+        }
+
+        let start = Self::key_hash(key);
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+
+        for (_, node_id) in points.range(start..).chain(points.range(..start)) {
+            if !seen.insert(*node_id) {
+                continue;
+            }
+            if is_eligible(node_id) {
+                result.push(*node_id);
+                if result.len() == limit {
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring_of(count: usize) -> (HashRing, Vec<NodeId>) {
+        let ring = HashRing::new();
+        let nodes: Vec<NodeId> = (0..count).map(|_| NodeId::new()).collect();
+        for node_id in &nodes {
+            ring.add_node(*node_id);
+        }
+        (ring, nodes)
+    }
+
+    #[test]
+    fn test_add_remove_node_updates_count() {
+        let (ring, nodes) = ring_of(3);
+        assert_eq!(ring.node_count(), 3);
+
+        ring.remove_node(&nodes[0]);
+        assert_eq!(ring.node_count(), 2);
+    }
+
+    #[test]
+    fn test_walk_is_deterministic_for_same_key() {
+        let (ring, _nodes) = ring_of(10);
+
+        let first = ring.walk("intent-42", 3, |_| true);
+        let second = ring.walk("intent-42", 3, |_| true);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 3);
+    }
+
+    #[test]
+    fn test_walk_skips_ineligible_nodes() {
+        let (ring, nodes) = ring_of(5);
+        let eligible = nodes[0];
+
+        let result = ring.walk("some-key", 1, |id| *id == eligible);
+
+        assert_eq!(result, vec![eligible]);
+    }
+
+    #[test]
+    fn test_walk_returns_empty_on_empty_ring() {
+        let ring = HashRing::new();
+        assert!(ring.walk("anything", 3, |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_walk_distributes_keys_reasonably_evenly() {
+        let (ring, nodes) = ring_of(20);
+
+        let mut hits: std::collections::HashMap<NodeId, usize> = std::collections::HashMap::new();
+        for i in 0..5000 {
+            let key = format!("key-{}", i);
+            if let Some(node_id) = ring.walk(&key, 1, |_| true).first() {
+                *hits.entry(*node_id).or_insert(0) += 1;
+            }
+        }
+
+        let average = 5000 / nodes.len();
+        for node_id in &nodes {
+            let count = *hits.get(node_id).unwrap_or(&0);
+            assert!(
+                count < average * 4,
+                "node {} received {} of 5000 keys, far above the ~{} average for 20 peers",
+                node_id,
+                count,
+                average
+            );
+        }
+    }
+
+    #[test]
+    fn test_membership_change_only_moves_a_fraction_of_keys() {
+        let (ring, nodes) = ring_of(10);
+        let keys: Vec<String> = (0..1000).map(|i| format!("key-{}", i)).collect();
+
+        let before: Vec<Option<NodeId>> = keys
+            .iter()
+            .map(|k| ring.walk(k, 1, |_| true).first().copied())
+            .collect();
+
+        let joining = NodeId::new();
+        ring.add_node(joining);
+
+        let after: Vec<Option<NodeId>> = keys
+            .iter()
+            .map(|k| ring.walk(k, 1, |_| true).first().copied())
+            .collect();
+
+        let moved = before
+            .iter()
+            .zip(after.iter())
+            .filter(|(b, a)| b != a)
+            .count();
+
+        // With 10 existing peers and one joining, only keys whose primary
+        // lands on one of the new peer's virtual nodes should move -
+        // expected around 1/11 of keys, nowhere near all of them.
+        assert!(
+            moved < keys.len() / 2,
+            "{} of {} keys moved after a single peer joined a 10-peer ring",
+            moved,
+            keys.len()
+        );
+        assert_eq!(nodes.len(), 10);
+    }
+}
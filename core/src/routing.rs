@@ -0,0 +1,293 @@
+//! Kademlia-style Routing Table
+//!
+//! `find_peers_with_capability` and `MeshClient`'s peer map are flat
+//! `HashMap`s, fine for a handful of directly connected peers but with no
+//! notion of "closest" nodes to route an intent through once the mesh grows
+//! past who we happen to hold a socket open to. This organizes every known
+//! peer - connected or merely heard about via gossip - into k-buckets keyed
+//! by XOR distance between 128-bit `NodeId` UUIDs, the same structure
+//! Kademlia uses for bounded per-node state with no single point of
+//! failure: bucket `i` holds peers whose distance to us has `i` leading
+//! zero bits, so bucket 0 is the "far half" of the ID space and bucket 127
+//! holds only the single ID that differs from ours in the lowest bit; our
+//! own ID has no bucket. Each bucket orders peers by last-seen, oldest first,
+//! and only evicts the least-recently-seen entry in favor of a new
+//! candidate once that entry is confirmed unreachable - a live peer is
+//! never displaced just because a bucket filled up.
+
+use std::collections::VecDeque;
+
+use tokio::sync::RwLock;
+
+use crate::node::NodeId;
+use crate::sampler::PeerDescriptor;
+
+/// Kademlia's conventional bucket size (`k`): how many peers a bucket holds
+/// before a new candidate has to wait for the least-recently-seen entry to
+/// prove itself unresponsive.
+pub const BUCKET_SIZE: usize = 20;
+
+/// `NodeId` wraps a 128-bit UUID, so there are 128 possible leading-zero
+/// counts for the XOR distance between two of them.
+const NUM_BUCKETS: usize = 128;
+
+/// Outcome of offering a candidate to the table, telling the caller whether
+/// anything further (like pinging a stale entry) needs to happen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Observation {
+    /// The candidate was already in its bucket; it's now marked most
+    /// recently seen.
+    Refreshed,
+    /// The candidate's bucket had room and it was inserted.
+    Inserted,
+    /// The candidate's bucket is full of other peers. `stale` is the
+    /// least-recently-seen entry in that bucket - ping it, and only call
+    /// `replace_stale` with the candidate if it doesn't answer.
+    BucketFull { stale: PeerDescriptor },
+}
+
+struct Bucket {
+    /// Ordered oldest (front) to most-recently-seen (back).
+    peers: VecDeque<PeerDescriptor>,
+}
+
+impl Bucket {
+    fn new() -> Self {
+        Self {
+            peers: VecDeque::new(),
+        }
+    }
+
+    fn position(&self, node_id: &NodeId) -> Option<usize> {
+        self.peers.iter().position(|p| p.node_id == *node_id)
+    }
+}
+
+/// Maintains the bucketed routing state described above for one local node.
+pub struct RoutingTable {
+    local_id: NodeId,
+    buckets: RwLock<Vec<Bucket>>,
+}
+
+impl RoutingTable {
+    /// Create an empty table for `local_id`.
+    pub fn new(local_id: NodeId) -> Self {
+        let buckets = (0..NUM_BUCKETS).map(|_| Bucket::new()).collect();
+        Self {
+            local_id,
+            buckets: RwLock::new(buckets),
+        }
+    }
+
+    /// Which bucket `peer_id` belongs in: the number of leading zero bits
+    /// of its XOR distance from `local_id`. `None` for `local_id` itself,
+    /// which has no meaningful bucket.
+    fn bucket_index(&self, peer_id: &NodeId) -> Option<usize> {
+        let distance = self.local_id.as_uuid().as_u128() ^ peer_id.as_uuid().as_u128();
+        if distance == 0 {
+            return None;
+        }
+        Some(distance.leading_zeros() as usize)
+    }
+
+    /// Offer a candidate peer to the table, inserting it, refreshing it if
+    /// already present, or reporting the bucket's stale entry if full. A
+    /// no-op for `local_id` itself.
+    pub async fn observe(&self, candidate: PeerDescriptor) -> Option<Observation> {
+        let index = self.bucket_index(&candidate.node_id)?;
+        let mut buckets = self.buckets.write().await;
+        let bucket = &mut buckets[index];
+
+        if let Some(pos) = bucket.position(&candidate.node_id) {
+            bucket.peers.remove(pos);
+            bucket.peers.push_back(candidate);
+            return Some(Observation::Refreshed);
+        }
+
+        if bucket.peers.len() < BUCKET_SIZE {
+            bucket.peers.push_back(candidate);
+            return Some(Observation::Inserted);
+        }
+
+        Some(Observation::BucketFull {
+            stale: bucket
+                .peers
+                .front()
+                .cloned()
+                .expect("bucket at capacity is non-empty"),
+        })
+    }
+
+    /// Evict `stale` in favor of `candidate`, after confirming `stale`
+    /// didn't respond to a ping. A no-op if `stale` has since been
+    /// refreshed out of the front position (it answered after all).
+    pub async fn replace_stale(&self, stale: &NodeId, candidate: PeerDescriptor) {
+        let Some(index) = self.bucket_index(stale) else {
+            return;
+        };
+        let mut buckets = self.buckets.write().await;
+        let bucket = &mut buckets[index];
+        if bucket.peers.front().map(|p| p.node_id) == Some(*stale) {
+            bucket.peers.pop_front();
+            bucket.peers.push_back(candidate);
+        }
+    }
+
+    /// Remove a peer from the table outright, e.g. on an explicit
+    /// disconnect rather than a ping timeout.
+    pub async fn remove(&self, node_id: &NodeId) {
+        let Some(index) = self.bucket_index(node_id) else {
+            return;
+        };
+        let mut buckets = self.buckets.write().await;
+        if let Some(pos) = buckets[index].position(node_id) {
+            buckets[index].peers.remove(pos);
+        }
+    }
+
+    /// The `count` known peers with the smallest XOR distance to `target`,
+    /// closest first.
+    pub async fn closest_peers(&self, target: &NodeId, count: usize) -> Vec<PeerDescriptor> {
+        let target_bits = target.as_uuid().as_u128();
+        let buckets = self.buckets.read().await;
+
+        let mut candidates: Vec<(u128, PeerDescriptor)> = buckets
+            .iter()
+            .flat_map(|bucket| bucket.peers.iter())
+            .map(|peer| (target_bits ^ peer.node_id.as_uuid().as_u128(), peer.clone()))
+            .collect();
+
+        candidates.sort_by_key(|(distance, _)| *distance);
+        candidates.truncate(count);
+        candidates.into_iter().map(|(_, peer)| peer).collect()
+    }
+
+    /// Total number of peers held across all buckets.
+    pub async fn len(&self) -> usize {
+        self.buckets
+            .read()
+            .await
+            .iter()
+            .map(|b| b.peers.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(node_id: NodeId) -> PeerDescriptor {
+        PeerDescriptor {
+            node_id,
+            address: format!("ws://{}", node_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observe_inserts_then_refreshes() {
+        let table = RoutingTable::new(NodeId::new());
+        let peer = descriptor(NodeId::new());
+
+        assert_eq!(
+            table.observe(peer.clone()).await,
+            Some(Observation::Inserted)
+        );
+        assert_eq!(table.observe(peer).await, Some(Observation::Refreshed));
+        assert_eq!(table.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_observe_self_is_noop() {
+        let local_id = NodeId::new();
+        let table = RoutingTable::new(local_id);
+
+        assert_eq!(table.observe(descriptor(local_id)).await, None);
+        assert_eq!(table.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_full_reports_stale_without_evicting() {
+        let table = RoutingTable::new(NodeId::from_uuid(uuid::Uuid::from_u128(0)));
+
+        // Against an all-zero local id, every id with the top bit set lands
+        // in bucket 0, so BUCKET_SIZE + 1 of them overflow that one bucket.
+        let peers: Vec<PeerDescriptor> = (0..=BUCKET_SIZE as u128)
+            .map(|i| descriptor(NodeId::from_uuid(uuid::Uuid::from_u128((1u128 << 127) | i))))
+            .collect();
+        for peer in &peers[..BUCKET_SIZE] {
+            assert_eq!(table.observe(peer.clone()).await, Some(Observation::Inserted));
+        }
+
+        assert_eq!(
+            table.observe(peers[BUCKET_SIZE].clone()).await,
+            Some(Observation::BucketFull { stale: peers[0].clone() })
+        );
+        assert_eq!(table.len().await, BUCKET_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_replace_stale_evicts_front_entry() {
+        let local_id = NodeId::new();
+        let table = RoutingTable::new(local_id);
+
+        let stale_peer = descriptor(NodeId::new());
+        table.observe(stale_peer.clone()).await;
+
+        let replacement = descriptor(NodeId::new());
+        table
+            .replace_stale(&stale_peer.node_id, replacement.clone())
+            .await;
+
+        let index = table.bucket_index(&stale_peer.node_id).unwrap();
+        let buckets = table.buckets.read().await;
+        assert_eq!(buckets[index].peers.len(), 1);
+        assert_eq!(buckets[index].peers[0].node_id, replacement.node_id);
+    }
+
+    #[tokio::test]
+    async fn test_remove_drops_peer() {
+        let table = RoutingTable::new(NodeId::new());
+        let peer = descriptor(NodeId::new());
+        table.observe(peer.clone()).await;
+
+        table.remove(&peer.node_id).await;
+
+        assert_eq!(table.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_closest_peers_orders_by_xor_distance() {
+        let local_id = NodeId::new();
+        let table = RoutingTable::new(local_id);
+
+        let peers: Vec<NodeId> = (0..10).map(|_| NodeId::new()).collect();
+        for peer_id in &peers {
+            table.observe(descriptor(*peer_id)).await;
+        }
+
+        let target = NodeId::new();
+        let closest = table.closest_peers(&target, 3).await;
+        assert_eq!(closest.len(), 3);
+
+        let mut expected = peers.clone();
+        expected.sort_by_key(|id| target.as_uuid().as_u128() ^ id.as_uuid().as_u128());
+        let expected_closest: Vec<NodeId> = expected.into_iter().take(3).collect();
+
+        assert_eq!(
+            closest.into_iter().map(|p| p.node_id).collect::<Vec<_>>(),
+            expected_closest
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closest_peers_caps_at_requested_count() {
+        let table = RoutingTable::new(NodeId::new());
+        for _ in 0..5 {
+            table.observe(descriptor(NodeId::new())).await;
+        }
+
+        let closest = table.closest_peers(&NodeId::new(), 2).await;
+        assert_eq!(closest.len(), 2);
+    }
+}
@@ -0,0 +1,302 @@
+//! Transactional Intent Groups (Saga)
+//!
+//! A single `Intent` succeeds or fails in isolation, but many real flows
+//! are multi-step - "capture photo" then "upload" then "notify" - and a
+//! failure partway through currently leaves the mesh in an inconsistent
+//! state with no rollback. `IntentGroup` layers the cohort/saga
+//! commit-or-compensate model on top of `IntentRouter`: an ordered list of
+//! stages, each run to completion in sequence, with the steps inside a
+//! stage dispatched together. Every step may carry a compensating intent
+//! (e.g. "delete uploaded blob"); if any step terminally fails, every
+//! already-`Completed` step seen so far - across this stage and every
+//! earlier one - is compensated in reverse order. `SagaCoordinator` tracks
+//! groups by ID so a caller elsewhere in the mesh can poll
+//! `get_group_status` without holding onto the `IntentGroup` itself.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::intent::{Intent, IntentRouter, IntentStatus};
+
+/// How often a running `IntentGroup` polls its in-flight steps for a
+/// terminal status.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How many times in all a compensating intent is routed before the group
+/// gives up and leaves it as a logged, unresolved rollback.
+const MAX_COMPENSATION_ATTEMPTS: usize = 3;
+
+/// One forward step of a group, optionally paired with the intent that
+/// undoes it.
+#[derive(Debug, Clone)]
+pub struct GroupStep {
+    pub intent: Intent,
+    pub compensation: Option<Intent>,
+}
+
+impl GroupStep {
+    /// A step with no compensation - its effect, if any, is accepted as
+    /// permanent even if a later step in the group fails.
+    pub fn new(intent: Intent) -> Self {
+        Self {
+            intent,
+            compensation: None,
+        }
+    }
+
+    /// Pair this step with the intent that undoes it, run in reverse order
+    /// if a later step in the group terminally fails.
+    pub fn with_compensation(mut self, compensation: Intent) -> Self {
+        self.compensation = Some(compensation);
+        self
+    }
+}
+
+/// Status of an `IntentGroup`, tracked independently of its individual
+/// steps' `IntentStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupStatus {
+    /// Stages are still being dispatched and awaited.
+    Running,
+
+    /// Every step in every stage reached `Completed`.
+    Committed,
+
+    /// A step terminally failed; compensations for already-completed steps
+    /// are being run in reverse order.
+    Compensating,
+
+    /// Every applicable compensation has been run (or exhausted its
+    /// retries) after a failure. The group did not commit.
+    RolledBack,
+}
+
+/// A step that reached `Completed`, carried forward so its compensation
+/// (if any) can be run if a later step fails.
+struct CompletedStep {
+    intent_id: Uuid,
+    compensation: Option<Intent>,
+}
+
+/// A multi-step, all-or-nothing workflow run over an `IntentRouter`. Build
+/// with ordered `stages` - each stage's steps are dispatched together and
+/// the group only advances to the next stage once every step in the
+/// current one reaches `Completed`.
+pub struct IntentGroup {
+    id: Uuid,
+    router: Arc<IntentRouter>,
+    stages: Vec<Vec<GroupStep>>,
+    status: RwLock<GroupStatus>,
+}
+
+impl IntentGroup {
+    /// Create a group over `stages`, not yet running.
+    pub fn new(router: Arc<IntentRouter>, stages: Vec<Vec<GroupStep>>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            router,
+            stages,
+            status: RwLock::new(GroupStatus::Running),
+        }
+    }
+
+    /// This group's ID, stable for its lifetime.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The group's current status.
+    pub async fn status(&self) -> GroupStatus {
+        *self.status.read().await
+    }
+
+    /// Run every stage to completion, compensating already-completed steps
+    /// and returning `RolledBack` on the first terminal failure. Only
+    /// returns `Committed` once every step in every stage has reached
+    /// `Completed`.
+    pub async fn run(&self) -> GroupStatus {
+        let mut completed: Vec<CompletedStep> = Vec::new();
+
+        for stage in &self.stages {
+            match self.run_stage(stage).await {
+                Ok(mut stage_completed) => completed.append(&mut stage_completed),
+                Err(mut stage_completed) => {
+                    completed.append(&mut stage_completed);
+                    self.compensate(completed).await;
+                    return GroupStatus::RolledBack;
+                }
+            }
+        }
+
+        *self.status.write().await = GroupStatus::Committed;
+        GroupStatus::Committed
+    }
+
+    /// Dispatch every step in `stage` and poll until each reaches a
+    /// terminal status. `Ok` carries every step that completed; `Err`
+    /// carries whatever subset of this stage completed before a sibling
+    /// step failed, so the caller can fold it into the steps still owed a
+    /// compensation. A step that fails to even route is never added to
+    /// `pending` in the first place, but every step that *was* routed -
+    /// including ones dispatched before a later sibling's routing failed -
+    /// is still polled to a terminal status here rather than abandoned, so
+    /// a `Completed` among them is never dropped without compensation.
+    async fn run_stage(
+        &self,
+        stage: &[GroupStep],
+    ) -> Result<Vec<CompletedStep>, Vec<CompletedStep>> {
+        let mut pending = Vec::with_capacity(stage.len());
+        let mut failed = false;
+        for step in stage {
+            if self.router.route(step.intent.clone()).await.is_err() {
+                failed = true;
+                break;
+            }
+            pending.push((step.intent.id, step.compensation.clone()));
+        }
+
+        let mut completed = Vec::new();
+        while !pending.is_empty() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let mut still_pending = Vec::new();
+            for (intent_id, compensation) in pending {
+                match self.router.get_status(intent_id).await {
+                    Some(IntentStatus::Completed { .. }) => completed.push(CompletedStep {
+                        intent_id,
+                        compensation,
+                    }),
+                    Some(IntentStatus::Failed { .. })
+                    | Some(IntentStatus::TimedOut)
+                    | Some(IntentStatus::Cancelled)
+                    | None => failed = true,
+                    _ => still_pending.push((intent_id, compensation)),
+                }
+            }
+            pending = still_pending;
+        }
+
+        if failed {
+            Err(completed)
+        } else {
+            Ok(completed)
+        }
+    }
+
+    /// Run every completed step's compensation in reverse order, skipping
+    /// steps with none.
+    async fn compensate(&self, completed: Vec<CompletedStep>) {
+        *self.status.write().await = GroupStatus::Compensating;
+
+        for step in completed.into_iter().rev() {
+            if let Some(compensation) = step.compensation {
+                self.run_compensation(compensation).await;
+            }
+        }
+
+        *self.status.write().await = GroupStatus::RolledBack;
+    }
+
+    /// Route `compensation` and wait for it to complete, making at most
+    /// `MAX_COMPENSATION_ATTEMPTS` attempts in all while it fails or its
+    /// routing is rejected outright.
+    async fn run_compensation(&self, compensation: Intent) {
+        for attempt in 1..=MAX_COMPENSATION_ATTEMPTS {
+            if self.router.route(compensation.clone()).await.is_err() {
+                tracing::warn!(
+                    intent_id = %compensation.id,
+                    attempt,
+                    "Failed to route compensating intent, retrying"
+                );
+                continue;
+            }
+
+            match self.await_terminal(compensation.id).await {
+                IntentStatus::Completed { .. } => return,
+                status => tracing::warn!(
+                    intent_id = %compensation.id,
+                    attempt,
+                    ?status,
+                    "Compensating intent did not complete, retrying"
+                ),
+            }
+        }
+
+        tracing::error!(
+            intent_id = %compensation.id,
+            "Compensation exhausted its retries - mesh may be left inconsistent"
+        );
+    }
+
+    /// Poll `intent_id`'s status until it reaches a terminal one.
+    async fn await_terminal(&self, intent_id: Uuid) -> IntentStatus {
+        loop {
+            match self.router.get_status(intent_id).await {
+                Some(status)
+                    if matches!(
+                        status,
+                        IntentStatus::Completed { .. }
+                            | IntentStatus::Failed { .. }
+                            | IntentStatus::TimedOut
+                            | IntentStatus::Cancelled
+                    ) =>
+                {
+                    return status;
+                }
+                Some(_) => tokio::time::sleep(POLL_INTERVAL).await,
+                None => {
+                    return IntentStatus::Failed {
+                        reason: "intent vanished from router".to_string(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tracks `IntentGroup`s by ID so a caller elsewhere in the mesh can submit
+/// a saga and later poll its outcome without holding the `IntentGroup`
+/// itself.
+pub struct SagaCoordinator {
+    router: Arc<IntentRouter>,
+    groups: RwLock<HashMap<Uuid, Arc<IntentGroup>>>,
+}
+
+impl SagaCoordinator {
+    /// Create a coordinator dispatching groups through `router`.
+    pub fn new(router: Arc<IntentRouter>) -> Self {
+        Self {
+            router,
+            groups: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Build a group over `stages`, track it, and start running it in the
+    /// background. Returns the group's ID immediately.
+    pub async fn submit(&self, stages: Vec<Vec<GroupStep>>) -> Uuid {
+        let group = Arc::new(IntentGroup::new(Arc::clone(&self.router), stages));
+        let id = group.id();
+
+        self.groups.write().await.insert(id, Arc::clone(&group));
+        tokio::spawn(async move {
+            group.run().await;
+        });
+
+        id
+    }
+
+    /// The current status of a tracked group, or `None` if `group_id` was
+    /// never submitted through this coordinator.
+    pub async fn get_group_status(&self, group_id: Uuid) -> Option<GroupStatus> {
+        let groups = self.groups.read().await;
+        if let Some(group) = groups.get(&group_id) {
+            Some(group.status().await)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Peer Sampler
+//!
+//! Maintains a fixed-size, near-uniform random sample of the mesh's peer
+//! population, independent of which peers `MeshClient` happens to hold a
+//! live WebSocket connection to. Based on the Brahms algorithm: the view is
+//! split into `view_size` slots, and slot `i` is held by whichever
+//! candidate minimizes a keyed hash `hash(seed_i, node_id)` for that slot's
+//! own random seed. Offering a batch of candidates (from a local connect,
+//! or a push/pull gossip exchange with another peer) only ever replaces a
+//! slot's holder with a strictly smaller hash, so a node flooding the mesh
+//! with arbitrarily many self-controlled IDs can win at most one slot per
+//! distinct hash draw - it cannot force out the rest of the view. Periodic
+//! reseeding keeps an adversary who has learned the current seeds from
+//! gaming future rounds indefinitely.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::node::NodeId;
+
+/// Default size of the sampled view. Small enough that a node in a mesh of
+/// thousands holds a bounded number of sample entries, large enough that
+/// gossip reaches the whole mesh in a handful of rounds.
+pub const DEFAULT_VIEW_SIZE: usize = 20;
+
+/// Number of view entries pushed/pulled per gossip round.
+pub const DEFAULT_FANOUT: usize = 6;
+
+/// A lightweight reference to a peer, just enough to dial and identify it -
+/// exchanged during sampling gossip rather than the heavier `PeerInfo`
+/// that's only populated once a peer is actually connected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerDescriptor {
+    pub node_id: NodeId,
+    pub address: String,
+}
+
+/// One slot of the sampled view: the candidate currently holding it, and
+/// the hash that won it the slot under the slot's current seed.
+struct Slot {
+    seed: u64,
+    holder: Option<(PeerDescriptor, u64)>,
+}
+
+impl Slot {
+    fn hash_of(seed: u64, node_id: &NodeId) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        node_id.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn offer(&mut self, candidate: &PeerDescriptor) {
+        let candidate_hash = Self::hash_of(self.seed, &candidate.node_id);
+        let should_replace = match &self.holder {
+            None => true,
+            Some((held, held_hash)) => {
+                held.node_id == candidate.node_id || candidate_hash < *held_hash
+            }
+        };
+        if should_replace {
+            self.holder = Some((candidate.clone(), candidate_hash));
+        }
+    }
+
+    fn reseed(&mut self) {
+        self.seed = OsRng.next_u64();
+        if let Some((holder, _)) = &self.holder {
+            let rehashed = Self::hash_of(self.seed, &holder.node_id);
+            self.holder.as_mut().unwrap().1 = rehashed;
+        }
+    }
+}
+
+/// Maintains the bounded, attack-resistant partial view described above.
+pub struct PeerSampler {
+    view_size: usize,
+    slots: RwLock<Vec<Slot>>,
+}
+
+impl PeerSampler {
+    /// Create a sampler with `view_size` slots, each seeded independently.
+    pub fn new(view_size: usize) -> Self {
+        let slots = (0..view_size)
+            .map(|_| Slot {
+                seed: OsRng.next_u64(),
+                holder: None,
+            })
+            .collect();
+        Self {
+            view_size,
+            slots: RwLock::new(slots),
+        }
+    }
+
+    /// Offer a batch of candidates - from a fresh connect or a gossip
+    /// exchange - to every slot. Each slot keeps whichever candidate it has
+    /// seen so far minimizes its own keyed hash.
+    pub async fn offer(&self, candidates: &[PeerDescriptor]) {
+        let mut slots = self.slots.write().await;
+        for slot in slots.iter_mut() {
+            for candidate in candidates {
+                slot.offer(candidate);
+            }
+        }
+    }
+
+    /// Regenerate every slot's seed, so an adversary who has inferred the
+    /// current seeds (e.g. by observing which of their IDs won which slot)
+    /// cannot keep gaming future rounds. Already-held candidates are kept,
+    /// just re-ranked under the new seed.
+    pub async fn reseed(&self) {
+        let mut slots = self.slots.write().await;
+        for slot in slots.iter_mut() {
+            slot.reseed();
+        }
+    }
+
+    /// The current sampled view.
+    pub async fn view(&self) -> Vec<PeerDescriptor> {
+        self.slots
+            .read()
+            .await
+            .iter()
+            .filter_map(|slot| slot.holder.as_ref().map(|(d, _)| d.clone()))
+            .collect()
+    }
+
+    /// Number of occupied slots (bounded by `view_size` even as the mesh
+    /// grows far beyond it).
+    pub async fn len(&self) -> usize {
+        self.slots
+            .read()
+            .await
+            .iter()
+            .filter(|s| s.holder.is_some())
+            .count()
+    }
+
+    pub fn view_size(&self) -> usize {
+        self.view_size
+    }
+
+    /// A random subset of the current view to push to a peer during a
+    /// gossip round, at most `DEFAULT_FANOUT` entries.
+    pub async fn push_subset(&self) -> Vec<PeerDescriptor> {
+        let mut view = self.view().await;
+        if view.len() <= DEFAULT_FANOUT {
+            return view;
+        }
+        // Fisher-Yates partial shuffle: only need the first FANOUT slots
+        // to be uniformly selected, not the whole vector ordered.
+        for i in 0..DEFAULT_FANOUT {
+            let j = i + (OsRng.next_u32() as usize) % (view.len() - i);
+            view.swap(i, j);
+        }
+        view.truncate(DEFAULT_FANOUT);
+        view
+    }
+
+    /// Merge a peer's pushed/pulled view into ours (the pull-side of a
+    /// push+pull gossip round). `MeshClient` drives the actual exchange by
+    /// sending/receiving `GossipMessage::PeerList`; this just folds the
+    /// result in.
+    pub async fn merge(&self, remote_view: &[PeerDescriptor]) {
+        self.offer(remote_view).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(node_id: NodeId) -> PeerDescriptor {
+        PeerDescriptor {
+            node_id,
+            address: format!("ws://{}", node_id),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_view_size_stays_bounded() {
+        let sampler = PeerSampler::new(10);
+
+        for _ in 0..1000 {
+            sampler.offer(&[descriptor(NodeId::new())]).await;
+        }
+
+        assert_eq!(sampler.len().await, 10);
+        assert_eq!(sampler.view().await.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_resists_injection_flood() {
+        let sampler = PeerSampler::new(20);
+
+        // A handful of honest peers arrive first.
+        let honest: Vec<PeerDescriptor> = (0..20).map(|_| descriptor(NodeId::new())).collect();
+        sampler.offer(&honest).await;
+
+        // An adversary floods with orders of magnitude more candidates
+        // than there are view slots.
+        let flood: Vec<PeerDescriptor> = (0..50_000).map(|_| descriptor(NodeId::new())).collect();
+        sampler.offer(&flood).await;
+
+        // The view stays exactly bounded regardless of how many candidates
+        // were offered.
+        assert_eq!(sampler.len().await, 20);
+
+        // At least some honest peers should still have survived the flood,
+        // since each slot is won independently by hash rather than by
+        // whoever the adversary most recently sent.
+        let view = sampler.view().await;
+        let honest_ids: std::collections::HashSet<NodeId> =
+            honest.iter().map(|d| d.node_id).collect();
+        let surviving_honest = view
+            .iter()
+            .filter(|d| honest_ids.contains(&d.node_id))
+            .count();
+        assert!(
+            surviving_honest > 0,
+            "expected at least one honest peer to survive a 50,000-candidate flood against 20 slots"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reseed_keeps_view_but_rehashes() {
+        let sampler = PeerSampler::new(5);
+        sampler.offer(&[descriptor(NodeId::new())]).await;
+        let before = sampler.view().await;
+
+        sampler.reseed().await;
+
+        let after = sampler.view().await;
+        assert_eq!(before.len(), after.len());
+        assert_eq!(
+            before
+                .iter()
+                .map(|d| d.node_id)
+                .collect::<std::collections::HashSet<_>>(),
+            after
+                .iter()
+                .map(|d| d.node_id)
+                .collect::<std::collections::HashSet<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_subset_respects_fanout() {
+        let sampler = PeerSampler::new(50);
+        let candidates: Vec<PeerDescriptor> = (0..50).map(|_| descriptor(NodeId::new())).collect();
+        sampler.offer(&candidates).await;
+
+        let subset = sampler.push_subset().await;
+        assert_eq!(subset.len(), DEFAULT_FANOUT);
+    }
+
+    #[tokio::test]
+    async fn test_merge_is_equivalent_to_offer() {
+        let sampler = PeerSampler::new(10);
+        let remote_view: Vec<PeerDescriptor> = (0..10).map(|_| descriptor(NodeId::new())).collect();
+
+        sampler.merge(&remote_view).await;
+
+        assert_eq!(sampler.len().await, 10);
+    }
+}
@@ -0,0 +1,312 @@
+//! Deferred Intent Scheduler
+//!
+//! `IntentRouter::route` commits synchronously to whatever looks cheapest
+//! at call time, so a burst of intents that momentarily exceeds capacity
+//! fails outright even though a peer frees up milliseconds later.
+//! `IntentScheduler` instead queues submissions in an earliest-deadline-
+//! first `BinaryHeap`, and a background tick pops the highest-ranked
+//! intent and re-checks `CapabilityRegistry`/`CostCollector` (via
+//! `IntentRouter::try_dispatch`) before committing - whatever still
+//! doesn't fit, or would exceed its capability type's concurrency cap,
+//! stays queued for the next tick.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::intent::{Intent, IntentRouter, RoutingDecision};
+
+/// How often the loop spawned by [`IntentScheduler::start`] drains the
+/// queue.
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Per-capability-type concurrency cap used when
+/// [`IntentScheduler::with_capacity`] hasn't set a tighter one.
+const DEFAULT_CAPACITY: usize = usize::MAX;
+
+/// One queued submission, ordered for `BinaryHeap` by `priority`
+/// (descending - higher priority pops first) and, as a tiebreaker,
+/// absolute deadline (ascending - the earliest deadline pops first).
+#[derive(Debug, Clone)]
+struct ScheduledIntent {
+    intent: Intent,
+    priority: u8,
+    deadline_ms: u64,
+}
+
+impl ScheduledIntent {
+    fn new(intent: Intent) -> Self {
+        let deadline_ms = intent.created_at_ms.saturating_add(intent.timeout_ms);
+        Self {
+            priority: intent.priority,
+            deadline_ms,
+            intent,
+        }
+    }
+}
+
+impl PartialEq for ScheduledIntent {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.deadline_ms == other.deadline_ms
+    }
+}
+
+impl Eq for ScheduledIntent {}
+
+impl PartialOrd for ScheduledIntent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledIntent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.deadline_ms.cmp(&self.deadline_ms))
+    }
+}
+
+/// Background, earliest-deadline-first scheduler sitting in front of
+/// `IntentRouter::route`.
+pub struct IntentScheduler {
+    router: Arc<IntentRouter>,
+    queue: RwLock<BinaryHeap<ScheduledIntent>>,
+
+    /// Max intents of a capability type allowed `Routed`/`Executing` at
+    /// once. Types absent from this map are uncapped.
+    capacity: HashMap<String, usize>,
+}
+
+impl IntentScheduler {
+    /// Create a scheduler over `router`, with no per-type concurrency cap.
+    pub fn new(router: Arc<IntentRouter>) -> Self {
+        Self {
+            router,
+            queue: RwLock::new(BinaryHeap::new()),
+            capacity: HashMap::new(),
+        }
+    }
+
+    /// Cap how many intents of `capability_type` may be `Routed`/
+    /// `Executing` at once, so a burst of same-typed intents can't
+    /// overcommit a single camera/compute node.
+    pub fn with_capacity(
+        mut self,
+        capability_type: impl Into<String>,
+        max_concurrent: usize,
+    ) -> Self {
+        self.capacity.insert(capability_type.into(), max_concurrent);
+        self
+    }
+
+    /// Enqueue `intent`, tracked as `Pending` immediately. The tick loop
+    /// places it once a target fits within its `max_cost` and its
+    /// capability type's concurrency cap allows it.
+    pub async fn submit(&self, intent: Intent) {
+        self.router.track_pending(&intent).await;
+        self.queue.write().await.push(ScheduledIntent::new(intent));
+    }
+
+    /// Spawn the background loop that ticks every `DEFAULT_TICK_INTERVAL`.
+    /// Abort the returned handle to stop it; the loop holds its own `Arc`
+    /// to the scheduler, so dropping the caller's handles does not stop it.
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let scheduler = self;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(DEFAULT_TICK_INTERVAL).await;
+                scheduler.tick().await;
+            }
+        })
+    }
+
+    /// Drain the queue once, highest-ranked first: dispatch whatever fits
+    /// within its concurrency cap and cost budget, and re-queue whatever
+    /// doesn't for the next tick. Returns the decisions placed this tick.
+    pub async fn tick(&self) -> Vec<RoutingDecision> {
+        let ranked = {
+            let mut queue = self.queue.write().await;
+            std::mem::take(&mut *queue).into_sorted_vec()
+        };
+
+        let mut placed = Vec::new();
+        let mut requeue = Vec::new();
+
+        // `into_sorted_vec` is ascending, so walk it back to front for
+        // highest-priority/earliest-deadline first.
+        for scheduled in ranked.into_iter().rev() {
+            let cap = self
+                .capacity
+                .get(&scheduled.intent.capability_type)
+                .copied()
+                .unwrap_or(DEFAULT_CAPACITY);
+            let in_flight = self
+                .router
+                .in_flight_count(&scheduled.intent.capability_type)
+                .await;
+
+            if in_flight >= cap {
+                requeue.push(scheduled);
+                continue;
+            }
+
+            match self.router.try_dispatch(&scheduled.intent).await {
+                Ok(decision) => placed.push(decision),
+                Err(_) => requeue.push(scheduled),
+            }
+        }
+
+        let mut queue = self.queue.write().await;
+        for scheduled in requeue {
+            queue.push(scheduled);
+        }
+
+        placed
+    }
+
+    /// Number of intents currently queued, awaiting placement.
+    pub async fn queued_count(&self) -> usize {
+        self.queue.read().await.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::{Capability, CapabilityRegistry};
+    use crate::cost::{CostCollector, NodeCost};
+    use crate::metrics::MockMetrics;
+    use crate::node::NodeId;
+
+    fn new_scheduler() -> (Arc<IntentScheduler>, Arc<CapabilityRegistry>, Arc<CostCollector>) {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = Arc::new(IntentRouter::new(
+            Arc::clone(&capabilities),
+            Arc::clone(&cost_collector),
+        ));
+        let scheduler = Arc::new(IntentScheduler::new(router));
+        (scheduler, capabilities, cost_collector)
+    }
+
+    #[tokio::test]
+    async fn test_submit_tracks_pending_and_queues() {
+        let (scheduler, _capabilities, _cost) = new_scheduler();
+
+        let intent = Intent::new("camera", "capture");
+        let intent_id = intent.id;
+        scheduler.submit(intent).await;
+
+        assert_eq!(scheduler.queued_count().await, 1);
+        assert_eq!(
+            scheduler.router.get_status(intent_id).await,
+            Some(crate::intent::IntentStatus::Pending)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tick_places_affordable_intent_and_drains_queue() {
+        let (scheduler, capabilities, _cost) = new_scheduler();
+        capabilities.register(Capability::new("camera", "Front Camera"));
+        scheduler.router.set_local_node_id(NodeId::new()).await;
+
+        let intent = Intent::new("camera", "capture");
+        scheduler.submit(intent).await;
+
+        let placed = scheduler.tick().await;
+
+        assert_eq!(placed.len(), 1);
+        assert_eq!(scheduler.queued_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_tick_requeues_intent_that_cannot_yet_be_placed() {
+        let (scheduler, _capabilities, _cost) = new_scheduler();
+        scheduler.router.set_local_node_id(NodeId::new()).await;
+
+        // No matching capability anywhere, so it can never be placed -
+        // stands in for "not placeable yet".
+        let intent = Intent::new("nonexistent", "action");
+        scheduler.submit(intent).await;
+
+        let placed = scheduler.tick().await;
+
+        assert!(placed.is_empty());
+        assert_eq!(scheduler.queued_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_tick_orders_by_priority_when_capacity_is_tight() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        capabilities.register(Capability::new("camera", "Front Camera"));
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = Arc::new(IntentRouter::new(
+            Arc::clone(&capabilities),
+            Arc::clone(&cost_collector),
+        ));
+        router.set_local_node_id(NodeId::new()).await;
+
+        // Cap at 1 concurrent "camera" dispatch so only the higher-priority
+        // of the two queued intents can be placed this tick.
+        let scheduler = IntentScheduler::new(Arc::clone(&router)).with_capacity("camera", 1);
+
+        let low = Intent::new("camera", "capture").with_priority(1);
+        let low_id = low.id;
+        let high = Intent::new("camera", "capture").with_priority(9);
+        let high_id = high.id;
+
+        scheduler.submit(low).await;
+        scheduler.submit(high).await;
+
+        let placed = scheduler.tick().await;
+
+        assert_eq!(placed.len(), 1);
+        assert!(matches!(
+            router.get_status(high_id).await.unwrap(),
+            crate::intent::IntentStatus::Routed { .. }
+        ));
+        assert_eq!(
+            router.get_status(low_id).await.unwrap(),
+            crate::intent::IntentStatus::Pending
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_cap_throttles_same_capability_type() {
+        let capabilities = Arc::new(CapabilityRegistry::new());
+        let metrics = Arc::new(MockMetrics::default());
+        let cost_collector = Arc::new(CostCollector::new(metrics));
+        let router = Arc::new(IntentRouter::new(
+            Arc::clone(&capabilities),
+            Arc::clone(&cost_collector),
+        ));
+        router.set_local_node_id(NodeId::new()).await;
+
+        let remote = NodeId::new();
+        capabilities.update_remote(remote, vec![Capability::new("camera", "Remote Camera")]);
+        cost_collector.update_peer_cost(
+            remote,
+            NodeCost {
+                total_cost: 0.1,
+                ..Default::default()
+            },
+        );
+
+        let scheduler = IntentScheduler::new(Arc::clone(&router)).with_capacity("camera", 1);
+
+        let first = Intent::new("camera", "capture").prefer_remote();
+        let second = Intent::new("camera", "capture").prefer_remote();
+        scheduler.submit(first).await;
+        scheduler.submit(second).await;
+
+        let placed = scheduler.tick().await;
+
+        assert_eq!(placed.len(), 1);
+        assert_eq!(scheduler.queued_count().await, 1);
+    }
+}
@@ -0,0 +1,198 @@
+//! Deterministic Mesh Simulation
+//!
+//! Testing cost propagation and peer selection across multiple nodes is
+//! otherwise stuck choosing between real networking (slow, flaky, and tied
+//! to `MeshClient`'s WebSocket transport) or wall-clock timers
+//! (nondeterministic once more than one `tokio::time::sleep` is in play).
+//! `MeshSimulation` sidesteps both for cost propagation: it holds the
+//! `CapabilityRegistry`/`CostCollector`/`IntentRouter` trio for each of N
+//! simulated nodes directly - the same pieces this crate's own tests
+//! already build by hand - ticks a shared `SimClock` forward by
+//! `gossip_interval_ms` per `step()`, and exchanges every node's freshly
+//! calculated `NodeCost` with every other node, exactly as a real gossip
+//! round would. No sockets, no sleeps for cost convergence - a test can
+//! drive K deterministic rounds and assert on `get_sorted_peer_costs` or
+//! the resulting routing decision directly. Note that `SimClock` only
+//! backs `CostCollector`'s timestamps here; `IntentRouter`'s own deadline
+//! bookkeeping (`created_at_ms`, watchdog sweeps) still runs on real
+//! wall-clock time, so a simulation shouldn't be used to deterministically
+//! exercise intent timeouts or preemption.
+
+use std::sync::Arc;
+
+use crate::capability::CapabilityRegistry;
+use crate::clock::{Clock, SimClock};
+use crate::cost::CostCollector;
+use crate::intent::IntentRouter;
+use crate::metrics::PlatformMetrics;
+use crate::node::NodeId;
+
+/// One simulated node: the same capabilities/cost-collector/router trio a
+/// real `AtmosphereNode` wires together, minus the networking and identity
+/// machinery `MeshSimulation` has no need to exercise.
+pub struct SimNode {
+    pub id: NodeId,
+    pub capabilities: Arc<CapabilityRegistry>,
+    pub cost_collector: Arc<CostCollector>,
+    pub router: Arc<IntentRouter>,
+}
+
+/// Drives N `SimNode`s through discrete, deterministic gossip rounds over
+/// a shared `SimClock`, instead of real networking and wall-clock timers.
+pub struct MeshSimulation {
+    clock: Arc<SimClock>,
+    gossip_interval_ms: u64,
+    nodes: Vec<SimNode>,
+}
+
+impl MeshSimulation {
+    /// Build a simulation of `node_count` nodes, each given its own
+    /// `PlatformMetrics` by `metrics_factory(index)`, gossiping every
+    /// `gossip_interval_ms` of simulated time.
+    pub fn new(
+        node_count: usize,
+        gossip_interval_ms: u64,
+        mut metrics_factory: impl FnMut(usize) -> Arc<dyn PlatformMetrics>,
+    ) -> Self {
+        let clock = Arc::new(SimClock::new());
+        let nodes = (0..node_count)
+            .map(|i| {
+                let capabilities = Arc::new(CapabilityRegistry::new());
+                let cost_collector = Arc::new(
+                    CostCollector::new(metrics_factory(i))
+                        .with_clock(Arc::clone(&clock) as Arc<dyn Clock>),
+                );
+                let router = Arc::new(IntentRouter::new(
+                    Arc::clone(&capabilities),
+                    Arc::clone(&cost_collector),
+                ));
+
+                SimNode {
+                    id: NodeId::new(),
+                    capabilities,
+                    cost_collector,
+                    router,
+                }
+            })
+            .collect();
+
+        Self {
+            clock,
+            gossip_interval_ms,
+            nodes,
+        }
+    }
+
+    /// The simulated nodes, for a test to inspect or route intents
+    /// through.
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    /// The shared simulation clock, in case a test wants to read the
+    /// current simulated time directly.
+    pub fn clock(&self) -> &Arc<SimClock> {
+        &self.clock
+    }
+
+    /// Advance simulated time by one `gossip_interval_ms` and run one
+    /// gossip round: every node calculates its current local cost, and
+    /// every other node learns it - exactly what a real
+    /// `GossipMessage::Cost` broadcast would do, minus the network.
+    pub fn step(&self) {
+        self.clock.advance(self.gossip_interval_ms);
+
+        let costs: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id, node.cost_collector.calculate_local_cost()))
+            .collect();
+
+        for node in &self.nodes {
+            for (peer_id, cost) in &costs {
+                if *peer_id != node.id {
+                    node.cost_collector.update_peer_cost(*peer_id, cost.clone());
+                }
+            }
+        }
+    }
+
+    /// Run `steps` consecutive gossip rounds.
+    pub fn run(&self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capability::Capability;
+    use crate::intent::Intent;
+    use crate::metrics::MockMetrics;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_peer_costs_converge_across_nodes() {
+        let sim = MeshSimulation::new(3, 30_000, |_| Arc::new(MockMetrics::default()));
+
+        sim.run(3);
+
+        let all_ids: HashSet<NodeId> = sim.nodes().iter().map(|n| n.id).collect();
+        for node in sim.nodes() {
+            let seen: HashSet<NodeId> = node
+                .cost_collector
+                .get_sorted_peer_costs()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            let expected: HashSet<NodeId> = all_ids
+                .iter()
+                .filter(|&&id| id != node.id)
+                .copied()
+                .collect();
+            assert_eq!(seen, expected);
+        }
+    }
+
+    #[test]
+    fn test_gossip_advances_the_shared_sim_clock() {
+        let sim = MeshSimulation::new(2, 10_000, |_| Arc::new(MockMetrics::default()));
+
+        assert_eq!(sim.clock().now_ms(), 0);
+        sim.run(3);
+        assert_eq!(sim.clock().now_ms(), 30_000);
+    }
+
+    #[tokio::test]
+    async fn test_routing_prefers_the_peer_with_healthier_battery() {
+        // Node 0 is a local router deciding where to send a "compute"
+        // intent; node 1 is healthy, node 2 is nearly dead - after a
+        // gossip round propagates both costs, routing should favor node 1.
+        let sim = MeshSimulation::new(3, 30_000, |i| {
+            Arc::new(MockMetrics {
+                battery: Some(if i == 2 { 5.0 } else { 90.0 }),
+                on_battery: true,
+                ..Default::default()
+            })
+        });
+
+        let router_id = sim.nodes()[0].id;
+        sim.nodes()[0].router.set_local_node_id(router_id).await;
+        for remote in &sim.nodes()[1..] {
+            sim.nodes()[0]
+                .capabilities
+                .update_remote(remote.id, vec![Capability::new("compute", "CPU")]);
+        }
+
+        sim.run(1);
+
+        let intent = Intent::new("compute", "process")
+            .prefer_remote()
+            .with_max_cost(1.0);
+        let decision = sim.nodes()[0].router.route(intent).await.unwrap();
+
+        assert_eq!(decision.target(), sim.nodes()[1].id);
+    }
+}
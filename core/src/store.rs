@@ -0,0 +1,258 @@
+//! Durable Intent Journal
+//!
+//! `IntentRouter` tracks `active_intents` only in an in-memory map, so a
+//! process restart - routine on mobile, where the OS kills backgrounded
+//! apps aggressively - loses every in-flight intent along with whatever
+//! result it already produced. `IntentStore` journals each intent plus its
+//! status transitions, mirroring the job/run table CI backends use to
+//! survive a worker restart mid-job. The default `SqliteIntentStore`
+//! buffers writes and flushes them in batches on a background task, so
+//! journalling a status transition never blocks `IntentRouter`'s dispatch
+//! path on disk I/O.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::intent::{Intent, IntentStatus};
+
+/// Persists `Intent`s and their status transitions so an `IntentRouter` can
+/// rehydrate in-flight work after a restart. Implemented by
+/// `SqliteIntentStore`; swap in another implementation to journal
+/// elsewhere (e.g. a platform-native datastore) or to stub persistence out
+/// in tests.
+#[async_trait::async_trait]
+pub trait IntentStore: Send + Sync {
+    /// Journal `intent`'s first appearance, at `status` (normally `Pending`).
+    async fn append(&self, intent: &Intent, status: &IntentStatus);
+
+    /// Record a status transition for an already-appended intent, along
+    /// with its `result` once one is available.
+    async fn update_status(
+        &self,
+        intent_id: Uuid,
+        status: &IntentStatus,
+        result: Option<&serde_json::Value>,
+    );
+
+    /// Every journalled intent still in a non-terminal status, for
+    /// `IntentRouter` to rehydrate on startup.
+    async fn load_active(&self) -> Vec<(Intent, IntentStatus)>;
+
+    /// Drop `intent_id`'s row - called alongside `IntentRouter::cleanup`
+    /// pruning the in-memory map, so the journal doesn't grow without
+    /// bound.
+    async fn prune(&self, intent_id: Uuid);
+}
+
+/// Max buffered writes before a forced flush, regardless of `FLUSH_INTERVAL`.
+const FLUSH_BATCH_SIZE: usize = 32;
+
+/// Max delay before buffered writes are flushed even if `FLUSH_BATCH_SIZE`
+/// hasn't been reached, so a quiet period doesn't strand a transition
+/// unjournalled.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+enum JournalOp {
+    Append {
+        intent: Box<Intent>,
+        status: IntentStatus,
+    },
+    UpdateStatus {
+        intent_id: Uuid,
+        status: IntentStatus,
+        result: Option<serde_json::Value>,
+    },
+    Prune {
+        intent_id: Uuid,
+    },
+}
+
+/// Default `IntentStore`, journalling to a local SQLite file.
+pub struct SqliteIntentStore {
+    conn: Arc<Mutex<Connection>>,
+    ops: mpsc::UnboundedSender<JournalOp>,
+}
+
+impl SqliteIntentStore {
+    /// Open (or create) the journal database at `path` and spawn its
+    /// background flush task.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS intents (
+                intent_id TEXT PRIMARY KEY,
+                intent_json TEXT NOT NULL,
+                status_json TEXT NOT NULL,
+                result_json TEXT,
+                created_at_ms INTEGER NOT NULL
+            )",
+        )?;
+
+        let conn = Arc::new(Mutex::new(conn));
+        let (ops, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::flush_loop(Arc::clone(&conn), receiver));
+
+        Ok(Self { conn, ops })
+    }
+
+    /// Drain `receiver` into batches, flushing on whichever comes first:
+    /// `FLUSH_BATCH_SIZE` buffered ops, or `FLUSH_INTERVAL` of quiet.
+    async fn flush_loop(
+        conn: Arc<Mutex<Connection>>,
+        mut receiver: mpsc::UnboundedReceiver<JournalOp>,
+    ) {
+        let mut batch = Vec::with_capacity(FLUSH_BATCH_SIZE);
+        loop {
+            match tokio::time::timeout(FLUSH_INTERVAL, receiver.recv()).await {
+                Ok(Some(op)) => {
+                    batch.push(op);
+                    if batch.len() < FLUSH_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                Ok(None) if batch.is_empty() => return, // sender dropped, nothing left to flush
+                Ok(None) => {} // sender dropped, flush what's left then exit
+                Err(_elapsed) if batch.is_empty() => continue,
+                Err(_elapsed) => {}
+            }
+
+            let pending = std::mem::take(&mut batch);
+            let conn = Arc::clone(&conn);
+            let flushed =
+                tokio::task::spawn_blocking(move || Self::write_batch(&conn, pending)).await;
+            match flushed {
+                Ok(Err(err)) => {
+                    tracing::warn!(error = %err, "Failed to flush intent journal batch")
+                }
+                Err(join_err) => {
+                    tracing::warn!(error = %join_err, "Intent journal flush task panicked")
+                }
+                Ok(Ok(())) => {}
+            }
+        }
+    }
+
+    fn write_batch(conn: &Mutex<Connection>, batch: Vec<JournalOp>) -> rusqlite::Result<()> {
+        let mut conn = conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for op in batch {
+            match op {
+                JournalOp::Append { intent, status } => {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO intents
+                            (intent_id, intent_json, status_json, result_json, created_at_ms)
+                         VALUES (?1, ?2, ?3, NULL, ?4)",
+                        params![
+                            intent.id.to_string(),
+                            serde_json::to_string(&intent).unwrap_or_default(),
+                            serde_json::to_string(&status).unwrap_or_default(),
+                            intent.created_at_ms as i64,
+                        ],
+                    )?;
+                }
+                JournalOp::UpdateStatus {
+                    intent_id,
+                    status,
+                    result,
+                } => {
+                    tx.execute(
+                        "UPDATE intents SET status_json = ?2, result_json = ?3 WHERE intent_id = ?1",
+                        params![
+                            intent_id.to_string(),
+                            serde_json::to_string(&status).unwrap_or_default(),
+                            result.map(|r| serde_json::to_string(&r).unwrap_or_default()),
+                        ],
+                    )?;
+                }
+                JournalOp::Prune { intent_id } => {
+                    tx.execute(
+                        "DELETE FROM intents WHERE intent_id = ?1",
+                        params![intent_id.to_string()],
+                    )?;
+                }
+            }
+        }
+        tx.commit()
+    }
+
+    fn read_active(conn: &Mutex<Connection>) -> Vec<(Intent, IntentStatus)> {
+        let conn = conn.lock().unwrap();
+        let mut stmt = match conn.prepare("SELECT intent_json, status_json FROM intents") {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to prepare intent journal read");
+                return Vec::new();
+            }
+        };
+
+        let rows = stmt.query_map([], |row| {
+            let intent_json: String = row.get(0)?;
+            let status_json: String = row.get(1)?;
+            Ok((intent_json, status_json))
+        });
+
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(err) => {
+                tracing::warn!(error = %err, "Failed to read intent journal");
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(Result::ok)
+            .filter_map(|(intent_json, status_json)| {
+                let intent: Intent = serde_json::from_str(&intent_json).ok()?;
+                let status: IntentStatus = serde_json::from_str(&status_json).ok()?;
+                matches!(
+                    status,
+                    IntentStatus::Pending
+                        | IntentStatus::Routed { .. }
+                        | IntentStatus::Executing { .. }
+                )
+                .then_some((intent, status))
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl IntentStore for SqliteIntentStore {
+    async fn append(&self, intent: &Intent, status: &IntentStatus) {
+        let _ = self.ops.send(JournalOp::Append {
+            intent: Box::new(intent.clone()),
+            status: status.clone(),
+        });
+    }
+
+    async fn update_status(
+        &self,
+        intent_id: Uuid,
+        status: &IntentStatus,
+        result: Option<&serde_json::Value>,
+    ) {
+        let _ = self.ops.send(JournalOp::UpdateStatus {
+            intent_id,
+            status: status.clone(),
+            result: result.cloned(),
+        });
+    }
+
+    async fn load_active(&self) -> Vec<(Intent, IntentStatus)> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || Self::read_active(&conn))
+            .await
+            .unwrap_or_else(|join_err| {
+                tracing::warn!(error = %join_err, "Intent journal read task panicked");
+                Vec::new()
+            })
+    }
+
+    async fn prune(&self, intent_id: Uuid) {
+        let _ = self.ops.send(JournalOp::Prune { intent_id });
+    }
+}
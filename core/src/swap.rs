@@ -0,0 +1,306 @@
+//! Model weight swap subsystem
+//!
+//! Memory-maps model tensor layers from a file on disk and pages them in/out
+//! based on a configurable memory budget, so a model larger than available
+//! RAM can still run on constrained devices (inspired by nntrainer's Android
+//! swap support, which mmaps layer weights to `/data/local/tmp`).
+//! `SwapManager::is_fully_resident_viable` reports whether free memory
+//! covers the whole budget; `get_layer` enforces the budget either way, so
+//! the manager never switches to a separate fully-resident mode.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::PlatformMetrics;
+
+/// Identifies a single layer's weights within the swap file.
+pub type LayerId = u32;
+
+/// Where a layer's weights live in the backing file.
+#[derive(Debug, Clone, Copy)]
+struct LayerLocation {
+    offset: u64,
+    len: u64,
+}
+
+/// A layer's weights once paged into memory.
+struct ResidentLayer {
+    bytes: Vec<u8>,
+    /// Monotonically increasing access counter, used to find the least
+    /// recently used layer when the budget is exceeded.
+    last_used: u64,
+}
+
+/// Configuration for the swap subsystem, surfaced on `NodeConfig`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapConfig {
+    /// Directory used to store/mmap swapped layer files.
+    pub swap_dir: PathBuf,
+
+    /// Maximum resident set size for paged-in layers, in megabytes.
+    pub budget_mb: u64,
+
+    /// Threshold of `available_memory_mb` below which layers should be
+    /// evicted to stay under budget rather than growing further.
+    pub low_memory_threshold_mb: u64,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        Self {
+            swap_dir: PathBuf::from("/data/local/tmp/atmosphere-swap"),
+            budget_mb: 512,
+            low_memory_threshold_mb: 256,
+        }
+    }
+}
+
+/// Pages model layers in and out of a memory budget, backed by a single
+/// mmap-able file containing each layer's raw tensor bytes back to back.
+pub struct SwapManager {
+    config: SwapConfig,
+    metrics: Arc<dyn PlatformMetrics>,
+    layout: HashMap<LayerId, LayerLocation>,
+    resident: Mutex<HashMap<LayerId, ResidentLayer>>,
+    access_clock: Mutex<u64>,
+}
+
+impl SwapManager {
+    /// Create a swap manager over the `weights.bin` file in
+    /// `config.swap_dir`, whose layers are described by `layout` (layer id
+    /// -> byte range within the file).
+    pub fn new(
+        config: SwapConfig,
+        metrics: Arc<dyn PlatformMetrics>,
+        layout: HashMap<LayerId, LayerLocation>,
+    ) -> Self {
+        Self {
+            config,
+            metrics,
+            layout,
+            resident: Mutex::new(HashMap::new()),
+            access_clock: Mutex::new(0),
+        }
+    }
+
+    /// Build the layout for a swap file whose layers are stored back to back
+    /// in order, each `layer_bytes` long.
+    pub fn uniform_layout(layer_count: u32, layer_bytes: u64) -> HashMap<LayerId, LayerLocation> {
+        (0..layer_count)
+            .map(|id| {
+                (
+                    id,
+                    LayerLocation {
+                        offset: u64::from(id) * layer_bytes,
+                        len: layer_bytes,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the device currently has enough free memory to run fully
+    /// resident (no eviction needed).
+    pub fn is_fully_resident_viable(&self) -> bool {
+        self.metrics.available_memory_mb() >= self.config.budget_mb
+    }
+
+    /// Get a layer's weights, paging it in from `swap_dir` if it isn't
+    /// already resident. Evicts least-recently-used layers first if the
+    /// configured budget would otherwise be exceeded.
+    pub fn get_layer(&self, layer_id: LayerId) -> std::io::Result<Vec<u8>> {
+        let mut resident = self.resident.lock().unwrap();
+
+        let mut clock = self.access_clock.lock().unwrap();
+        *clock += 1;
+        let now = *clock;
+        drop(clock);
+
+        if let Some(layer) = resident.get_mut(&layer_id) {
+            layer.last_used = now;
+            return Ok(layer.bytes.clone());
+        }
+
+        let location = *self
+            .layout
+            .get(&layer_id)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "unknown layer id"))?;
+
+        let bytes = self.read_layer_from_disk(location)?;
+
+        self.evict_if_over_budget(&mut resident, bytes.len() as u64);
+        resident.insert(
+            layer_id,
+            ResidentLayer {
+                bytes: bytes.clone(),
+                last_used: now,
+            },
+        );
+
+        Ok(bytes)
+    }
+
+    /// Drop any resident layers, freeing their memory without forgetting
+    /// the on-disk layout (they can be paged back in on demand).
+    pub fn evict_all(&self) {
+        self.resident.lock().unwrap().clear();
+    }
+
+    /// Check current device memory pressure and evict layers if it has
+    /// dropped below `low_memory_threshold_mb`. Intended to be called
+    /// periodically alongside cost collection.
+    pub fn check_memory_pressure(&self) {
+        if self.metrics.available_memory_mb() < self.config.low_memory_threshold_mb {
+            let mut resident = self.resident.lock().unwrap();
+            self.evict_lru(&mut resident, resident.len());
+        }
+    }
+
+    /// Total bytes currently resident across all paged-in layers.
+    pub fn resident_bytes(&self) -> u64 {
+        self.resident
+            .lock()
+            .unwrap()
+            .values()
+            .map(|l| l.bytes.len() as u64)
+            .sum()
+    }
+
+    /// Map the swap file and copy out just `location`'s range. mmap rather
+    /// than `seek`+`read_exact` so the OS pages in only the pages this
+    /// layer actually touches, and so repeated reads of a layer that's been
+    /// evicted and re-paged-in benefit from the page cache instead of
+    /// re-issuing a read syscall over the same bytes every time.
+    fn read_layer_from_disk(&self, location: LayerLocation) -> std::io::Result<Vec<u8>> {
+        let path = self.swap_file_path();
+        let file = File::open(&path)?;
+        // SAFETY: `file` is our own swap file under `swap_dir`; nothing else
+        // in this process truncates or rewrites it while mapped, so the
+        // mapping stays valid for the lifetime of this call.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let start = location.offset as usize;
+        let end = start
+            .checked_add(location.len as usize)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "layer range overflows"))?;
+        let slice = mmap.get(start..end).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "layer range exceeds swap file length",
+            )
+        })?;
+
+        Ok(slice.to_vec())
+    }
+
+    fn swap_file_path(&self) -> PathBuf {
+        self.config.swap_dir.join("weights.bin")
+    }
+
+    fn evict_if_over_budget(&self, resident: &mut HashMap<LayerId, ResidentLayer>, incoming_len: u64) {
+        let budget_bytes = self.config.budget_mb * 1024 * 1024;
+        let mut current: u64 = resident.values().map(|l| l.bytes.len() as u64).sum();
+
+        while current + incoming_len > budget_bytes && !resident.is_empty() {
+            if let Some((&lru_id, _)) = resident.iter().min_by_key(|(_, l)| l.last_used) {
+                if let Some(removed) = resident.remove(&lru_id) {
+                    current -= removed.bytes.len() as u64;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn evict_lru(&self, resident: &mut HashMap<LayerId, ResidentLayer>, count: usize) {
+        let mut by_recency: Vec<LayerId> = resident.keys().copied().collect();
+        by_recency.sort_by_key(|id| resident[id].last_used);
+
+        for id in by_recency.into_iter().take(count / 2 + count % 2) {
+            resident.remove(&id);
+        }
+    }
+}
+
+/// Whether `path`'s parent directory exists, used to validate a configured
+/// swap directory before attempting to mmap into it.
+pub fn swap_dir_available(path: &Path) -> bool {
+    path.parent().map(|p| p.exists()).unwrap_or(false) || path.exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MockMetrics;
+    use std::io::Write;
+
+    fn write_test_swap_file(dir: &Path, layer_count: u32, layer_bytes: u64) {
+        std::fs::create_dir_all(dir).unwrap();
+        let mut file = File::create(dir.join("weights.bin")).unwrap();
+        for layer in 0..layer_count {
+            let fill = (layer % 256) as u8;
+            file.write_all(&vec![fill; layer_bytes as usize]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_get_layer_reads_correct_bytes() {
+        let dir = std::env::temp_dir().join("atmosphere_swap_test_basic");
+        write_test_swap_file(&dir, 4, 16);
+
+        let config = SwapConfig {
+            swap_dir: dir,
+            budget_mb: 1,
+            low_memory_threshold_mb: 0,
+        };
+        let metrics = Arc::new(MockMetrics::default());
+        let layout = SwapManager::uniform_layout(4, 16);
+        let manager = SwapManager::new(config, metrics, layout);
+
+        let layer2 = manager.get_layer(2).unwrap();
+        assert_eq!(layer2, vec![2u8; 16]);
+    }
+
+    #[test]
+    fn test_eviction_respects_budget() {
+        let dir = std::env::temp_dir().join("atmosphere_swap_test_budget");
+        // Each layer is 1 MiB; budget is 2 MiB, so only 2 can be resident.
+        write_test_swap_file(&dir, 4, 1024 * 1024);
+
+        let config = SwapConfig {
+            swap_dir: dir,
+            budget_mb: 2,
+            low_memory_threshold_mb: 0,
+        };
+        let metrics = Arc::new(MockMetrics::default());
+        let layout = SwapManager::uniform_layout(4, 1024 * 1024);
+        let manager = SwapManager::new(config, metrics, layout);
+
+        manager.get_layer(0).unwrap();
+        manager.get_layer(1).unwrap();
+        manager.get_layer(2).unwrap();
+
+        assert!(manager.resident_bytes() <= 2 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_fully_resident_viable_when_ample_memory() {
+        let config = SwapConfig {
+            budget_mb: 512,
+            ..Default::default()
+        };
+        let metrics = Arc::new(MockMetrics {
+            memory_mb: 4096,
+            total_memory_mb: 8192,
+            ..Default::default()
+        });
+        let manager = SwapManager::new(config, metrics, HashMap::new());
+
+        assert!(manager.is_fully_resident_viable());
+    }
+}
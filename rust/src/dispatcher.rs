@@ -0,0 +1,121 @@
+//! Inference session dispatcher
+//!
+//! Mirrors the Dispatcher/unique_jvm pattern used elsewhere in Android JNI
+//! layers: a single cached `JavaVM` plus a table of in-flight sessions lets
+//! native worker threads call back into Java safely, and lets callers cancel
+//! a specific generation without tearing down the whole engine.
+
+use jni::{JavaVM, JNIEnv};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Cached reference to the JVM this library was loaded into.
+///
+/// Set once from `JNI_OnLoad`-equivalent initialization (here, the first
+/// call that hands us a `JNIEnv`) and reused by every worker thread that
+/// needs to attach itself to call back into Java.
+static JVM: OnceLock<JavaVM> = OnceLock::new();
+
+/// Monotonically increasing session id generator.
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// State tracked per in-flight inference session.
+struct Session {
+    /// Checked by the token loop; set to request early termination.
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Registry of active inference sessions, keyed by session id.
+static SESSIONS: Mutex<Option<HashMap<u64, Session>>> = Mutex::new(None);
+
+/// Cache the `JavaVM` the first time we see an env, so later worker threads
+/// can attach without needing a `JNIEnv` handed to them.
+pub fn cache_vm(env: &JNIEnv) {
+    if JVM.get().is_none() {
+        if let Ok(vm) = env.get_java_vm() {
+            let _ = JVM.set(vm);
+        }
+    }
+}
+
+/// Get the cached `JavaVM`, if one has been recorded yet.
+pub fn cached_vm() -> Option<&'static JavaVM> {
+    JVM.get()
+}
+
+/// Register a new session and return its id plus a cancellation flag the
+/// token loop should check on every iteration.
+pub fn start_session() -> (u64, Arc<AtomicBool>) {
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    SESSIONS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(
+            id,
+            Session {
+                cancelled: Arc::clone(&cancelled),
+            },
+        );
+
+    (id, cancelled)
+}
+
+/// Request cancellation of a running session. Returns `false` if the
+/// session id is unknown (already completed, or never existed).
+pub fn cancel_session(session_id: u64) -> bool {
+    let sessions = SESSIONS.lock().unwrap();
+    match sessions.as_ref().and_then(|s| s.get(&session_id)) {
+        Some(session) => {
+            session.cancelled.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Remove a session's bookkeeping once it completes or is cancelled. The
+/// session's `GlobalRef` callback is not held here: it is owned by the
+/// worker thread running the session and released when that thread's work
+/// returns.
+pub fn end_session(session_id: u64) {
+    if let Some(sessions) = SESSIONS.lock().unwrap().as_mut() {
+        sessions.remove(&session_id);
+    }
+}
+
+/// Whether any session is currently in flight.
+pub fn is_busy() -> bool {
+    SESSIONS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| !s.is_empty())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_lifecycle() {
+        let (id, cancelled) = start_session();
+        assert!(!cancelled.load(Ordering::SeqCst));
+        assert!(is_busy());
+
+        assert!(cancel_session(id));
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        end_session(id);
+        assert!(!cancel_session(id));
+    }
+
+    #[test]
+    fn test_cancel_unknown_session() {
+        assert!(!cancel_session(u64::MAX));
+    }
+}
@@ -0,0 +1,277 @@
+//! On-device inference engine
+//!
+//! Wraps a loaded GGUF model (via `llama-cpp-2`'s bindings to llama.cpp) and
+//! exposes both whole-response and token-streaming generation. Kept separate
+//! from the JNI glue so it can be unit tested without a JVM.
+
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors produced while loading or running the inference engine
+#[derive(Error, Debug)]
+pub enum EngineError {
+    #[error("failed to load model from {path}: {reason}")]
+    LoadFailed { path: String, reason: String },
+
+    #[error("inference failed: {0}")]
+    GenerationFailed(String),
+}
+
+/// Generates tokens for a loaded model, decoupling `Engine`'s lifecycle
+/// (load once, stream many times across JNI calls) from any one inference
+/// library - the same trait-object seam `PlatformMetrics` gives
+/// `atmosphere-core`'s `CostCollector` over platform-specific hardware
+/// reads. `LlamaCppBackend` is the real implementation; `EchoBackend`
+/// exists only so `Engine`'s lifecycle and streaming contract can be
+/// exercised in tests without a GGUF file on disk.
+trait InferenceBackend: Send {
+    /// Load `path`, sizing the backend's context to `context_size` tokens.
+    fn load(&mut self, path: &Path, context_size: u32) -> Result<(), EngineError>;
+
+    /// Generate tokens for `prompt`, calling `on_token` once per token and
+    /// checking `should_cancel` before each one so generation can stop
+    /// early without that counting as a failure.
+    fn generate_streaming(
+        &mut self,
+        prompt: &str,
+        on_token: &mut dyn FnMut(&str),
+        should_cancel: &mut dyn FnMut() -> bool,
+    ) -> Result<(), EngineError>;
+}
+
+/// Real on-device inference via `llama-cpp-2`'s bindings to llama.cpp:
+/// tokenizes the prompt, decodes it in a single batch, then greedily
+/// samples and decodes one token at a time until the model emits its
+/// end-of-generation token or `should_cancel` fires. `load` does not apply
+/// `context_size`: each generation creates its context from
+/// `LlamaContextParams::default()`.
+struct LlamaCppBackend {
+    backend: llama_cpp_2::llama_backend::LlamaBackend,
+    model: Option<llama_cpp_2::model::LlamaModel>,
+}
+
+impl LlamaCppBackend {
+    fn new() -> Result<Self, EngineError> {
+        let backend = llama_cpp_2::llama_backend::LlamaBackend::init().map_err(|e| EngineError::LoadFailed {
+            path: String::new(),
+            reason: format!("failed to initialize llama.cpp backend: {}", e),
+        })?;
+        Ok(Self { backend, model: None })
+    }
+}
+
+impl InferenceBackend for LlamaCppBackend {
+    fn load(&mut self, path: &Path, _context_size: u32) -> Result<(), EngineError> {
+        let params = llama_cpp_2::model::params::LlamaModelParams::default();
+        let model =
+            llama_cpp_2::model::LlamaModel::load_from_file(&self.backend, path, &params).map_err(|e| {
+                EngineError::LoadFailed {
+                    path: path.display().to_string(),
+                    reason: e.to_string(),
+                }
+            })?;
+        self.model = Some(model);
+        Ok(())
+    }
+
+    fn generate_streaming(
+        &mut self,
+        prompt: &str,
+        on_token: &mut dyn FnMut(&str),
+        should_cancel: &mut dyn FnMut() -> bool,
+    ) -> Result<(), EngineError> {
+        use llama_cpp_2::model::AddBos;
+
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| EngineError::GenerationFailed("no model loaded".to_string()))?;
+
+        let tokens = model
+            .str_to_token(prompt, AddBos::Always)
+            .map_err(|e| EngineError::GenerationFailed(format!("tokenizing prompt: {}", e)))?;
+
+        let ctx_params = llama_cpp_2::context::params::LlamaContextParams::default();
+        let mut ctx = model
+            .new_context(&self.backend, ctx_params)
+            .map_err(|e| EngineError::GenerationFailed(format!("creating context: {}", e)))?;
+
+        let mut batch = llama_cpp_2::llama_batch::LlamaBatch::new(tokens.len().max(512), 1);
+        for (i, token) in tokens.iter().enumerate() {
+            let is_last = i == tokens.len() - 1;
+            batch
+                .add(*token, i as i32, &[0], is_last)
+                .map_err(|e| EngineError::GenerationFailed(format!("building prompt batch: {}", e)))?;
+        }
+        ctx.decode(&mut batch)
+            .map_err(|e| EngineError::GenerationFailed(format!("decoding prompt: {}", e)))?;
+
+        let mut n_cur = batch.n_tokens();
+        loop {
+            if should_cancel() {
+                break;
+            }
+
+            let next_token = ctx
+                .sample_token_greedy(batch.n_tokens() - 1)
+                .map_err(|e| EngineError::GenerationFailed(format!("sampling token: {}", e)))?;
+
+            if model.is_eog_token(next_token) {
+                break;
+            }
+
+            let piece = model
+                .token_to_str(next_token, llama_cpp_2::model::Special::Tokenize)
+                .map_err(|e| EngineError::GenerationFailed(format!("detokenizing output: {}", e)))?;
+            on_token(&piece);
+
+            batch.clear();
+            batch
+                .add(next_token, n_cur, &[0], true)
+                .map_err(|e| EngineError::GenerationFailed(format!("building decode batch: {}", e)))?;
+            n_cur += 1;
+
+            ctx.decode(&mut batch)
+                .map_err(|e| EngineError::GenerationFailed(format!("decoding token: {}", e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Word-chunked prompt echo, standing in for a real backend so `Engine`'s
+/// lifecycle and streaming contract are exercisable in tests without a
+/// GGUF file or linking against libllama.
+struct EchoBackend;
+
+impl InferenceBackend for EchoBackend {
+    fn load(&mut self, _path: &Path, _context_size: u32) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn generate_streaming(
+        &mut self,
+        prompt: &str,
+        on_token: &mut dyn FnMut(&str),
+        should_cancel: &mut dyn FnMut() -> bool,
+    ) -> Result<(), EngineError> {
+        for word in prompt.split_whitespace() {
+            if should_cancel() {
+                break;
+            }
+            on_token(word);
+            on_token(" ");
+        }
+        Ok(())
+    }
+}
+
+/// A loaded model ready to run inference.
+///
+/// `backend` keeps the weights resident for the lifetime of this struct so
+/// repeated calls to `generate`/`generate_streaming` don't pay reload cost.
+pub struct Engine {
+    model_path: String,
+    context_size: u32,
+    backend: Box<dyn InferenceBackend>,
+}
+
+impl Engine {
+    /// Load a model from `path`. Fails if the file doesn't exist or the
+    /// backend rejects the format.
+    pub fn load(path: &str) -> Result<Self, EngineError> {
+        if !Path::new(path).exists() {
+            return Err(EngineError::LoadFailed {
+                path: path.to_string(),
+                reason: "file not found".to_string(),
+            });
+        }
+
+        let context_size = 4096;
+        let mut backend: Box<dyn InferenceBackend> = Box::new(LlamaCppBackend::new()?);
+        backend.load(Path::new(path), context_size)?;
+
+        Ok(Self {
+            model_path: path.to_string(),
+            context_size,
+            backend,
+        })
+    }
+
+    /// Path the currently loaded model was read from.
+    pub fn model_path(&self) -> &str {
+        &self.model_path
+    }
+
+    /// Run inference to completion and return the full response.
+    pub fn generate(&mut self, prompt: &str) -> Result<String, EngineError> {
+        let mut response = String::new();
+        self.generate_streaming(prompt, |token| response.push_str(token))?;
+        Ok(response)
+    }
+
+    /// Run inference, invoking `on_token` once per generated token.
+    ///
+    /// Kept generic over the callback so the JNI layer can pass a closure
+    /// that calls back into Java, while tests can pass a closure that just
+    /// appends to a `Vec`.
+    pub fn generate_streaming(
+        &mut self,
+        prompt: &str,
+        on_token: impl FnMut(&str),
+    ) -> Result<(), EngineError> {
+        self.generate_streaming_cancellable(prompt, on_token, || false)
+    }
+
+    /// Like `generate_streaming`, but checks `should_cancel` before emitting
+    /// each token and stops early (without error) when it returns `true`.
+    pub fn generate_streaming_cancellable(
+        &mut self,
+        prompt: &str,
+        mut on_token: impl FnMut(&str),
+        mut should_cancel: impl FnMut() -> bool,
+    ) -> Result<(), EngineError> {
+        if prompt.is_empty() {
+            return Err(EngineError::GenerationFailed("empty prompt".to_string()));
+        }
+
+        self.backend.generate_streaming(prompt, &mut on_token, &mut should_cancel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = Engine::load("/nonexistent/model.gguf");
+        assert!(matches!(result, Err(EngineError::LoadFailed { .. })));
+    }
+
+    #[test]
+    fn test_generate_streaming_empty_prompt() {
+        let mut engine = Engine {
+            model_path: "test".to_string(),
+            context_size: 4096,
+            backend: Box::new(EchoBackend),
+        };
+        let result = engine.generate_streaming("", |_| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_generate_streaming_emits_tokens() {
+        let mut engine = Engine {
+            model_path: "test".to_string(),
+            context_size: 4096,
+            backend: Box::new(EchoBackend),
+        };
+        let mut tokens = Vec::new();
+        engine
+            .generate_streaming("hello world", |t| tokens.push(t.to_string()))
+            .unwrap();
+        assert!(tokens.iter().any(|t| t == "hello"));
+        assert!(tokens.iter().any(|t| t == "world"));
+    }
+}
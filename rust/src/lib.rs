@@ -3,14 +3,19 @@
 //! This library provides JNI bindings for the Atmosphere Android app,
 //! exposing LLM inference capabilities to Kotlin/Java code.
 
-use jni::objects::{JClass, JString};
-use jni::sys::{jboolean, jstring, JNI_FALSE, JNI_TRUE};
-use jni::JNIEnv;
-use log::{info, Level};
-use std::sync::atomic::{AtomicBool, Ordering};
+mod dispatcher;
+mod engine;
 
-// Global state for model loading
-static MODEL_LOADED: AtomicBool = AtomicBool::new(false);
+use engine::{Engine, EngineError};
+use jni::objects::{GlobalRef, JClass, JObject, JString};
+use jni::sys::{jboolean, jlong, jstring, JNI_FALSE, JNI_TRUE};
+use jni::{JNIEnv, JavaVM};
+use log::{error, info, Level};
+use std::sync::Mutex;
+
+/// Global inference engine, loaded by `nativeLoadModel` and reused across
+/// `nativeInference`/`nativeInferenceStreaming` calls until unloaded.
+static ENGINE: Mutex<Option<Engine>> = Mutex::new(None);
 
 /// Initialize Android logger
 #[allow(dead_code)]
@@ -22,7 +27,14 @@ fn init_logger() {
     );
 }
 
-/// JNI: Run inference on the given prompt
+/// Turn an `EngineError` into a Java `IllegalStateException` on `env`. The
+/// caller still returns its own null/sentinel value to the VM.
+fn throw_engine_error(env: &mut JNIEnv, err: &EngineError) {
+    let _ = env.throw_new("java/lang/IllegalStateException", err.to_string());
+}
+
+/// JNI: Run inference on the given prompt, returning the full response
+/// once generation completes.
 ///
 /// # Arguments
 /// * `env` - JNI environment
@@ -30,49 +42,207 @@ fn init_logger() {
 /// * `prompt` - Input prompt string
 ///
 /// # Returns
-/// Generated response string
+/// Generated response string, or throws `IllegalStateException` on failure.
 #[no_mangle]
 pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_nativeInference(
     mut env: JNIEnv,
     _class: JClass,
     prompt: JString,
 ) -> jstring {
-    // Get the input string from Java
     let input: String = match env.get_string(&prompt) {
         Ok(s) => s.into(),
         Err(e) => {
-            let error_msg = format!("Failed to get prompt string: {}", e);
-            return env
-                .new_string(error_msg)
-                .expect("Couldn't create error string")
-                .into_raw();
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Failed to get prompt string: {}", e),
+            );
+            return std::ptr::null_mut();
         }
     };
 
     info!("Received inference request: {} chars", input.len());
 
-    // TODO: Implement actual LLM inference here
-    // For now, return a placeholder response
-    let response = if MODEL_LOADED.load(Ordering::SeqCst) {
-        // Placeholder for actual inference
-        format!("Model response to: {}", input)
+    let mut guard = ENGINE.lock().unwrap();
+    let engine = match guard.as_mut() {
+        Some(engine) => engine,
+        None => {
+            let _ = env.throw_new("java/lang/IllegalStateException", "Model not loaded");
+            return std::ptr::null_mut();
+        }
+    };
+
+    match engine.generate(&input) {
+        Ok(response) => match env.new_string(response) {
+            Ok(output) => output.into_raw(),
+            Err(e) => {
+                let _ = env.throw_new(
+                    "java/lang/IllegalStateException",
+                    format!("Failed to create response string: {}", e),
+                );
+                std::ptr::null_mut()
+            }
+        },
+        Err(e) => {
+            throw_engine_error(&mut env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// JNI: Run inference on the given prompt, invoking `callback.onToken(String)`
+/// once per generated token on a dedicated worker thread.
+///
+/// The worker thread is attached to the JVM for the duration of generation
+/// and detached once the call returns, so `onToken` is always invoked from a
+/// thread the JVM knows about.
+///
+/// # Returns
+/// A session id to pass to `nativeCancelInference`, or `0` if no model is
+/// loaded / the call could not be dispatched.
+#[no_mangle]
+pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_nativeInferenceStreaming(
+    mut env: JNIEnv,
+    _class: JClass,
+    prompt: JString,
+    callback: JObject,
+) -> jlong {
+    let input: String = match env.get_string(&prompt) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Failed to get prompt string: {}", e),
+            );
+            return 0;
+        }
+    };
+
+    if ENGINE.lock().unwrap().is_none() {
+        let _ = env.throw_new("java/lang/IllegalStateException", "Model not loaded");
+        return 0;
+    }
+
+    let callback_ref = match env.new_global_ref(callback) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalStateException",
+                format!("Failed to pin callback: {}", e),
+            );
+            return 0;
+        }
+    };
+
+    dispatcher::cache_vm(&env);
+    let jvm = match dispatcher::cached_vm() {
+        Some(jvm) => jvm,
+        None => {
+            let _ = env.throw_new("java/lang/IllegalStateException", "Failed to cache JavaVM");
+            return 0;
+        }
+    };
+
+    let (session_id, cancelled) = dispatcher::start_session();
+    std::thread::spawn(move || stream_tokens(jvm, callback_ref, input, session_id, cancelled));
+
+    session_id as jlong
+}
+
+/// JNI: Request cancellation of an in-flight streaming session started by
+/// `nativeInferenceStreaming`. The token loop observes the cancellation on
+/// its next iteration and stops after emitting `onDone`.
+///
+/// # Returns
+/// `true` if `session_id` referred to a known in-flight session.
+#[no_mangle]
+pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_nativeCancelInference(
+    _env: JNIEnv,
+    _class: JClass,
+    session_id: jlong,
+) -> jboolean {
+    if dispatcher::cancel_session(session_id as u64) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+/// JNI: Whether any inference session is currently in flight.
+#[no_mangle]
+pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_nativeIsBusy(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jboolean {
+    if dispatcher::is_busy() {
+        JNI_TRUE
     } else {
-        format!(
-            "Model not loaded. Echo: {}",
-            input.chars().take(100).collect::<String>()
-        )
+        JNI_FALSE
+    }
+}
+
+/// Worker body for streaming inference: attaches to the JVM, generates
+/// tokens one at a time, and calls `onToken`/`onDone`/`onError` on `callback`.
+///
+/// Cleans up the session's dispatcher entry (and with it the `GlobalRef`
+/// callback) on every exit path, including cancellation.
+fn stream_tokens(
+    jvm: &'static JavaVM,
+    callback: GlobalRef,
+    prompt: String,
+    session_id: u64,
+    cancelled: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) {
+    let mut env = match jvm.attach_current_thread() {
+        Ok(env) => env,
+        Err(e) => {
+            error!("Failed to attach inference worker to JVM: {}", e);
+            dispatcher::end_session(session_id);
+            return;
+        }
+    };
+
+    let mut guard = ENGINE.lock().unwrap();
+    let engine = match guard.as_mut() {
+        Some(engine) => engine,
+        None => {
+            let _ = env.call_method(
+                &callback,
+                "onError",
+                "(Ljava/lang/String;)V",
+                &[(&env.new_string("Model not loaded").unwrap()).into()],
+            );
+            drop(guard);
+            dispatcher::end_session(session_id);
+            return;
+        }
     };
 
-    // Return the response
-    match env.new_string(response) {
-        Ok(output) => output.into_raw(),
+    let result = engine.generate_streaming_cancellable(
+        &prompt,
+        |token| {
+            let jtoken = match env.new_string(token) {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+            let _ = env.call_method(&callback, "onToken", "(Ljava/lang/String;)V", &[(&jtoken).into()]);
+        },
+        || cancelled.load(std::sync::atomic::Ordering::SeqCst),
+    );
+    drop(guard);
+
+    match result {
+        Ok(()) => {
+            let _ = env.call_method(&callback, "onDone", "()V", &[]);
+        }
         Err(e) => {
-            let error_msg = format!("Failed to create response string: {}", e);
-            env.new_string(error_msg)
-                .expect("Couldn't create error string")
-                .into_raw()
+            if let Ok(msg) = env.new_string(e.to_string()) {
+                let _ = env.call_method(&callback, "onError", "(Ljava/lang/String;)V", &[(&msg).into()]);
+            }
         }
     }
+
+    dispatcher::end_session(session_id);
+    // `env` drops here, detaching the worker thread from the JVM.
 }
 
 /// JNI: Load a model from the given path
@@ -100,12 +270,18 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_00024Com
 
     info!("Loading model from: {}", path);
 
-    // TODO: Implement actual model loading
-    // For now, simulate success
-    MODEL_LOADED.store(true, Ordering::SeqCst);
-
-    info!("Model loaded successfully");
-    JNI_TRUE
+    match Engine::load(&path) {
+        Ok(engine) => {
+            *ENGINE.lock().unwrap() = Some(engine);
+            info!("Model loaded successfully");
+            JNI_TRUE
+        }
+        Err(e) => {
+            error!("Failed to load model: {}", e);
+            let _ = env.throw_new("java/lang/IllegalStateException", e.to_string());
+            JNI_FALSE
+        }
+    }
 }
 
 /// JNI: Unload the currently loaded model
@@ -120,8 +296,7 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_00024Com
 ) {
     info!("Unloading model");
 
-    // TODO: Implement actual model unloading
-    MODEL_LOADED.store(false, Ordering::SeqCst);
+    *ENGINE.lock().unwrap() = None;
 
     info!("Model unloaded");
 }
@@ -139,7 +314,7 @@ pub extern "C" fn Java_com_llamafarm_atmosphere_viewmodel_ChatViewModel_00024Com
     _env: JNIEnv,
     _class: JClass,
 ) -> jboolean {
-    if MODEL_LOADED.load(Ordering::SeqCst) {
+    if ENGINE.lock().unwrap().is_some() {
         JNI_TRUE
     } else {
         JNI_FALSE
@@ -151,11 +326,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_model_state() {
-        assert!(!MODEL_LOADED.load(Ordering::SeqCst));
-        MODEL_LOADED.store(true, Ordering::SeqCst);
-        assert!(MODEL_LOADED.load(Ordering::SeqCst));
-        MODEL_LOADED.store(false, Ordering::SeqCst);
-        assert!(!MODEL_LOADED.load(Ordering::SeqCst));
+    fn test_engine_state_starts_empty() {
+        assert!(ENGINE.lock().unwrap().is_none());
     }
 }